@@ -9,11 +9,11 @@ use near_env::PanicMessage;
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
     env,
-    json_types::{U128, U64},
+    json_types::{Base58CryptoHash, U128, U64},
     serde::{Deserialize, Serialize},
     AccountId, CryptoHash,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 
 /// The error variants thrown by *mg-core*.
 #[derive(Serialize, PanicMessage)]
@@ -260,9 +260,15 @@ pub type TokenId = U64;
 /// thus maximum number allowed is `2^53`.
 pub type Timestamp = u64;
 
-/// Mapping from `AccountId`s to balance (in NEARs).
-/// The balance indicates the amount a Marketplace contract should pay when a Token is being sold.
-pub type Payout = HashMap<AccountId, U128>;
+/// Payout of a token sale, as defined by NEP-199.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct Payout {
+    /// Mapping from `AccountId`s to balance (in NEARs).
+    /// The balance indicates the amount a Marketplace contract should pay when a Token is being sold.
+    pub payout: HashMap<AccountId, U128>,
+}
 
 /// Returns the sha256 of `value`.
 pub fn crypto_hash(value: &String) -> CryptoHash {
@@ -284,13 +290,129 @@ pub struct Collectible {
     pub creator_id: AccountId,
     /// Indicates how many `Token`s can be minted out of this `Collectible`.
     pub current_supply: u16,
+    /// Indicates how many `Token`s this `Collectible` was created with.
+    /// Unlike `current_supply`, it remains unchanged when tokens are minted.
+    pub supply: u16,
     /// The list of `TokenId`s actually minted out of this `Collectible`.
     pub minted_tokens: Vec<TokenId>,
     /// Indicates the royalty as percentage (in NEARs) to be paid to `creator_id`
     /// every time a minted token out of this `Collectible` is reselled.
     pub royalty: Fraction,
+    /// Royalties paid to the collaborators of `creator_id`, on top of `royalty`,
+    /// every time a minted token out of this `Collectible` is reselled.
+    pub royalty_split: Vec<RoyaltyShare>,
+    /// Indicates the price (in NEARs) to be paid when claiming a `Token` out of this `Collectible`.
+    /// A `mint_price` of `0` means tokens can be claimed for free.
+    pub mint_price: U128,
+    /// The root of the Merkle tree of accounts allowed to claim `Token`s out of this `Collectible`.
+    /// When present, tokens can only be claimed with a proof, see `merkle`.
+    pub allowlist_root: Option<Base58CryptoHash>,
+    /// Indicates how many `Token`s each account can claim out of this `Collectible`.
+    /// Tokens transferred or burned still count towards this limit.
+    /// A `max_claims_per_account` of `null` means there is no limit.
+    pub max_claims_per_account: Option<u16>,
+    /// Indicates who can transfer the `Token`s minted out of this `Collectible`.
+    pub transferability: Transferability,
     /// Additional info provided by NEP-177.
     pub metadata: Metadata,
+    /// Indicates whether `metadata` can no longer be updated.
+    pub frozen: bool,
+    /// The commitment of the real metadata of this `Collectible`, until it is revealed.
+    /// While present, `metadata` is just a placeholder, see `reveal`.
+    pub metadata_commitment: Option<Base58CryptoHash>,
+    /// Indicates when `Token`s can no longer be claimed out of this `Collectible`,
+    /// as UNIX epoch datetime in miliseconds, while claims start at `metadata.starts_at`.
+    /// Unlike `metadata.expires_at`, it does not affect the access granted by claimed tokens.
+    pub claims_end_at: Option<Timestamp>,
+}
+
+impl Collectible {
+    /// Returns whether the tokens of this `Collectible` can be transferred
+    /// from `owner_id` to `receiver_id` according to its `transferability`.
+    /// This does not check whether the sender owns or is approved for a given token.
+    pub fn is_transferable(&self, owner_id: &AccountId, receiver_id: &AccountId) -> bool {
+        match self.transferability {
+            Transferability::Transferable => true,
+            Transferability::NonTransferable => false,
+            Transferability::CreatorOnly => {
+                owner_id == &self.creator_id || receiver_id == &self.creator_id
+            }
+        }
+    }
+}
+
+/// Royalty paid to a collaborator of a `Collectible`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug, Clone))]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltyShare {
+    /// The collaborator to be paid.
+    pub account_id: AccountId,
+    /// The royalty as percentage (in NEARs) of the sale price paid to `account_id`.
+    pub share: Fraction,
+}
+
+/// Indicates who can transfer the `Token`s of a `Collectible`.
+/// Only `Transferable` tokens can be approved for markets.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_arch = "wasm"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum Transferability {
+    /// Tokens can be transferred by their owners and approved accounts.
+    Transferable,
+    /// Tokens cannot be transferred at all, *i.e.*, they are soulbound to their owners.
+    NonTransferable,
+    /// Tokens can only be transferred to or from the `creator_id` of the `Collectible`,
+    /// *e.g.*, the creator hands out tokens and owners give them back.
+    CreatorOnly,
+}
+
+impl Display for Transferability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let transferability = match self {
+            Transferability::Transferable => "transferable",
+            Transferability::NonTransferable => "non_transferable",
+            Transferability::CreatorOnly => "creator_only",
+        };
+        write!(f, "{}", transferability)
+    }
+}
+
+/// Indicates whether `Token`s can be claimed out of a `Collectible` at a given time,
+/// according to the `starts_at` and `expires_at` of its `metadata` and its `claims_end_at`.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_arch = "wasm"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum ClaimStatus {
+    /// Claims have not started yet.
+    Upcoming,
+    /// Tokens can be claimed.
+    Live,
+    /// Claims have already ended.
+    Ended,
+}
+
+impl ClaimStatus {
+    /// Returns the `ClaimStatus` of the given `collectible` at `now`, in miliseconds.
+    pub fn at(collectible: &Collectible, now: Timestamp) -> Self {
+        if collectible.metadata.starts_at.map_or(false, |starts_at| now < starts_at) {
+            ClaimStatus::Upcoming
+        } else if Self::ends_at(collectible).map_or(false, |ends_at| now >= ends_at) {
+            ClaimStatus::Ended
+        } else {
+            ClaimStatus::Live
+        }
+    }
+
+    /// Returns when claims of the given `collectible` end, if ever,
+    /// *i.e.*, the earliest of its `claims_end_at` and the `expires_at` of its `metadata`.
+    /// Tokens cannot be claimed once they no longer grant access.
+    pub fn ends_at(collectible: &Collectible) -> Option<Timestamp> {
+        match (collectible.claims_end_at, collectible.metadata.expires_at) {
+            (Some(claims_end_at), Some(expires_at)) => Some(claims_end_at.min(expires_at)),
+            (claims_end_at, expires_at) => claims_end_at.or(expires_at),
+        }
+    }
 }
 
 /// Represents a copy made out of a given collectible.
@@ -304,6 +426,9 @@ pub struct Token {
     pub token_id: TokenId,
     /// The collectible identifier for this `Token`.
     pub gate_id: GateId,
+    /// The edition number of this `Token` within its `Collectible`, starting from `1`.
+    /// It is assigned when minted, following the order in which tokens are claimed.
+    pub edition: u16,
     /// The owner of this token.
     pub owner_id: AccountId,
     /// Represents when this `Token` was minted, in nanoseconds.
@@ -320,7 +445,8 @@ pub struct Token {
 
     #[borsh_skip]
     /// Additional info defined by NEP-177.
-    /// This `metadata` effectively joins fields from its respective `gate_id`.
+    /// This `metadata` effectively joins fields from its respective `gate_id`,
+    /// with the `edition` appended to the `title`, *e.g.*, `My collectible #7`.
     pub metadata: Metadata,
 }
 
@@ -362,8 +488,8 @@ pub struct Metadata {
 }
 
 /// Represents an individual approval by some marketplace account id.
-#[derive(BorshDeserialize, BorshSerialize, Serialize)]
-#[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug, Deserialize))]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
 #[serde(crate = "near_sdk::serde")]
 pub struct TokenApproval {
     /// Id used to avoid selling the same token more than once.
@@ -379,17 +505,88 @@ impl TokenApproval {
     }
 }
 
+/// Roles an account can hold to perform privileged operations in a contract.
+/// The `Owner` implicitly holds every role,
+/// while an `Admin` also acts as `CollectibleMinter` and `Pauser`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_arch = "wasm"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum Role {
+    /// The single account that owns the contract.
+    Owner,
+    /// Manages the contract on behalf of the owner, *e.g.*, grants other roles.
+    Admin,
+    /// Allowed to create collectibles.
+    CollectibleMinter,
+    /// Allowed to pause and unpause the contract.
+    Pauser,
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let role = match self {
+            Role::Owner => "owner",
+            Role::Admin => "admin",
+            Role::CollectibleMinter => "collectible_minter",
+            Role::Pauser => "pauser",
+        };
+        write!(f, "{}", role)
+    }
+}
+
+/// Features that can be paused in case of an emergency.
+/// Each contract only checks the features it implements.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_arch = "wasm"), derive(Debug))]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum Pausable {
+    /// Claiming tokens in the NFT contract.
+    Minting,
+    /// Transferring and approving tokens in the NFT contract.
+    Transfers,
+    /// Buying tokens in the Marketplace contract.
+    Purchases,
+}
+
+impl Display for Pausable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let feature = match self {
+            Pausable::Minting => "minting",
+            Pausable::Transfers => "transfers",
+            Pausable::Purchases => "purchases",
+        };
+        write!(f, "{}", feature)
+    }
+}
+
+/// Parameters of the NFT contract that rule royalties and fees.
+/// They are set when the contract is initialized, and can be later updated by an admin.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftConfig {
+    /// Minimum `royalty` allowed when creating a `Collectible`.
+    pub min_royalty: Fraction,
+    /// Maximum `royalty` allowed when creating a `Collectible`.
+    pub max_royalty: Fraction,
+    /// Percentage fee paid to `mintgate_fee_account_id` when a `Token` is sold.
+    pub mintgate_fee: Fraction,
+    /// Account receiving the `mintgate_fee`.
+    pub mintgate_fee_account_id: AccountId,
+}
+
 /// Non-Fungible Token (NEP-171) v1.0.0
 /// https://nomicon.io/Standards/NonFungibleToken/Core.html
 ///
-/// Payouts is part of an ongoing (yet not settled) NEP spec:
-/// <https://github.com/thor314/NEPs/blob/patch-5/specs/Standards/NonFungibleToken/payouts.md>
+/// Royalties and Payouts (NEP-199) v2.0.0
+/// <https://github.com/near/NEPs/blob/master/neps/nep-0199.md>
 pub mod nep171 {
 
-    use super::{Payout, Token, TokenId};
+    use super::{Payout, Token, TokenApproval, TokenId};
     use near_env::near_ext;
-    use near_sdk::ext_contract;
     use near_sdk::json_types::{ValidAccountId, U128, U64};
+    use near_sdk::{ext_contract, AccountId, Promise, PromiseOrValue};
+    use std::collections::HashMap;
 
     #[near_ext]
     #[ext_contract(nft)]
@@ -402,7 +599,7 @@ pub mod nep171 {
             memo: Option<String>,
         );
 
-        fn nft_payout(&self, token_id: U64, balance: U128) -> Payout;
+        fn nft_payout(&self, token_id: U64, balance: U128, max_len_payout: Option<u32>) -> Payout;
 
         fn nft_transfer_payout(
             &mut self,
@@ -410,11 +607,50 @@ pub mod nep171 {
             token_id: TokenId,
             approval_id: Option<U64>,
             memo: Option<String>,
-            balance: Option<U128>,
-        ) -> Option<Payout>;
+            balance: U128,
+            max_len_payout: Option<u32>,
+        ) -> Payout;
+
+        fn nft_transfer_call(
+            &mut self,
+            receiver_id: ValidAccountId,
+            token_id: TokenId,
+            approval_id: Option<U64>,
+            memo: Option<String>,
+            msg: String,
+        ) -> Promise;
 
         fn nft_token(&self, token_id: TokenId) -> Option<Token>;
     }
+
+    /// Callback made by the NFT contract itself after `nft_on_transfer`
+    /// to return the token to its previous owner when needed.
+    #[near_ext]
+    #[ext_contract(nft_resolver)]
+    pub trait NonFungibleTokenResolver {
+        fn nft_resolve_transfer(
+            &mut self,
+            owner_id: AccountId,
+            receiver_id: AccountId,
+            token_id: TokenId,
+            approvals: Option<HashMap<AccountId, TokenApproval>>,
+        ) -> bool;
+    }
+
+    /// This interface defines the method to be called on the receiver contract
+    /// when a token is transferred using `nft_transfer_call`.
+    /// The receiver returns `true` when the token should be returned to `previous_owner_id`.
+    #[near_ext]
+    #[ext_contract(nft_receiver)]
+    pub trait NonFungibleTokenReceiver {
+        fn nft_on_transfer(
+            &mut self,
+            sender_id: AccountId,
+            previous_owner_id: AccountId,
+            token_id: TokenId,
+            msg: String,
+        ) -> PromiseOrValue<bool>;
+    }
 }
 
 /// Non-Fungible Token Metadata (NEP-177) v1.0.0
@@ -469,6 +705,8 @@ pub mod nep178 {
         Promise,
     };
 
+    #[near_ext]
+    #[ext_contract(nft_approval)]
     pub trait NonFungibleTokenApprovalMgmt {
         fn nft_approve(
             &mut self,
@@ -477,6 +715,13 @@ pub mod nep178 {
             msg: Option<String>,
         ) -> Promise;
 
+        fn nft_is_approved(
+            &self,
+            token_id: TokenId,
+            approved_account_id: ValidAccountId,
+            approval_id: Option<U64>,
+        ) -> bool;
+
         fn nft_revoke(&mut self, token_id: TokenId, account_id: ValidAccountId) -> Promise;
 
         fn nft_revoke_all(&mut self, token_id: TokenId);
@@ -531,6 +776,225 @@ pub mod nep181 {
     }
 }
 
+/// Events Format (NEP-297) v1.0.0
+///
+/// <https://nomicon.io/Standards/EventsFormat.html>
+///
+/// Besides the events defined by the Non-Fungible Token standard (`nep171`),
+/// this module defines MintGate specific events under the `mintgate` standard.
+pub mod nep297 {
+
+    use super::{GateId, NftConfig, Pausable, Role, TokenId};
+    use near_sdk::{
+        env,
+        json_types::{U128, U64},
+        serde::{Deserialize, Serialize},
+        serde_json, AccountId,
+    };
+
+    /// Prefix of every log line carrying an event.
+    pub const EVENT_JSON: &str = "EVENT_JSON:";
+
+    /// Any event emitted by our contracts, tagged by its `standard`.
+    /// The `event` field of each variant is flattened,
+    /// so it adds both `event` and `data` fields.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde", tag = "standard", rename_all = "snake_case")]
+    pub enum Event {
+        Nep171 {
+            version: String,
+            #[serde(flatten)]
+            event: Nep171Event,
+        },
+        Mintgate {
+            version: String,
+            #[serde(flatten)]
+            event: MintGateEvent,
+        },
+    }
+
+    impl Event {
+        /// Logs this event as `EVENT_JSON:{...}`.
+        pub fn emit(&self) {
+            let json = serde_json::to_string(self).unwrap();
+            env::log(format!("{}{}", EVENT_JSON, json).as_bytes());
+        }
+
+        /// Parses an event out of a log line.
+        /// Returns `None` when `log` does not contain a known event.
+        pub fn from_log(log: &str) -> Option<Self> {
+            log.strip_prefix(EVENT_JSON).and_then(|json| serde_json::from_str(json).ok())
+        }
+    }
+
+    /// Events defined by the Non-Fungible Token standard.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde", tag = "event", content = "data", rename_all = "snake_case")]
+    pub enum Nep171Event {
+        NftMint(Vec<NftMintData>),
+        NftTransfer(Vec<NftTransferData>),
+        NftBurn(Vec<NftBurnData>),
+    }
+
+    impl Nep171Event {
+        /// Emits this event under the `nep171` standard.
+        pub fn emit(self) {
+            Event::Nep171 { version: "1.0.0".to_string(), event: self }.emit();
+        }
+    }
+
+    /// Data of the `nft_mint` event.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct NftMintData {
+        /// The account receiving the minted tokens.
+        pub owner_id: AccountId,
+        /// The tokens minted.
+        pub token_ids: Vec<TokenId>,
+        /// Optional message.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memo: Option<String>,
+    }
+
+    /// Data of the `nft_transfer` event.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct NftTransferData {
+        /// The approved account that made the transfer on behalf of the owner, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub authorized_id: Option<AccountId>,
+        /// The owner before the transfer.
+        pub old_owner_id: AccountId,
+        /// The owner after the transfer.
+        pub new_owner_id: AccountId,
+        /// The tokens transferred.
+        pub token_ids: Vec<TokenId>,
+        /// Optional message.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memo: Option<String>,
+    }
+
+    /// Data of the `nft_burn` event.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct NftBurnData {
+        /// The approved account that burned the tokens on behalf of the owner, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub authorized_id: Option<AccountId>,
+        /// The owner of the burned tokens.
+        pub owner_id: AccountId,
+        /// The tokens burned.
+        pub token_ids: Vec<TokenId>,
+        /// Optional message.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memo: Option<String>,
+    }
+
+    /// Events specific to MintGate.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde", tag = "event", content = "data", rename_all = "snake_case")]
+    pub enum MintGateEvent {
+        CollectibleCreate(Vec<CollectibleCreateData>),
+        CollectibleDelete(Vec<CollectibleDeleteData>),
+        NftApprove(Vec<NftApproveData>),
+        NftRevoke(Vec<NftRevokeData>),
+        RoleGrant(Vec<RoleData>),
+        RoleRevoke(Vec<RoleData>),
+        ConfigUpdate(Vec<NftConfig>),
+        Pause(Vec<PauseData>),
+        Unpause(Vec<PauseData>),
+    }
+
+    impl MintGateEvent {
+        /// Emits this event under the `mintgate` standard.
+        pub fn emit(self) {
+            Event::Mintgate { version: "1.0.0".to_string(), event: self }.emit();
+        }
+    }
+
+    /// Data of the `collectible_create` event.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct CollectibleCreateData {
+        /// The creator of the collectible.
+        pub creator_id: AccountId,
+        /// The identifier of the collectible.
+        pub gate_id: GateId,
+        /// How many tokens can be minted out of the collectible.
+        pub supply: u16,
+    }
+
+    /// Data of the `collectible_delete` event.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct CollectibleDeleteData {
+        /// The creator of the collectible.
+        pub creator_id: AccountId,
+        /// The identifier of the collectible.
+        pub gate_id: GateId,
+    }
+
+    /// Data of the `nft_approve` event.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct NftApproveData {
+        /// The owner of the approved token.
+        pub owner_id: AccountId,
+        /// The token approved.
+        pub token_id: TokenId,
+        /// The account approved to transfer the token, usually a marketplace.
+        pub account_id: AccountId,
+        /// The approval ID assigned to `account_id`.
+        pub approval_id: U64,
+        /// Minimum price the token should be sold for.
+        pub min_price: U128,
+    }
+
+    /// Data of the `nft_revoke` event.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct NftRevokeData {
+        /// The owner of the token.
+        pub owner_id: AccountId,
+        /// The token whose approval was revoked.
+        pub token_id: TokenId,
+        /// The account no longer approved to transfer the token.
+        pub account_id: AccountId,
+    }
+
+    /// Data of both `role_grant` and `role_revoke` events.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RoleData {
+        /// The account granted or revoked.
+        pub account_id: AccountId,
+        /// The role granted or revoked.
+        pub role: Role,
+    }
+
+    /// Data of both `pause` and `unpause` events.
+    #[derive(Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct PauseData {
+        /// The account that paused or unpaused `feature`.
+        pub account_id: AccountId,
+        /// The feature paused or unpaused.
+        pub feature: Pausable,
+    }
+}
+
 /// In our implementation of the standard,
 /// The `nft_approve` method must conform with the following:
 /// - The `msg` argument must contain a value, *i.e.*, cannot be `None`.
@@ -558,3 +1022,502 @@ pub struct MarketApproveMsg {
     /// Represents the `creator_id` of the collectible of the token being approved if present.
     pub creator_id: Option<AccountId>,
 }
+
+/// Merkle trees used to restrict claims of a `Collectible` to an allowlist of accounts.
+///
+/// Each leaf is the hash of an allowed `AccountId`,
+/// and each inner node is the hash of its two children sorted,
+/// so proofs do not need to indicate whether a sibling is on the left or on the right.
+/// When a level has an odd number of nodes, the last one is promoted to the next level.
+/// Leaves and inner nodes are hashed with a different prefix,
+/// so an inner node cannot be passed off as a leaf.
+pub mod merkle {
+
+    use near_sdk::{env, AccountId, CryptoHash};
+
+    /// Prefix of the preimage of each leaf.
+    const LEAF_PREFIX: u8 = 0;
+
+    /// Prefix of the preimage of each inner node.
+    const NODE_PREFIX: u8 = 1;
+
+    /// Returns the leaf of `account_id` using `sha256`.
+    fn leaf_with<F>(account_id: &AccountId, sha256: F) -> CryptoHash
+    where
+        F: Fn(&[u8]) -> CryptoHash,
+    {
+        let mut preimage = vec![LEAF_PREFIX];
+        preimage.extend_from_slice(account_id.as_bytes());
+        sha256(&preimage)
+    }
+
+    /// Returns the parent node of `a` and `b` using `sha256`.
+    fn node_with<F>(a: &CryptoHash, b: &CryptoHash, sha256: F) -> CryptoHash
+    where
+        F: Fn(&[u8]) -> CryptoHash,
+    {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        let mut preimage = vec![NODE_PREFIX];
+        preimage.extend_from_slice(left);
+        preimage.extend_from_slice(right);
+        sha256(&preimage)
+    }
+
+    fn env_sha256(value: &[u8]) -> CryptoHash {
+        let mut hash = CryptoHash::default();
+        hash.copy_from_slice(&env::sha256(value));
+        hash
+    }
+
+    /// Returns whether `proof` shows that `account_id` belongs to the tree with the given `root`.
+    /// Hashes are computed with `env::sha256`, so it is meant to be used within contracts.
+    pub fn verify_proof(root: &CryptoHash, account_id: &AccountId, proof: &[CryptoHash]) -> bool {
+        let hash = proof.iter().fold(leaf_with(account_id, env_sha256), |hash, sibling| {
+            node_with(&hash, sibling, env_sha256)
+        });
+        &hash == root
+    }
+
+    /// Builds Merkle trees off-chain, *e.g.*, to get the root to set on a `Collectible`,
+    /// and the proof each allowed account needs to claim a token.
+    #[cfg(not(target_arch = "wasm"))]
+    pub struct MerkleTree {
+        /// The leaves of the tree.
+        accounts: Vec<AccountId>,
+        /// Every level of the tree, from the leaves up to the root.
+        levels: Vec<Vec<CryptoHash>>,
+    }
+
+    #[cfg(not(target_arch = "wasm"))]
+    impl MerkleTree {
+        /// Builds the tree whose leaves are the given `accounts`.
+        /// Panics if `accounts` is empty.
+        pub fn new(accounts: &[AccountId]) -> Self {
+            assert!(!accounts.is_empty(), "A Merkle tree needs at least one account");
+
+            let mut levels: Vec<Vec<CryptoHash>> =
+                vec![accounts.iter().map(|account_id| leaf_with(account_id, sha256)).collect()];
+            while levels.last().unwrap().len() > 1 {
+                let level = levels
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|pair| match pair {
+                        [a, b] => node_with(a, b, sha256),
+                        [a] => *a,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+                levels.push(level);
+            }
+
+            Self { accounts: accounts.to_vec(), levels }
+        }
+
+        /// Returns the root of this tree.
+        pub fn root(&self) -> CryptoHash {
+            self.levels.last().unwrap()[0]
+        }
+
+        /// Returns the proof of `account_id`.
+        /// Or `None` if `account_id` is not a leaf of this tree.
+        pub fn proof(&self, account_id: &AccountId) -> Option<Vec<CryptoHash>> {
+            let mut index = self.accounts.iter().position(|leaf| leaf == account_id)?;
+            let mut proof = Vec::new();
+            for level in &self.levels[..self.levels.len() - 1] {
+                if let Some(sibling) = level.get(index ^ 1) {
+                    proof.push(*sibling);
+                }
+                index /= 2;
+            }
+            Some(proof)
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm"))]
+    fn sha256(value: &[u8]) -> CryptoHash {
+        use sha2::{Digest, Sha256};
+
+        let mut hash = CryptoHash::default();
+        hash.copy_from_slice(&Sha256::digest(value));
+        hash
+    }
+}
+
+/// One-time claim codes, to share a drop as secret links or QR codes.
+///
+/// The NFT contract stores only the `crypto_hash` of each code,
+/// so codes cannot be read from the contract state.
+/// This module generates codes off-chain, along with the hashes to add to a `Collectible`.
+#[cfg(not(target_arch = "wasm"))]
+pub mod claim_code {
+
+    use near_sdk::{bs58, CryptoHash};
+    use sha2::{Digest, Sha256};
+
+    /// Length in bytes of each code, before encoding it in base58.
+    const CODE_LEN: usize = 16;
+
+    /// Generates `count` claim codes along with their hashes, starting `from` the given index.
+    /// Each code is derived from the secret `seed` and its index,
+    /// so codes can be generated again from the same `seed`,
+    /// and further batches can be generated starting from the next index.
+    pub fn generate(seed: &[u8], from: u32, count: u32) -> Vec<(String, CryptoHash)> {
+        (from..from + count)
+            .map(|index| {
+                let mut preimage = seed.to_vec();
+                preimage.extend_from_slice(&index.to_le_bytes());
+                let code = bs58::encode(&Sha256::digest(&preimage)[..CODE_LEN]).into_string();
+                let hash = hash(&code);
+                (code, hash)
+            })
+            .collect()
+    }
+
+    /// Returns the hash of `code`, as `crypto_hash` does within contracts.
+    pub fn hash(code: &str) -> CryptoHash {
+        let mut hash = CryptoHash::default();
+        hash.copy_from_slice(&Sha256::digest(code.as_bytes()));
+        hash
+    }
+}
+
+/// Vouchers signed off-chain by creators, so buyers can mint tokens lazily, *i.e.*, on redemption.
+///
+/// A `Voucher` is signed with the ed25519 key its creator has registered in the NFT contract.
+/// The signed message is the Borsh serialization of the NFT contract account ID followed by the voucher,
+/// so a voucher issued for a contract cannot be redeemed in another one.
+pub mod voucher {
+
+    use crate::{fraction::Fraction, gate::ValidGateId, Timestamp};
+    use ed25519_dalek::{PublicKey, Signature, Verifier};
+    use near_sdk::{
+        borsh::{self, BorshDeserialize, BorshSerialize},
+        json_types::{U128, U64},
+        serde::{Deserialize, Serialize},
+        AccountId,
+    };
+    use std::convert::TryFrom;
+
+    #[cfg(not(target_arch = "wasm"))]
+    use near_sdk::json_types::{Base58PublicKey, Base64VecU8};
+
+    /// The curve prefix of ed25519 keys in a `Base58PublicKey`.
+    pub const ED25519_CURVE: u8 = 0;
+
+    /// Represents the terms, set by the creator of a `Collectible`, to mint one of its tokens.
+    #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug, Clone))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct Voucher {
+        /// The `Collectible` to mint the token from.
+        pub gate_id: ValidGateId,
+        /// The `Collectible` to create when `gate_id` does not exist yet,
+        /// so creators need not create their collectibles before issuing vouchers.
+        /// It is ignored once the collectible exists.
+        pub collectible: Option<VoucherCollectible>,
+        /// The only account allowed to redeem this voucher.
+        /// When `None`, any account can redeem it.
+        pub receiver_id: Option<AccountId>,
+        /// The price to pay when redeeming this voucher.
+        /// It replaces the `mint_price` of the collectible.
+        pub price: U128,
+        /// When this voucher expires, as a UNIX epoch datetime in milliseconds.
+        pub expires_at: Timestamp,
+        /// Chosen by the creator, each nonce can be redeemed only once across all of its vouchers.
+        pub nonce: U64,
+    }
+
+    /// Represents the `Collectible` created by the first voucher redeemed for its `GateId`.
+    /// It is created with no `mint_price`, `royalty_split` nor claim window.
+    #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug, Clone))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct VoucherCollectible {
+        /// The creator of the collectible, whose voucher key signs the voucher.
+        pub creator_id: AccountId,
+        /// The title of the collectible.
+        pub title: String,
+        /// The description of the collectible.
+        pub description: String,
+        /// How many tokens can be minted out of the collectible.
+        pub supply: u16,
+        /// The royalty paid to `creator_id` every time a token is resold.
+        pub royalty: Fraction,
+        /// The media of the collectible, as in its `Metadata`.
+        pub media: Option<String>,
+        /// The hash of `media`, as in its `Metadata`.
+        pub media_hash: Option<String>,
+        /// The off-chain reference of the collectible, as in its `Metadata`.
+        pub reference: Option<String>,
+        /// The hash of `reference`, as in its `Metadata`.
+        pub reference_hash: Option<String>,
+    }
+
+    impl Voucher {
+        /// Returns the message to be signed for this voucher to be redeemed in `contract_id`.
+        pub fn message(&self, contract_id: &AccountId) -> Vec<u8> {
+            let mut message = contract_id.try_to_vec().unwrap();
+            message.extend(self.try_to_vec().unwrap());
+            message
+        }
+
+        /// Returns whether `signature` is a valid signature of this voucher by the ed25519 `public_key`.
+        /// Malformed keys and signatures are not valid.
+        pub fn verify(&self, contract_id: &AccountId, public_key: &[u8], signature: &[u8]) -> bool {
+            match (PublicKey::from_bytes(public_key), Signature::try_from(signature)) {
+                (Ok(public_key), Ok(signature)) => {
+                    public_key.verify(&self.message(contract_id), &signature).is_ok()
+                }
+                _ => false,
+            }
+        }
+    }
+
+    /// Signs vouchers off-chain, *e.g.*, to issue them from the creator's tooling.
+    #[cfg(not(target_arch = "wasm"))]
+    pub struct VoucherSigner {
+        keypair: ed25519_dalek::Keypair,
+    }
+
+    #[cfg(not(target_arch = "wasm"))]
+    impl VoucherSigner {
+        /// Builds the signer whose ed25519 secret key is `seed`.
+        pub fn from_seed(seed: &[u8; 32]) -> Self {
+            let secret = ed25519_dalek::SecretKey::from_bytes(seed).unwrap();
+            let public = PublicKey::from(&secret);
+            Self { keypair: ed25519_dalek::Keypair { secret, public } }
+        }
+
+        /// Returns the public key to be registered in the NFT contract.
+        pub fn public_key(&self) -> Base58PublicKey {
+            let mut public_key = vec![ED25519_CURVE];
+            public_key.extend_from_slice(self.keypair.public.as_bytes());
+            Base58PublicKey(public_key)
+        }
+
+        /// Returns the signature of `voucher` to be redeemed in `contract_id`.
+        pub fn sign(&self, contract_id: &AccountId, voucher: &Voucher) -> Base64VecU8 {
+            use ed25519_dalek::Signer;
+
+            Base64VecU8(self.keypair.sign(&voucher.message(contract_id)).to_bytes().to_vec())
+        }
+    }
+}
+
+/// Linkdrops let accounts without any NEAR claim a `Token`.
+///
+/// The creator funds a linkdrop by giving away the secret key of a new keypair,
+/// whose public key becomes a function-call access key of the NFT contract,
+/// limited to the methods claiming the linkdrop.
+/// The claimer then signs with that key to get the token,
+/// either into an existing account or into a new account created on the fly.
+pub mod linkdrop {
+
+    use crate::TokenId;
+    use near_env::near_ext;
+    use near_sdk::{
+        borsh::{self, BorshDeserialize, BorshSerialize},
+        ext_contract,
+        json_types::{Base58PublicKey, U128},
+        serde::{Deserialize, Serialize},
+        AccountId, Promise,
+    };
+
+    /// Represents a `Token` held by the NFT contract until its linkdrop is claimed.
+    #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug, Clone))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct Linkdrop {
+        /// The token to be given to the claimer.
+        pub token_id: TokenId,
+        /// The account that funded this linkdrop.
+        pub funder_id: AccountId,
+        /// The balance given to the claimer along with the token.
+        pub amount: U128,
+    }
+
+    /// The linkdrop contract deployed on top-level accounts, *e.g.*, `testnet` or `near`.
+    /// It creates sub-accounts of its top-level account on behalf of other contracts.
+    #[near_ext]
+    #[ext_contract(registrar)]
+    pub trait LinkdropRegistrar {
+        fn create_account(
+            &mut self,
+            new_account_id: AccountId,
+            new_public_key: Base58PublicKey,
+        ) -> Promise;
+    }
+}
+
+/// Raffles give every entrant the same chance to claim a `Token` of an oversubscribed `Collectible`.
+///
+/// Accounts enter the raffle attaching a refundable deposit until the raffle ends.
+/// Then anyone can draw its winners, up to the `current_supply` of the collectible,
+/// using the random seed of the block.
+/// Only winners can claim, and losers get their deposit back.
+pub mod raffle {
+
+    use crate::Timestamp;
+    use near_sdk::{
+        borsh::{self, BorshDeserialize, BorshSerialize},
+        env,
+        json_types::U128,
+        serde::{Deserialize, Serialize},
+    };
+    use std::convert::TryInto;
+
+    /// Represents the raffle of a `Collectible`.
+    #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug, Clone))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct Raffle {
+        /// Accounts can enter the raffle until this time, as a UNIX epoch datetime in milliseconds.
+        /// Its winners can be drawn afterwards.
+        pub ends_at: Timestamp,
+        /// The deposit to attach when entering the raffle.
+        /// It is returned to losers, and to winners when they claim their token.
+        pub entry_deposit: U128,
+        /// How many accounts have entered the raffle.
+        pub entries: u64,
+        /// How many winners are to be drawn, set when the draw starts.
+        pub winners: Option<u16>,
+        /// How many winners have been drawn so far.
+        pub drawn: u16,
+    }
+
+    impl Raffle {
+        /// Returns whether all the winners of this raffle have been drawn.
+        pub fn is_drawn(&self) -> bool {
+            self.winners == Some(self.drawn)
+        }
+    }
+
+    /// Represents the entry of an account in a raffle.
+    #[derive(
+        BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq,
+    )]
+    #[cfg_attr(not(target_arch = "wasm"), derive(Debug))]
+    #[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+    pub enum RaffleEntry {
+        /// The account has entered the raffle, but it has not been drawn as a winner.
+        Entered,
+        /// The account has been drawn as a winner, so it can claim a token.
+        Won,
+    }
+
+    /// Returns a random index in `0..len` for the `round`-th pick of a draw,
+    /// derived from the given random `seed`.
+    /// Hashes falling in the last, incomplete range of `len` values are rehashed,
+    /// so every index is equally likely to be picked.
+    pub fn pick(seed: &[u8], round: u64, len: u64) -> u64 {
+        let limit = (1u128 << 64) / len as u128 * len as u128;
+        let mut preimage = seed.to_vec();
+        preimage.extend_from_slice(&round.to_le_bytes());
+        let mut hash = env::sha256(&preimage);
+        loop {
+            let value = u64::from_le_bytes(hash[..8].try_into().unwrap());
+            if (value as u128) < limit {
+                return value % len;
+            }
+            hash = env::sha256(&hash);
+        }
+    }
+}
+
+/// Commit-reveal of the `Metadata` of blind `Collectible`s, whose art is revealed after the drop.
+///
+/// Until then, the collectible shows some placeholder metadata,
+/// and it only stores the commitment of the real one,
+/// *i.e.*, the sha256 of the Borsh serialization of its `HiddenMetadata` followed by a secret salt.
+/// The salt keeps the real metadata from being guessed out of its commitment.
+pub mod reveal {
+
+    use near_sdk::{
+        borsh::{self, BorshDeserialize, BorshSerialize},
+        env,
+        serde::{Deserialize, Serialize},
+        CryptoHash,
+    };
+
+    /// The `Metadata` fields of a `Collectible` hidden until it is revealed,
+    /// *i.e.*, the ones set by `update_collectible_metadata`.
+    #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+    #[cfg_attr(not(target_arch = "wasm"), derive(PartialEq, Debug, Clone))]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct HiddenMetadata {
+        pub title: String,
+        pub description: String,
+        pub media: Option<String>,
+        pub media_hash: Option<String>,
+        pub reference: Option<String>,
+        pub reference_hash: Option<String>,
+        pub extra: Option<String>,
+    }
+
+    /// Returns the commitment of `metadata` with the given `salt` using `sha256`.
+    fn commitment_with<F>(metadata: &HiddenMetadata, salt: &str, sha256: F) -> CryptoHash
+    where
+        F: Fn(&[u8]) -> CryptoHash,
+    {
+        let mut preimage = metadata.try_to_vec().unwrap();
+        preimage.extend_from_slice(salt.as_bytes());
+        sha256(&preimage)
+    }
+
+    fn env_sha256(value: &[u8]) -> CryptoHash {
+        let mut hash = CryptoHash::default();
+        hash.copy_from_slice(&env::sha256(value));
+        hash
+    }
+
+    /// Returns whether `metadata` along with `salt` is the preimage of `commitment`.
+    /// Hashes are computed with `env::sha256`, so it is meant to be used within contracts.
+    pub fn verify(commitment: &CryptoHash, metadata: &HiddenMetadata, salt: &str) -> bool {
+        &commitment_with(metadata, salt, env_sha256) == commitment
+    }
+
+    /// Returns the commitment of `metadata` with the given secret `salt`,
+    /// to be set off-chain on a `Collectible` before its drop.
+    #[cfg(not(target_arch = "wasm"))]
+    pub fn commitment(metadata: &HiddenMetadata, salt: &str) -> CryptoHash {
+        commitment_with(metadata, salt, |value| {
+            use sha2::{Digest, Sha256};
+
+            let mut hash = CryptoHash::default();
+            hash.copy_from_slice(&Sha256::digest(value));
+            hash
+        })
+    }
+}
+
+/// Versioning of the contract state layout.
+/// Contracts are plain Borsh structs, so any change in their layout breaks already deployed contracts.
+/// Along with its state, each contract stores the version of its layout,
+/// so a newly deployed contract knows which layout it has to `migrate` from.
+pub mod state {
+
+    use near_sdk::{
+        borsh::{BorshDeserialize, BorshSerialize},
+        env,
+    };
+
+    /// Represents the version of a contract state layout.
+    pub type StateVersion = u16;
+
+    /// The storage key holding the `StateVersion`, stored apart from the contract state.
+    const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+
+    /// Returns the `StateVersion` stored in this contract.
+    /// Contracts deployed before versioning was introduced do not store any version,
+    /// thus their state layout is considered to be version `1`.
+    pub fn read_state_version() -> StateVersion {
+        env::storage_read(STATE_VERSION_KEY)
+            .map_or(1, |bytes| StateVersion::try_from_slice(&bytes).unwrap())
+    }
+
+    /// Stores `version` as the `StateVersion` of this contract.
+    pub fn write_state_version(version: StateVersion) {
+        env::storage_write(STATE_VERSION_KEY, &version.try_to_vec().unwrap());
+    }
+}
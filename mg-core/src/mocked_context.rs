@@ -13,6 +13,7 @@ macro_rules! mock_context {
         pub struct MockedContext<T> {
             contract: T,
             pub context: ::near_sdk::VMContext,
+            promise_results: Vec<::near_sdk::PromiseResult>,
         }
 
         impl<T> ::std::ops::Deref for MockedContext<T> {
@@ -37,7 +38,7 @@ macro_rules! mock_context {
             {
                 let context = ::near_sdk::test_utils::VMContextBuilder::new().build();
                 testing_env!(context.clone());
-                Self { contract: init(), context }
+                Self { contract: init(), context, promise_results: Vec::new() }
             }
 
             /// Runs the given `action` as account `account_id`.
@@ -57,6 +58,15 @@ macro_rules! mock_context {
                 self
             }
 
+            /// Sets the results of the promises seen by callbacks, *i.e.*, `env::promise_result`.
+            pub fn promise_results(
+                &mut self,
+                promise_results: Vec<::near_sdk::PromiseResult>,
+            ) -> &mut Self {
+                self.promise_results = promise_results;
+                self
+            }
+
             pub fn pred_id(&self) -> ValidAccountId {
                 self.context.predecessor_account_id.clone().try_into().unwrap()
             }
@@ -69,7 +79,25 @@ macro_rules! mock_context {
                         .to_ne_bytes()
                         .to_vec();
                 self.context.block_timestamp += 1;
-                testing_env!(self.context.clone());
+                use ::near_sdk::PromiseResult;
+                let promise_results = self
+                    .promise_results
+                    .iter()
+                    .map(|result| match result {
+                        PromiseResult::NotReady => PromiseResult::NotReady,
+                        PromiseResult::Successful(value) => {
+                            PromiseResult::Successful(value.clone())
+                        }
+                        PromiseResult::Failed => PromiseResult::Failed,
+                    })
+                    .collect();
+                testing_env!(
+                    self.context.clone(),
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    promise_results
+                );
             }
         }
     };
@@ -0,0 +1,29 @@
+use mg_core::{claim_code, crypto_hash};
+use near_sdk::{test_utils::VMContextBuilder, testing_env, MockedBlockchain};
+use std::collections::HashSet;
+
+#[test]
+fn generated_hashes_match_contract_hashes() {
+    testing_env!(VMContextBuilder::new().build());
+
+    for (code, hash) in claim_code::generate(b"secret", 0, 10) {
+        assert_eq!(hash, crypto_hash(&code));
+        assert_eq!(hash, claim_code::hash(&code));
+    }
+}
+
+#[test]
+fn generated_codes_are_unique() {
+    let codes = claim_code::generate(b"secret", 0, 100);
+    let unique = codes.iter().map(|(code, _)| code).collect::<HashSet<_>>();
+    assert_eq!(unique.len(), 100);
+}
+
+#[test]
+fn codes_are_generated_again_from_the_same_seed() {
+    let codes = claim_code::generate(b"secret", 0, 10);
+
+    assert_eq!(claim_code::generate(b"secret", 0, 10), codes);
+    assert_eq!(claim_code::generate(b"secret", 4, 6), codes[4..]);
+    assert_ne!(claim_code::generate(b"another secret", 0, 10), codes);
+}
@@ -0,0 +1,70 @@
+use mg_core::nep297::{
+    CollectibleCreateData, Event, MintGateEvent, Nep171Event, NftMintData, NftTransferData,
+};
+use near_sdk::{json_types::U64, serde_json};
+
+#[test]
+fn serialize_nft_mint() {
+    let event = Event::Nep171 {
+        version: "1.0.0".to_string(),
+        event: Nep171Event::NftMint(vec![NftMintData {
+            owner_id: "alice".to_string(),
+            token_ids: vec![U64(0), U64(1)],
+            memo: None,
+        }]),
+    };
+    assert_eq!(
+        serde_json::to_string(&event).unwrap(),
+        r#"{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"alice","token_ids":["0","1"]}]}"#
+    );
+}
+
+#[test]
+fn serialize_nft_transfer() {
+    let event = Event::Nep171 {
+        version: "1.0.0".to_string(),
+        event: Nep171Event::NftTransfer(vec![NftTransferData {
+            authorized_id: Some("market".to_string()),
+            old_owner_id: "alice".to_string(),
+            new_owner_id: "bob".to_string(),
+            token_ids: vec![U64(5)],
+            memo: Some("gift".to_string()),
+        }]),
+    };
+    assert_eq!(
+        serde_json::to_string(&event).unwrap(),
+        r#"{"standard":"nep171","version":"1.0.0","event":"nft_transfer","data":[{"authorized_id":"market","old_owner_id":"alice","new_owner_id":"bob","token_ids":["5"],"memo":"gift"}]}"#
+    );
+}
+
+#[test]
+fn parse_event_from_log() {
+    let log = r#"EVENT_JSON:{"standard":"mintgate","version":"1.0.0","event":"collectible_create","data":[{"creator_id":"alice","gate_id":"gate","supply":10}]}"#;
+    assert_eq!(
+        Event::from_log(log),
+        Some(Event::Mintgate {
+            version: "1.0.0".to_string(),
+            event: MintGateEvent::CollectibleCreate(vec![CollectibleCreateData {
+                creator_id: "alice".to_string(),
+                gate_id: "gate".to_string(),
+                supply: 10,
+            }])
+        })
+    );
+
+    let log = r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"bob","token_ids":["7"]}]}"#;
+    assert_eq!(
+        Event::from_log(log),
+        Some(Event::Nep171 {
+            version: "1.0.0".to_string(),
+            event: Nep171Event::NftMint(vec![NftMintData {
+                owner_id: "bob".to_string(),
+                token_ids: vec![U64(7)],
+                memo: None,
+            }])
+        })
+    );
+
+    assert_eq!(Event::from_log("claim_token(pred=alice)"), None);
+    assert_eq!(Event::from_log(r#"EVENT_JSON:{"standard":"nep141"}"#), None);
+}
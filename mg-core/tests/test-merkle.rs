@@ -0,0 +1,75 @@
+use mg_core::merkle::{verify_proof, MerkleTree};
+use near_sdk::{test_utils::VMContextBuilder, testing_env, AccountId, MockedBlockchain};
+
+fn accounts(n: usize) -> Vec<AccountId> {
+    (0..n).map(|i| format!("account-{}", i)).collect()
+}
+
+#[test]
+fn verify_proof_of_every_account() {
+    testing_env!(VMContextBuilder::new().build());
+
+    for n in 1..=9 {
+        let accounts = accounts(n);
+        let tree = MerkleTree::new(&accounts);
+        for account_id in &accounts {
+            let proof = tree.proof(account_id).unwrap();
+            assert!(verify_proof(&tree.root(), account_id, &proof));
+        }
+    }
+}
+
+#[test]
+fn single_account_tree_has_empty_proof() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let tree = MerkleTree::new(&accounts(1));
+    assert_eq!(tree.proof(&"account-0".to_string()), Some(Vec::new()));
+    assert!(verify_proof(&tree.root(), &"account-0".to_string(), &[]));
+}
+
+#[test]
+fn no_proof_for_account_not_in_tree() {
+    let tree = MerkleTree::new(&accounts(4));
+    assert_eq!(tree.proof(&"mallory".to_string()), None);
+}
+
+#[test]
+fn invalid_proof_is_rejected() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let accounts = accounts(5);
+    let tree = MerkleTree::new(&accounts);
+    let proof = tree.proof(&accounts[2]).unwrap();
+
+    assert!(!verify_proof(&tree.root(), &accounts[2], &proof[1..]));
+    assert!(!verify_proof(&tree.root(), &accounts[2], &[]));
+
+    let mut tampered = proof.clone();
+    tampered[0][0] ^= 1;
+    assert!(!verify_proof(&tree.root(), &accounts[2], &tampered));
+
+    let other_tree = MerkleTree::new(&accounts[1..]);
+    assert!(!verify_proof(&other_tree.root(), &accounts[2], &proof));
+}
+
+#[test]
+fn reused_proof_of_another_account_is_rejected() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let accounts = accounts(4);
+    let tree = MerkleTree::new(&accounts);
+    let proof = tree.proof(&accounts[0]).unwrap();
+
+    assert!(verify_proof(&tree.root(), &accounts[0], &proof));
+    for account_id in &accounts[1..] {
+        assert!(!verify_proof(&tree.root(), account_id, &proof));
+    }
+    assert!(!verify_proof(&tree.root(), &"mallory".to_string(), &proof));
+}
+
+#[test]
+#[should_panic(expected = "A Merkle tree needs at least one account")]
+fn empty_tree_should_panic() {
+    MerkleTree::new(&[]);
+}
@@ -0,0 +1,26 @@
+use mg_core::raffle;
+use near_sdk::{test_utils::VMContextBuilder, testing_env, MockedBlockchain};
+
+#[test]
+fn pick_is_within_range() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let seed = [7; 32];
+    for len in &[1, 2, 3, 10, 1 << 32, (1 << 63) + 1, u64::MAX] {
+        for round in 0..16 {
+            assert!(raffle::pick(&seed, round, *len) < *len);
+        }
+    }
+}
+
+#[test]
+fn pick_is_deterministic_by_seed_and_round() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let seed = [7; 32];
+    assert_eq!(raffle::pick(&seed, 3, 1000), raffle::pick(&seed, 3, 1000));
+    assert_eq!(raffle::pick(&seed, 3, 1), 0);
+
+    let picks = (0..16).map(|round| raffle::pick(&seed, round, 1000)).collect::<Vec<_>>();
+    assert!(picks.iter().any(|pick| *pick != picks[0]));
+}
@@ -0,0 +1,35 @@
+use mg_core::reveal::{self, HiddenMetadata};
+use near_sdk::{test_utils::VMContextBuilder, testing_env, MockedBlockchain};
+
+fn hidden_metadata() -> HiddenMetadata {
+    HiddenMetadata {
+        title: "Title".to_string(),
+        description: "Description".to_string(),
+        media: Some("media".to_string()),
+        media_hash: None,
+        reference: Some("ref".to_string()),
+        reference_hash: None,
+        extra: None,
+    }
+}
+
+#[test]
+fn commitment_is_verified_within_contracts() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let commitment = reveal::commitment(&hidden_metadata(), "salt");
+    assert!(reveal::verify(&commitment, &hidden_metadata(), "salt"));
+}
+
+#[test]
+fn commitment_depends_on_metadata_and_salt() {
+    testing_env!(VMContextBuilder::new().build());
+
+    let commitment = reveal::commitment(&hidden_metadata(), "salt");
+    assert!(!reveal::verify(&commitment, &hidden_metadata(), "pepper"));
+
+    let mut metadata = hidden_metadata();
+    metadata.extra = Some("{}".to_string());
+    assert!(!reveal::verify(&commitment, &metadata, "salt"));
+    assert_ne!(reveal::commitment(&metadata, "salt"), commitment);
+}
@@ -0,0 +1,105 @@
+use mg_core::voucher::{Voucher, VoucherCollectible, VoucherSigner};
+use near_sdk::{
+    json_types::{U128, U64},
+    serde_json::{self, json},
+};
+use std::convert::TryInto;
+
+fn voucher() -> Voucher {
+    Voucher {
+        gate_id: "GPZkspuVGaZxwWoP6bJoWU".try_into().unwrap(),
+        collectible: None,
+        receiver_id: Some("bob".to_string()),
+        price: U128(1000),
+        expires_at: 1_000_000,
+        nonce: U64(1),
+    }
+}
+
+fn verify(signer: &VoucherSigner, contract_id: &str, voucher: &Voucher, signature: &[u8]) -> bool {
+    voucher.verify(&contract_id.to_string(), &signer.public_key().0[1..], signature)
+}
+
+#[test]
+fn verify_signed_voucher() {
+    let signer = VoucherSigner::from_seed(&[1; 32]);
+    let signature = signer.sign(&"nft".to_string(), &voucher());
+
+    assert!(verify(&signer, "nft", &voucher(), &signature.0));
+}
+
+#[test]
+fn public_key_is_prefixed_by_ed25519_curve() {
+    let public_key = VoucherSigner::from_seed(&[1; 32]).public_key();
+
+    assert_eq!(public_key.0.len(), 33);
+    assert_eq!(public_key.0[0], 0);
+}
+
+#[test]
+fn voucher_signed_for_another_contract_is_rejected() {
+    let signer = VoucherSigner::from_seed(&[1; 32]);
+    let signature = signer.sign(&"nft".to_string(), &voucher());
+
+    assert!(!verify(&signer, "other-nft", &voucher(), &signature.0));
+}
+
+#[test]
+fn voucher_signed_by_another_key_is_rejected() {
+    let signer = VoucherSigner::from_seed(&[1; 32]);
+    let signature = VoucherSigner::from_seed(&[2; 32]).sign(&"nft".to_string(), &voucher());
+
+    assert!(!verify(&signer, "nft", &voucher(), &signature.0));
+}
+
+#[test]
+fn tampered_voucher_is_rejected() {
+    let signer = VoucherSigner::from_seed(&[1; 32]);
+    let signature = signer.sign(&"nft".to_string(), &voucher());
+
+    let tampered = vec![
+        Voucher { gate_id: "HPZkspuVGaZxwWoP6bJoWU".try_into().unwrap(), ..voucher() },
+        Voucher {
+            collectible: Some(VoucherCollectible {
+                creator_id: "alice".to_string(),
+                title: "Title".to_string(),
+                description: "Description".to_string(),
+                supply: 10,
+                royalty: "5/100".parse().unwrap(),
+                media: None,
+                media_hash: None,
+                reference: None,
+                reference_hash: None,
+            }),
+            ..voucher()
+        },
+        Voucher { receiver_id: None, ..voucher() },
+        Voucher { price: U128(0), ..voucher() },
+        Voucher { expires_at: 2_000_000, ..voucher() },
+        Voucher { nonce: U64(2), ..voucher() },
+    ];
+    for voucher in &tampered {
+        assert!(!verify(&signer, "nft", voucher, &signature.0));
+    }
+}
+
+#[test]
+fn malformed_key_or_signature_is_rejected() {
+    let signer = VoucherSigner::from_seed(&[1; 32]);
+    let signature = signer.sign(&"nft".to_string(), &voucher());
+    let public_key = signer.public_key();
+
+    assert!(!verify(&signer, "nft", &voucher(), &signature.0[1..]));
+    assert!(!voucher().verify(&"nft".to_string(), &public_key.0, &signature.0));
+    assert!(!voucher().verify(&"nft".to_string(), &[], &signature.0));
+}
+
+#[test]
+fn voucher_with_malformed_gate_id_is_rejected() {
+    let mut voucher = serde_json::to_value(voucher()).unwrap();
+    for gate_id in &["", "GPZkspuVGaZxwWoP6bJoWU/", "GPZkspuVGaZxwWoP6bJoWUGPZkspuVGaZxwWoP6bJoWU"]
+    {
+        voucher["gate_id"] = json!(gate_id);
+        assert!(serde_json::from_value::<Voucher>(voucher.clone()).is_err());
+    }
+}
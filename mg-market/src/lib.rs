@@ -10,7 +10,9 @@ use mg_core::{
     crypto_hash,
     gate::{GateId, ValidGateId},
     nep178::NonFungibleTokenApprovalsReceiver,
-    MarketApproveMsg, Payout, TokenId,
+    nep297::{MintGateEvent, PauseData, RoleData},
+    state::{read_state_version, write_state_version, StateVersion},
+    MarketApproveMsg, Pausable, Payout, Role, TokenId,
 };
 use near_env::{near_ext, near_log, PanicMessage};
 use near_sdk::{
@@ -27,8 +29,17 @@ use near_sdk::{
 setup_alloc!();
 
 const GAS_FOR_ROYALTIES: Gas = 120_000_000_000_000;
+const GAS_FOR_NFT_IS_APPROVED: Gas = 10_000_000_000_000;
+const GAS_FOR_RELIST_TOKEN: Gas = 20_000_000_000_000;
 const NO_DEPOSIT: Balance = 0;
 
+/// Maximum number of accounts this marketplace is willing to pay out on a single sale.
+const MAX_LEN_PAYOUT: u32 = 16;
+
+/// The current version of the `MarketContract` state layout.
+/// Bump it whenever the layout changes, and add its migration into `migrate`.
+const STATE_VERSION: StateVersion = 3;
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct MarketContract {
@@ -42,6 +53,12 @@ pub struct MarketContract {
     tokens_by_owner_id: LookupMap<AccountId, UnorderedSet<TokenKey>>,
     /// Holds token IDs for sale by `creator_id`.
     tokens_by_creator_id: LookupMap<AccountId, UnorderedSet<TokenKey>>,
+    /// The owner of this contract, it implicitly holds every `Role`.
+    owner_id: AccountId,
+    /// Accounts holding each `Role` other than `Owner`.
+    roles: LookupMap<Role, UnorderedSet<AccountId>>,
+    /// Features currently paused, see `pause`.
+    paused: Vec<Pausable>,
 }
 
 /// In marketplace contract, each token must be addressed by `<nft contract id, token id>`.
@@ -56,8 +73,8 @@ impl Display for TokenKey {
 }
 
 /// Represents a token being sold in this marketplace.
-#[derive(BorshDeserialize, BorshSerialize, Serialize)]
-#[cfg_attr(not(target_arch = "wasm"), derive(Debug, Deserialize))]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[cfg_attr(not(target_arch = "wasm"), derive(Debug))]
 #[serde(crate = "near_sdk::serde")]
 pub struct TokenForSale {
     /// The contract account where this token has been minted.
@@ -87,6 +104,8 @@ enum Keys {
     TokensByOwnerIdValue(CryptoHash),
     TokensByCreatorId,
     TokensByCreatorIdValue(CryptoHash),
+    Roles,
+    RolesValue { role: Role },
 }
 
 /// The error variants thrown by *mg-market*.
@@ -105,6 +124,24 @@ pub enum Panics {
     /// Thrown when deposit is not enough to buy a token.
     #[panic_msg = "Not enough deposit to cover token minimum price"]
     NotEnoughDepositToBuyToken,
+    /// Thrown when `migrate` does not know how to migrate the stored state.
+    #[panic_msg = "Unable to migrate state from version `{}` to `{}`"]
+    UnsupportedStateMigration { from: StateVersion, to: StateVersion },
+    /// Thrown when `migrate` is called on a contract without state.
+    #[panic_msg = "Unable to find the state to migrate"]
+    StateNotFound,
+    /// Thrown when `migrate` needs an `owner_id` for a layout without owner.
+    #[panic_msg = "An `owner_id` is required to migrate state from version `{}`"]
+    MigrationOwnerMissing { from: StateVersion },
+    /// Thrown when a privileged method is called by an account not holding the required role.
+    #[panic_msg = "Account `{}` does not have the `{}` role"]
+    Unauthorized { account_id: AccountId, role: Role },
+    /// Thrown when trying to grant or revoke the `Owner` role.
+    #[panic_msg = "The owner role cannot be granted nor revoked, use `transfer_ownership` instead"]
+    OwnerRoleNotGrantable,
+    /// Thrown when calling a method whose feature is paused.
+    #[panic_msg = "The `{}` feature is paused"]
+    Paused { feature: Pausable },
 }
 
 /// Methods for the Marketplace contract.
@@ -113,14 +150,129 @@ pub enum Panics {
 #[near_bindgen]
 impl MarketContract {
     /// Initializes the Market contract.
+    ///
+    /// - `owner_id` is the account owning this contract, it is allowed to perform every privileged operation.
     #[init]
-    pub fn init() -> Self {
+    pub fn init(owner_id: ValidAccountId) -> Self {
+        write_state_version(STATE_VERSION);
+
         Self {
             tokens_for_sale: UnorderedMap::new(Keys::TokensForSale),
             tokens_by_nft_id: LookupMap::new(Keys::TokensByNftId),
             tokens_by_gate_id: LookupMap::new(Keys::TokensByGateId),
             tokens_by_owner_id: LookupMap::new(Keys::TokensByOwnerId),
             tokens_by_creator_id: LookupMap::new(Keys::TokensByCreatorId),
+            owner_id: owner_id.into(),
+            roles: LookupMap::new(Keys::Roles),
+            paused: Vec::new(),
+        }
+    }
+
+    /// Migrates the state of an already deployed contract into the current layout.
+    /// This method must be called right after deploying a new version of this contract,
+    /// *e.g.*, in the same transaction, and only the contract account itself can call it.
+    ///
+    /// - `owner_id` becomes the owner when migrating from version `2` or older,
+    /// since these layouts had no owner. It is ignored otherwise.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate(owner_id: Option<ValidAccountId>) -> Self {
+        let contract = match read_state_version() {
+            from @ 1..=2 => {
+                let owner_id =
+                    owner_id.unwrap_or_else(|| Panics::MigrationOwnerMissing { from }.panic());
+                legacy::MarketContractV1::read().into_contract(owner_id.into())
+            }
+            from => Panics::UnsupportedStateMigration { from, to: STATE_VERSION }.panic(),
+        };
+
+        write_state_version(STATE_VERSION);
+        contract
+    }
+
+    /// Pauses `feature` in case of an emergency, until it is unpaused.
+    /// This contract checks the `purchases` feature, *i.e.*, `buy_token`.
+    /// View methods are never paused.
+    /// Only accounts holding the `Pauser` role can pause.
+    pub fn pause(&mut self, feature: Pausable) {
+        let account_id = self.assert_role(Role::Pauser);
+
+        if !self.paused.contains(&feature) {
+            self.paused.push(feature);
+
+            MintGateEvent::Pause(vec![PauseData { account_id, feature }]).emit();
+        }
+    }
+
+    /// Unpauses `feature` previously paused with `pause`.
+    /// Only accounts holding the `Pauser` role can unpause.
+    pub fn unpause(&mut self, feature: Pausable) {
+        let account_id = self.assert_role(Role::Pauser);
+
+        if self.paused.contains(&feature) {
+            self.paused.retain(|paused| paused != &feature);
+
+            MintGateEvent::Unpause(vec![PauseData { account_id, feature }]).emit();
+        }
+    }
+
+    /// Returns the features currently paused.
+    pub fn get_paused(&self) -> Vec<Pausable> {
+        self.paused.clone()
+    }
+
+    /// Grants `role` to `account_id`.
+    /// Only the owner can grant the `Admin` role,
+    /// whereas any `Admin` can grant the remaining roles.
+    /// This contract only checks the `Pauser` role.
+    pub fn grant_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_role_manager(role);
+
+        let mut holders =
+            self.roles.get(&role).unwrap_or_else(|| UnorderedSet::new(Keys::RolesValue { role }));
+        if holders.insert(account_id.as_ref()) {
+            self.roles.insert(&role, &holders);
+
+            MintGateEvent::RoleGrant(vec![RoleData { account_id: account_id.into(), role }]).emit();
+        }
+    }
+
+    /// Revokes `role` from `account_id`.
+    /// The same rules of `grant_role` apply.
+    pub fn revoke_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_role_manager(role);
+
+        if let Some(mut holders) = self.roles.get(&role) {
+            if holders.remove(account_id.as_ref()) {
+                self.roles.insert(&role, &holders);
+
+                MintGateEvent::RoleRevoke(vec![RoleData { account_id: account_id.into(), role }])
+                    .emit();
+            }
+        }
+    }
+
+    /// Transfers the ownership of this contract to `new_owner_id`.
+    /// Only the current owner can transfer the ownership.
+    pub fn transfer_ownership(&mut self, new_owner_id: ValidAccountId) {
+        let owner_id = self.assert_role(Role::Owner);
+        self.owner_id = new_owner_id.into();
+
+        MintGateEvent::RoleRevoke(vec![RoleData { account_id: owner_id, role: Role::Owner }])
+            .emit();
+        MintGateEvent::RoleGrant(vec![RoleData {
+            account_id: self.owner_id.clone(),
+            role: Role::Owner,
+        }])
+        .emit();
+    }
+
+    /// Returns the accounts explicitly holding `role`.
+    /// Note that the owner holds every role, but it is listed only for the `Owner` role.
+    pub fn get_role_holders(&self, role: Role) -> Vec<AccountId> {
+        match role {
+            Role::Owner => vec![self.owner_id.clone()],
+            role => self.roles.get(&role).map_or_else(Vec::new, |holders| holders.to_vec()),
         }
     }
 
@@ -156,36 +308,46 @@ impl MarketContract {
     ///
     /// When the token is sold,
     /// royalties are paid by this marketplace according to `nft_contract_id::nft_transfer_payout`.
+    /// At most `MAX_LEN_PAYOUT` accounts are paid out, as defined by NEP-199.
+    /// If the NFT contract refuses the transfer,
+    /// *e.g.*, the token is no longer transferable or has more than `MAX_LEN_PAYOUT` payees,
+    /// the deposit is refunded to the buyer.
+    /// The token is put back for sale only when this marketplace is still approved to transfer it,
+    /// see `nft_is_approved`, otherwise its listing is dropped.
     #[payable]
     pub fn buy_token(&mut self, nft_contract_id: ValidAccountId, token_id: TokenId) {
+        self.assert_not_paused(Pausable::Purchases);
+
         let token_key = TokenKey(nft_contract_id.to_string(), token_id);
-        if let Some(TokenForSale { owner_id, min_price, gate_id, creator_id, .. }) =
-            self.tokens_for_sale.get(&token_key)
-        {
+        if let Some(token) = self.tokens_for_sale.get(&token_key) {
             let buyer_id = env::predecessor_account_id();
 
-            if buyer_id == owner_id {
+            if buyer_id == token.owner_id {
                 Panics::BuyOwnTokenNotAllowed.panic();
             }
 
             let deposit = env::attached_deposit();
-            if deposit < min_price.0 {
+            if deposit < token.min_price.0 {
                 Panics::NotEnoughDepositToBuyToken.panic();
             }
 
-            self.remove_token_id(&token_key, &owner_id, &gate_id, &creator_id);
+            self.remove_token_id(&token_key, &token.owner_id, &token.gate_id, &token.creator_id);
 
             mg_core::nep171::nft::nft_transfer_payout(
-                buyer_id.try_into().unwrap(),
+                buyer_id.clone().try_into().unwrap(),
                 token_id,
                 None,
                 None,
-                Some(U128(deposit)),
+                U128(deposit),
+                Some(MAX_LEN_PAYOUT),
                 &nft_contract_id,
                 0,
                 env::prepaid_gas() / 3,
             )
             .then(self_callback::make_payouts(
+                buyer_id,
+                U128(deposit),
+                token,
                 &env::current_account_id(),
                 NO_DEPOSIT,
                 GAS_FOR_ROYALTIES,
@@ -195,6 +357,48 @@ impl MarketContract {
         }
     }
 
+    /// Returns whether `account_id` holds `role`,
+    /// either explicitly or implied by the `Owner` or `Admin` roles.
+    fn has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        if account_id == &self.owner_id {
+            return true;
+        }
+
+        let holds =
+            |role| self.roles.get(&role).map_or(false, |holders| holders.contains(account_id));
+        match role {
+            Role::Owner => false,
+            Role::Admin => holds(Role::Admin),
+            role => holds(Role::Admin) || holds(role),
+        }
+    }
+
+    /// Ensures the `predecessor_account_id` holds `role`.
+    /// Returns the `predecessor_account_id` when it does, panics otherwise.
+    fn assert_role(&self, role: Role) -> AccountId {
+        let account_id = env::predecessor_account_id();
+        if !self.has_role(&account_id, role) {
+            Panics::Unauthorized { account_id, role }.panic();
+        }
+        account_id
+    }
+
+    /// Ensures the `predecessor_account_id` is allowed to grant and revoke `role`.
+    fn assert_role_manager(&self, role: Role) {
+        match role {
+            Role::Owner => Panics::OwnerRoleNotGrantable.panic(),
+            Role::Admin => self.assert_role(Role::Owner),
+            _ => self.assert_role(Role::Admin),
+        };
+    }
+
+    /// Ensures `feature` is not paused.
+    fn assert_not_paused(&self, feature: Pausable) {
+        if self.paused.contains(&feature) {
+            Panics::Paused { feature }.panic();
+        }
+    }
+
     fn remove_token_id(
         &mut self,
         token_key: &TokenKey,
@@ -222,19 +426,42 @@ impl MarketContract {
 #[near_ext]
 #[ext_contract(self_callback)]
 trait SelfCallback {
-    fn make_payouts(&mut self);
+    fn make_payouts(&mut self, buyer_id: AccountId, deposit: U128, token: TokenForSale);
+
+    fn relist_token(&mut self, token: TokenForSale);
 }
 
 #[near_log(skip_args, only_pub)]
 #[near_bindgen]
 impl SelfCallback for MarketContract {
+    /// Pays out the sale of `token` to `buyer_id`.
+    /// When `nft_transfer_payout` failed, the token has not been transferred,
+    /// so `deposit` is refunded to `buyer_id` and `token` is relisted by `relist_token`.
     #[private]
-    fn make_payouts(&mut self) {
+    fn make_payouts(&mut self, buyer_id: AccountId, deposit: U128, token: TokenForSale) {
         match env::promise_result(0) {
             PromiseResult::NotReady => unreachable!(),
-            PromiseResult::Failed => unreachable!(),
+            PromiseResult::Failed => {
+                Promise::new(buyer_id).transfer(deposit.0);
+
+                let nft_contract_id = token.nft_contract_id.clone();
+                mg_core::nep178::nft_approval::nft_is_approved(
+                    token.token_id,
+                    env::current_account_id().try_into().unwrap(),
+                    Some(token.approval_id),
+                    &nft_contract_id,
+                    0,
+                    GAS_FOR_NFT_IS_APPROVED,
+                )
+                .then(self_callback::relist_token(
+                    token,
+                    &env::current_account_id(),
+                    NO_DEPOSIT,
+                    GAS_FOR_RELIST_TOKEN,
+                ));
+            }
             PromiseResult::Successful(value) => {
-                if let Ok(payout) = serde_json::from_slice::<Payout>(&value) {
+                if let Ok(Payout { payout }) = serde_json::from_slice::<Payout>(&value) {
                     for (receiver_id, amount) in payout {
                         Promise::new(receiver_id).transfer(amount.0);
                     }
@@ -244,6 +471,19 @@ impl SelfCallback for MarketContract {
             }
         }
     }
+
+    /// Puts `token` back for sale after a failed purchase,
+    /// but only when `nft_is_approved` tells that this marketplace can still transfer it
+    /// with the same `approval_id`, *i.e.*, it has neither been transferred nor revoked.
+    /// Otherwise, the listing is dropped.
+    #[private]
+    fn relist_token(&mut self, token: TokenForSale) {
+        if let PromiseResult::Successful(value) = env::promise_result(0) {
+            if serde_json::from_slice::<bool>(&value).unwrap_or(false) {
+                self.insert_token(token);
+            }
+        }
+    }
 }
 
 /// This interface defines methods to be called
@@ -309,41 +549,43 @@ impl MarketContract {
         approve_msg: MarketApproveMsg,
         approval_id: U64,
     ) {
-        let token_key = TokenKey(nft_contract_id.clone(), token_id);
-        self.tokens_for_sale.insert(
-            &token_key,
-            &TokenForSale {
-                nft_contract_id: nft_contract_id.clone(),
-                token_id,
-                owner_id: owner_id.clone().into(),
-                approval_id,
-                min_price: approve_msg.min_price,
-                gate_id: approve_msg.gate_id.clone().map(|g| g.to_string()),
-                creator_id: approve_msg.creator_id.clone(),
-            },
-        );
+        self.insert_token(TokenForSale {
+            nft_contract_id: nft_contract_id.clone(),
+            token_id,
+            owner_id: owner_id.clone(),
+            approval_id,
+            min_price: approve_msg.min_price,
+            gate_id: approve_msg.gate_id.map(|g| g.to_string()),
+            creator_id: approve_msg.creator_id,
+        });
+    }
+
+    /// Puts `token` for sale and adds it into all indexes.
+    fn insert_token(&mut self, token: TokenForSale) {
+        let token_key = TokenKey(token.nft_contract_id.clone(), token.token_id);
+        self.tokens_for_sale.insert(&token_key, &token);
 
         insert_token_id_to(
             &mut self.tokens_by_nft_id,
-            &nft_contract_id,
-            &token_id,
+            &token.nft_contract_id,
+            &token.token_id,
             Keys::TokensByNftIdValue,
         );
         insert_token_id_to(
             &mut self.tokens_by_owner_id,
-            &owner_id.into(),
+            &token.owner_id,
             &token_key,
             Keys::TokensByOwnerIdValue,
         );
-        if let Some(gate_id) = approve_msg.gate_id {
+        if let Some(gate_id) = token.gate_id {
             insert_token_id_to(
                 &mut self.tokens_by_gate_id,
-                gate_id.as_ref(),
+                &gate_id,
                 &token_key,
                 Keys::TokensByGateIdValue,
             );
         }
-        if let Some(creator_id) = approve_msg.creator_id {
+        if let Some(creator_id) = token.creator_id {
             insert_token_id_to(
                 &mut self.tokens_by_creator_id,
                 &creator_id,
@@ -395,3 +637,40 @@ fn remove_token_id_from<T: BorshSerialize + BorshDeserialize + Clone, K: BorshSe
         }
     }
 }
+
+/// Layouts of previous versions of `MarketContract`, used to `migrate` deployed contracts.
+mod legacy {
+
+    use super::*;
+
+    /// Layout up to version `2`, when state versioning was introduced.
+    /// The contract had no owner, so it could not be paused.
+    #[derive(BorshDeserialize)]
+    pub struct MarketContractV1 {
+        tokens_for_sale: UnorderedMap<TokenKey, TokenForSale>,
+        tokens_by_nft_id: LookupMap<AccountId, UnorderedSet<TokenId>>,
+        tokens_by_gate_id: LookupMap<GateId, UnorderedSet<TokenKey>>,
+        tokens_by_owner_id: LookupMap<AccountId, UnorderedSet<TokenKey>>,
+        tokens_by_creator_id: LookupMap<AccountId, UnorderedSet<TokenKey>>,
+    }
+
+    impl MarketContractV1 {
+        pub fn read() -> Self {
+            env::state_read().unwrap_or_else(|| Panics::StateNotFound.panic())
+        }
+
+        /// Since there was no owner, it has to be given when migrating.
+        pub fn into_contract(self, owner_id: AccountId) -> MarketContract {
+            MarketContract {
+                tokens_for_sale: self.tokens_for_sale,
+                tokens_by_nft_id: self.tokens_by_nft_id,
+                tokens_by_gate_id: self.tokens_by_gate_id,
+                tokens_by_owner_id: self.tokens_by_owner_id,
+                tokens_by_creator_id: self.tokens_by_creator_id,
+                owner_id,
+                roles: LookupMap::new(Keys::Roles),
+                paused: Vec::new(),
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@
 use mg_core::{
     gate::ValidGateId,
     mock_context,
-    mocked_context::{alice, any, bob, charlie, gate_id, nft},
+    mocked_context::{alice, any, bob, charlie, gate_id, mintgate_admin, nft},
     nep178::NonFungibleTokenApprovalsReceiver,
     MarketApproveMsg, TokenId,
 };
@@ -118,7 +118,9 @@ fn approve_msg(price: u128, gate_id: ValidGateId, creator_id: ValidAccountId) ->
 }
 
 fn init_contract() -> MockedContext<MarketContractChecker> {
-    MockedContext::new(|| MarketContractChecker { contract: MarketContract::init() })
+    MockedContext::new(|| MarketContractChecker {
+        contract: MarketContract::init(mintgate_admin()),
+    })
 }
 
 fn init() -> MockedContext<MarketContractChecker> {
@@ -262,3 +264,118 @@ mod buy_token {
             });
     }
 }
+
+mod pause {
+
+    use super::*;
+    use mg_core::{
+        nep297::{Event, MintGateEvent, PauseData},
+        Pausable, Role,
+    };
+    use near_sdk::test_utils::get_logs;
+
+    fn pause_events() -> Vec<MintGateEvent> {
+        get_logs()
+            .iter()
+            .filter_map(|log| match Event::from_log(log) {
+                Some(Event::Mintgate { event, .. }) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pause_and_unpause_emit_events() {
+        init().run_as(mintgate_admin(), |contract| {
+            assert!(contract.get_paused().is_empty());
+
+            contract.pause(Pausable::Purchases);
+            assert_eq!(contract.get_paused(), vec![Pausable::Purchases]);
+            assert_eq!(
+                pause_events(),
+                vec![MintGateEvent::Pause(vec![PauseData {
+                    account_id: mintgate_admin().to_string(),
+                    feature: Pausable::Purchases,
+                }])]
+            );
+
+            contract.unpause(Pausable::Purchases);
+            assert!(contract.get_paused().is_empty());
+            assert_eq!(
+                pause_events(),
+                vec![MintGateEvent::Unpause(vec![PauseData {
+                    account_id: mintgate_admin().to_string(),
+                    feature: Pausable::Purchases,
+                }])]
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `pauser` role")]
+    fn pause_from_non_pauser_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.pause(Pausable::Purchases);
+        });
+    }
+
+    #[test]
+    fn grant_pauser_role_to_pause() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(alice(), Role::Pauser);
+                assert_eq!(contract.get_role_holders(Role::Pauser), vec![alice().to_string()]);
+            })
+            .run_as(alice(), |contract| {
+                contract.pause(Pausable::Purchases);
+                assert_eq!(contract.get_paused(), vec![Pausable::Purchases]);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `pauser` role")]
+    fn unpause_after_revoke_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(alice(), Role::Pauser);
+                contract.revoke_role(alice(), Role::Pauser);
+                contract.pause(Pausable::Purchases);
+            })
+            .run_as(alice(), |contract| {
+                contract.unpause(Pausable::Purchases);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "The `purchases` feature is paused")]
+    fn buy_a_token_while_purchases_are_paused_should_panic() {
+        let token_id = 5.into();
+        init()
+            .run_as(nft(), |contract| {
+                let msg = approve_msg(1000, gate_id(1), charlie());
+                contract.nft_on_approve(token_id, bob(), 0.into(), msg);
+            })
+            .run_as(mintgate_admin(), |contract| {
+                contract.pause(Pausable::Purchases);
+            })
+            .run_as(alice(), |contract| {
+                assert_eq!(contract.get_tokens_for_sale().len(), 1);
+
+                contract.attach_deposit(1500);
+                contract.buy_token(nft(), token_id);
+            });
+    }
+
+    #[test]
+    fn transfer_ownership_to_pause() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.transfer_ownership(alice());
+            })
+            .run_as(alice(), |contract| {
+                assert_eq!(contract.get_role_holders(Role::Owner), vec![alice().to_string()]);
+                contract.pause(Pausable::Purchases);
+                assert_eq!(contract.get_paused(), vec![Pausable::Purchases]);
+            });
+    }
+}
@@ -17,28 +17,41 @@
 //! <https://github.com/near/NEPs/blob/master/specs/Standards/NonFungibleToken/ApprovalManagement.md>
 //! - Non-Fungible Token Enumeration NEP-181
 //! <https://github.com/near/NEPs/blob/master/specs/Standards/NonFungibleToken/Enumeration.md>
+//! - Events NEP-297
+//! <https://github.com/near/NEPs/blob/master/specs/Standards/EventsFormat.md>
 #![deny(warnings)]
 
 use mg_core::{
     crypto_hash,
     fraction::Fraction,
     gate::{GateId, ValidGateId},
-    nep171::NonFungibleTokenCore,
+    linkdrop::{self, Linkdrop},
+    merkle,
+    nep171::{nft_receiver, nft_resolver, NonFungibleTokenCore, NonFungibleTokenResolver},
     nep177::{NFTContractMetadata, NonFungibleTokenMetadata},
     nep178::NonFungibleTokenApprovalMgmt,
     nep181::NonFungibleTokenEnumeration,
-    Collectible, MarketApproveMsg, Metadata, NftApproveMsg, Payout, Token, TokenApproval, TokenId,
+    nep297::{
+        CollectibleCreateData, CollectibleDeleteData, MintGateEvent, Nep171Event, NftApproveData,
+        NftBurnData, NftMintData, NftRevokeData, NftTransferData, PauseData, RoleData,
+    },
+    raffle::{self, Raffle, RaffleEntry},
+    reveal::{self, HiddenMetadata},
+    state::{read_state_version, write_state_version, StateVersion},
+    voucher::{Voucher, VoucherCollectible, ED25519_CURVE},
+    ClaimStatus, Collectible, MarketApproveMsg, Metadata, NftApproveMsg, NftConfig, Pausable,
+    Payout, Role, RoyaltyShare, Timestamp, Token, TokenApproval, TokenId, Transferability,
 };
 use near_env::{near_ext, near_log, PanicMessage};
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::{LookupMap, UnorderedMap, UnorderedSet},
+    collections::{LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector},
     env, ext_contract,
-    json_types::{ValidAccountId, U128, U64},
-    log, near_bindgen,
+    json_types::{Base58CryptoHash, Base58PublicKey, Base64VecU8, ValidAccountId, U128, U64},
+    near_bindgen,
     serde::{Deserialize, Serialize},
     serde_json, setup_alloc, AccountId, Balance, BorshStorageKey, CryptoHash, Gas, PanicOnDefault,
-    Promise, PromiseResult,
+    Promise, PromiseResult, PublicKey, StorageUsage,
 };
 use std::{cmp::Ordering, collections::HashMap, convert::TryInto, fmt::Display};
 
@@ -55,8 +68,13 @@ pub struct NftContract {
     collectibles_by_creator: LookupMap<AccountId, UnorderedSet<GateId>>,
     tokens: UnorderedMap<TokenId, Token>,
     tokens_by_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
-    /// Admin account is only account allowed to make certain calls.
-    admin_id: AccountId,
+    /// The `TokenId` to assign to the next minted `Token`.
+    /// It is never decremented, so IDs of burned tokens are not reused.
+    next_token_id: u64,
+    /// The owner of this contract, it implicitly holds every `Role`.
+    owner_id: AccountId,
+    /// Accounts holding each `Role` other than `Owner`.
+    roles: LookupMap<Role, UnorderedSet<AccountId>>,
     /// Metadata describing this NFT contract
     metadata: NFTContractMetadata,
     /// Indicates the minimum allowed `royalty` to be set on a `Collectible` when an Artist creates it.
@@ -68,6 +86,34 @@ pub struct NftContract {
     mintgate_fee: Fraction,
     /// Designated MintGate NEAR account id to receive `mintgate_fee` after a sale.
     mintgate_fee_account_id: AccountId,
+    /// The highest total royalty, *i.e.*, `royalty` plus `royalty_split`,
+    /// of the collectibles created so far, multiplied by `ROYALTY_SCALE`.
+    /// It bounds the `mintgate_fee`, so tokens of every collectible can still be sold.
+    highest_royalty: Balance,
+    /// Accounts that have already claimed a `Token` using their allowlist proof, by `GateId`.
+    allowlist_claims: LookupSet<(GateId, AccountId)>,
+    /// How many `Token`s each account has claimed, by `GateId`.
+    /// Unlike `tokens_by_owner`, it is not updated when tokens are transferred or burned.
+    claims_by_account: LookupMap<(GateId, AccountId), u16>,
+    /// Features currently paused, see `pause`.
+    paused: Vec<Pausable>,
+    /// The ed25519 key each creator signs its vouchers with, see `set_voucher_key`.
+    voucher_keys: LookupMap<AccountId, Base58PublicKey>,
+    /// Nonces of the vouchers already redeemed, by creator.
+    redeemed_vouchers: LookupSet<(AccountId, u64)>,
+    /// Hashes of the claim codes not used yet, by `GateId`, see `add_claim_codes`.
+    claim_codes: LookupMap<GateId, UnorderedSet<CryptoHash>>,
+    /// Linkdrops not claimed yet, by the public key of their access key, see `add_linkdrop`.
+    linkdrops: LookupMap<PublicKey, Linkdrop>,
+    /// Linkdrops whose access key is being added, by its public key.
+    /// They are moved to `linkdrops` only once the access key has been added.
+    pending_linkdrops: LookupMap<PublicKey, Linkdrop>,
+    /// Raffles of collectibles, by `GateId`, see `create_raffle`.
+    raffles: LookupMap<GateId, Raffle>,
+    /// Accounts that have entered each raffle, in the order they are drawn.
+    raffle_entrants: LookupMap<GateId, Vector<AccountId>>,
+    /// Entries of each account holding a raffle deposit, by `GateId`.
+    raffle_entries: LookupMap<(GateId, AccountId), RaffleEntry>,
 }
 
 /// To create a persistent collection on the blockchain, *e.g.*,
@@ -82,6 +128,20 @@ enum Keys {
     Tokens,
     TokensByOwner,
     TokensByOwnerValue { owner_id_hash: CryptoHash },
+    Roles,
+    RolesValue { role: Role },
+    AllowlistClaims,
+    ClaimsByAccount,
+    VoucherSigners,
+    RedeemedVouchers,
+    ClaimCodes,
+    ClaimCodesValue { gate_id_hash: CryptoHash },
+    Linkdrops,
+    PendingLinkdrops,
+    Raffles,
+    RaffleEntrants,
+    RaffleEntrantsValue { gate_id_hash: CryptoHash },
+    RaffleEntries,
 }
 
 /// The error variants thrown by *mg-nft*.
@@ -96,28 +156,108 @@ pub enum Panic {
     RoyaltyMaxThanAllowed { royalty: Fraction, gate_id: String },
     #[panic_msg = "Royalty `{}` is too large for the given NFT fee `{}`"]
     RoyaltyTooLarge { royalty: Fraction, mintgate_fee: Fraction },
+    #[panic_msg = "Royalty split of `{}` is greater than max royalty `{}`"]
+    RoyaltySplitMaxThanAllowed { gate_id: GateId, max_royalty: Fraction },
+    #[panic_msg = "Royalty split of `{}` is too large for the given NFT fee `{}`"]
+    RoyaltySplitTooLarge { gate_id: GateId, mintgate_fee: Fraction },
+    #[panic_msg = "Mintgate fee `{}` is too large for the royalties of existing collectibles"]
+    MintgateFeeTooLarge { mintgate_fee: Fraction },
     #[panic_msg = "Gate ID `{}` already exists"]
     GateIdAlreadyExists { gate_id: GateId },
     #[panic_msg = "Gate ID `{}` must have a positive supply"]
     ZeroSupplyNotAllowed { gate_id: GateId },
     #[panic_msg = "Invalid argument for gate ID `{}`: {}"]
     InvalidArgument { gate_id: GateId, reason: String },
-    #[panic_msg = "Operation is allowed only for admin"]
-    AdminRestrictedOperation,
+    #[panic_msg = "Account `{}` does not have the `{}` role"]
+    Unauthorized { account_id: AccountId, role: Role },
+    #[panic_msg = "The owner role cannot be granted nor revoked, use `transfer_ownership` instead"]
+    OwnerRoleNotGrantable,
+    #[panic_msg = "The `{}` feature is paused"]
+    Paused { feature: Pausable },
     #[panic_msg = "Gate ID `{}` was not found"]
     GateIdNotFound { gate_id: GateId },
+    #[panic_msg = "Metadata of gate ID `{}` is frozen"]
+    CollectibleFrozen { gate_id: GateId },
+    #[panic_msg = "Metadata of gate ID `{}` has already been committed"]
+    MetadataAlreadyCommitted { gate_id: GateId },
+    #[panic_msg = "Metadata of gate ID `{}` has no commitment to reveal"]
+    MetadataNotCommitted { gate_id: GateId },
+    #[panic_msg = "Revealed metadata of gate ID `{}` does not match its commitment"]
+    InvalidMetadataReveal { gate_id: GateId },
+    #[panic_msg = "Account `{}` is not the creator of gate ID `{}`"]
+    NotCollectibleCreator { account_id: AccountId, gate_id: GateId },
+    #[panic_msg = "Gate ID `{}` requires an allowlist proof to claim"]
+    AllowlistProofRequired { gate_id: GateId },
+    #[panic_msg = "Account `{}` is not in the allowlist of gate ID `{}`"]
+    InvalidAllowlistProof { account_id: AccountId, gate_id: GateId },
+    #[panic_msg = "Account `{}` has already claimed gate ID `{}` with its allowlist proof"]
+    AllowlistProofAlreadyUsed { account_id: AccountId, gate_id: GateId },
+    #[panic_msg = "Account `{}` has already claimed `{}` tokens of gate ID `{}`"]
+    ClaimLimitReached { account_id: AccountId, max_claims_per_account: u16, gate_id: GateId },
+    #[panic_msg = "Gate ID `{}` requires a claim code to claim"]
+    ClaimCodeRequired { gate_id: GateId },
+    #[panic_msg = "Invalid or already used claim code for gate ID `{}`"]
+    InvalidClaimCode { gate_id: GateId },
+    #[panic_msg = "Gate ID `{}` already has a raffle"]
+    RaffleAlreadyExists { gate_id: GateId },
+    #[panic_msg = "Gate ID `{}` has no raffle"]
+    RaffleNotFound { gate_id: GateId },
+    #[panic_msg = "Raffle of gate ID `{}` ended at `{}`"]
+    RaffleEnded { gate_id: GateId, ends_at: Timestamp },
+    #[panic_msg = "Raffle of gate ID `{}` ends at `{}`, its winners cannot be drawn yet"]
+    RaffleNotEnded { gate_id: GateId, ends_at: Timestamp },
+    #[panic_msg = "Account `{}` has already entered the raffle of gate ID `{}`"]
+    RaffleAlreadyEntered { account_id: AccountId, gate_id: GateId },
+    #[panic_msg = "Account `{}` has no entry in the raffle of gate ID `{}`"]
+    RaffleEntryNotFound { account_id: AccountId, gate_id: GateId },
+    #[panic_msg = "Winners of the raffle of gate ID `{}` have already been drawn"]
+    RaffleAlreadyDrawn { gate_id: GateId },
+    #[panic_msg = "Winners of the raffle of gate ID `{}` have not been drawn yet"]
+    RaffleNotDrawn { gate_id: GateId },
+    #[panic_msg = "Account `{}` has not won the raffle of gate ID `{}`"]
+    NotRaffleWinner { account_id: AccountId, gate_id: GateId },
+    #[panic_msg = "Gate ID `{}` has raffle entries"]
+    RaffleHasEntries { gate_id: GateId },
+    #[panic_msg = "Tokens of gate ID `{}` can only be claimed by the winners of its raffle"]
+    RaffleWinnersOnly { gate_id: GateId },
+    #[panic_msg = "Claims for gate ID `{}` start at `{}`"]
+    ClaimNotStarted { gate_id: GateId, starts_at: Timestamp },
+    #[panic_msg = "Claims for gate ID `{}` ended at `{}`"]
+    ClaimEnded { gate_id: GateId, claims_end_at: Timestamp },
+    #[panic_msg = "Tokens of gate ID `{}` cannot be transferred nor approved by `{}`, they are `{}`"]
+    TokenNotTransferable { gate_id: GateId, sender_id: AccountId, transferability: Transferability },
     #[panic_msg = "Tokens for gate id `{}` have already been claimed"]
     GateIdExhausted { gate_id: GateId },
+    #[panic_msg = "Gate ID `{}` has only `{}` tokens left to mint"]
+    NotEnoughSupply { gate_id: GateId, current_supply: u16 },
+    #[panic_msg = "Unable to mint `{}` tokens in a single call, at most `{}` are allowed with the attached gas"]
+    MintToTooLong { len: u64, max_len: u64 },
+    #[panic_msg = "Voucher keys must be ed25519 public keys"]
+    InvalidVoucherKey,
+    #[panic_msg = "Account `{}` has no voucher key set"]
+    VoucherKeyNotFound { account_id: AccountId },
+    #[panic_msg = "Invalid voucher signature for gate ID `{}`"]
+    InvalidVoucherSignature { gate_id: GateId },
+    #[panic_msg = "Account `{}` is not the receiver of the voucher for gate ID `{}`"]
+    VoucherNotForAccount { account_id: AccountId, gate_id: GateId },
+    #[panic_msg = "Voucher for gate ID `{}` expired at `{}`"]
+    VoucherExpired { gate_id: GateId, expires_at: Timestamp },
+    #[panic_msg = "Voucher nonce `{:?}` of account `{}` has already been redeemed"]
+    VoucherAlreadyRedeemed { nonce: U64, creator_id: AccountId },
+    #[panic_msg = "A linkdrop already exists for the given public key"]
+    LinkdropAlreadyExists,
+    #[panic_msg = "No linkdrop was found for the given public key"]
+    LinkdropNotFound,
+    #[panic_msg = "Account `{}` did not fund the linkdrop"]
+    NotLinkdropFunder { account_id: AccountId },
+    #[panic_msg = "Account `{}` cannot be created, it must be a sub-account of either this contract or a top-level account"]
+    InvalidNewAccountId { account_id: AccountId },
     #[panic_msg = "Gate ID `{}` has already some claimed tokens"]
     GateIdHasTokens { gate_id: GateId },
-    #[panic_msg = "Unable to delete gate ID `{}`"]
-    NotAuthorized { gate_id: GateId },
     #[panic_msg = "Token ID `{:?}` was not found"]
     TokenIdNotFound { token_id: U64 },
     #[panic_msg = "Token ID `{:?}` does not belong to account `{}`"]
     TokenIdNotOwnedBy { token_id: U64, owner_id: AccountId },
-    #[panic_msg = "At most one approval is allowed per Token"]
-    OneApprovalAllowed,
     #[panic_msg = "Sender `{}` is not authorized to make transfer"]
     SenderNotAuthToTransfer { sender_id: AccountId },
     #[panic_msg = "The token owner and the receiver should be different"]
@@ -130,6 +270,26 @@ pub enum Panic {
     MsgFormatMinPriceMissing { reason: String },
     #[panic_msg = "Could not revoke approval for `{}`"]
     RevokeApprovalFailed { account_id: AccountId },
+    #[panic_msg = "Attached deposit `{:?}` is not enough to cover storage cost `{:?}`"]
+    NotEnoughDepositForStorage { attached_deposit: U128, storage_cost: U128 },
+    #[panic_msg = "Attached deposit `{:?}` is not enough to pay the mint price `{:?}`"]
+    NotEnoughDepositForMintPrice { attached_deposit: U128, mint_price: U128 },
+    #[panic_msg = "Attached deposit `{:?}` is not enough to fund a linkdrop, at least `{:?}` is required"]
+    NotEnoughDepositForLinkdrop { attached_deposit: U128, min_deposit: U128 },
+    #[panic_msg = "Attached deposit `{:?}` is not enough to enter the raffle, `{:?}` is required"]
+    NotEnoughDepositForRaffleEntry { attached_deposit: U128, entry_deposit: U128 },
+    #[panic_msg = "Not enough gas attached to make a transfer call"]
+    NotEnoughGasForTransferCall,
+    #[panic_msg = "Payout exceeds `max_len_payout` of `{}` accounts"]
+    PayoutTooLong { max_len_payout: u32 },
+    #[panic_msg = "Unable to migrate state from version `{}` to `{}`"]
+    UnsupportedStateMigration { from: StateVersion, to: StateVersion },
+    #[panic_msg = "Unable to find the state to migrate"]
+    StateNotFound,
+    #[panic_msg = "There is no migration in progress"]
+    MigrationNotStarted,
+    #[panic_msg = "A migration is in progress, call `migrate_batch` to complete it"]
+    MigrationInProgress,
     #[panic_msg = "At most 10 tokens are allowed to approve in batch"]
     ExceedTokensToBatchApprove,
     #[panic_msg = "{} error(s) detected, see `panics` fields for a full list of errors"]
@@ -156,42 +316,78 @@ impl NftContract {
     /// Initializes the contract.
     /// This contract methods needs to be explicitely called
     /// since the default construction of the contract will panic.
+    /// It cannot be called while a migration is in progress, see `migrate`.
     ///
-    /// - `admin_id` is the valid account that is allowed to perform certain operations.
+    /// - `owner_id` is the account owning this contract, it is allowed to perform every privileged operation.
     /// - `metadata` represents the general information of the contract.
     /// - `min_royalty` and `max_royalty` indicates what must be the max and min royalty respectively when creating a collectible.
     /// - `mintgate_fee` is the percetange to be paid to `mintgate_fee_account_id` for each sale.
     #[init]
     pub fn init(
-        admin_id: ValidAccountId,
+        owner_id: ValidAccountId,
         metadata: NFTContractMetadata,
         min_royalty: Fraction,
         max_royalty: Fraction,
         mintgate_fee: Fraction,
         mintgate_fee_account_id: ValidAccountId,
     ) -> Self {
-        min_royalty.check();
-        max_royalty.check();
+        if legacy::Migration::exists() {
+            Panic::MigrationInProgress.panic();
+        }
+        check_royalty_bounds(min_royalty, max_royalty);
         mintgate_fee.check();
 
-        if max_royalty.cmp(&min_royalty) == Ordering::Less {
-            Panic::MaxRoyaltyLessThanMinRoyalty { min_royalty, max_royalty }.panic();
-        }
+        write_state_version(STATE_VERSION);
 
         Self {
             collectibles: UnorderedMap::new(Keys::Collectibles),
             collectibles_by_creator: LookupMap::new(Keys::CollectiblesByCreator),
             tokens: UnorderedMap::new(Keys::Tokens),
             tokens_by_owner: LookupMap::new(Keys::TokensByOwner),
-            admin_id: admin_id.as_ref().to_string(),
+            next_token_id: 0,
+            owner_id: owner_id.into(),
+            roles: LookupMap::new(Keys::Roles),
             metadata,
             min_royalty,
             max_royalty,
             mintgate_fee,
             mintgate_fee_account_id: mintgate_fee_account_id.to_string(),
+            highest_royalty: 0,
+            allowlist_claims: LookupSet::new(Keys::AllowlistClaims),
+            claims_by_account: LookupMap::new(Keys::ClaimsByAccount),
+            paused: Vec::new(),
+            voucher_keys: LookupMap::new(Keys::VoucherSigners),
+            redeemed_vouchers: LookupSet::new(Keys::RedeemedVouchers),
+            claim_codes: LookupMap::new(Keys::ClaimCodes),
+            linkdrops: LookupMap::new(Keys::Linkdrops),
+            pending_linkdrops: LookupMap::new(Keys::PendingLinkdrops),
+            raffles: LookupMap::new(Keys::Raffles),
+            raffle_entrants: LookupMap::new(Keys::RaffleEntrants),
+            raffle_entries: LookupMap::new(Keys::RaffleEntries),
         }
     }
 
+    /// Migrates the state of an already deployed contract into the current layout.
+    /// This method must be called right after deploying a new version of this contract,
+    /// *e.g.*, in the same transaction, and only the contract account itself can call it.
+    ///
+    /// Since a single call cannot afford to migrate every collectible and token,
+    /// they are migrated afterwards by `migrate_batch`.
+    /// No other method can be called until the migration is complete.
+    #[private]
+    pub fn migrate() {
+        legacy::Migration::start(read_state_version()).write();
+        write_state_version(STATE_VERSION);
+    }
+
+    /// Migrates at most `limit` collectibles and tokens left by `migrate`.
+    /// It must be called until it returns `true`, *i.e.*, the migration is complete.
+    /// Only the contract account itself can call it.
+    #[private]
+    pub fn migrate_batch(limit: u32) -> bool {
+        legacy::Migration::read().migrate_batch(limit)
+    }
+
     /// Creates a new `Collectible`, identified by `gate_id`.
     /// The `supply` indicates maximum supply for this collectible.
     /// The `royalty` indicates the royalty (as percentage) paid to the creator (`predecessor_account_id`).
@@ -201,7 +397,27 @@ impl NftContract {
     /// Panics otherwise.
     /// This is to be able to make payouts all participants.
     ///
+    /// The optional `royalty_split` indicates the royalties paid to collaborators,
+    /// in addition to the `royalty` paid to the creator.
+    /// It can have at most `MAX_ROYALTY_SPLIT_LEN` shares.
+    /// The sum of `royalty` and all shares should be less or equal to `max_royalty`,
+    /// and adding `mintgate_fee` to it should be less than `1`.
+    ///
+    /// The optional `starts_at` and `claims_end_at` indicate when tokens can be claimed,
+    /// as UNIX epoch datetimes in miliseconds.
+    /// When `starts_at` is not given, tokens can be claimed right away.
+    /// When `claims_end_at` is not given, tokens can be claimed until the supply is exhausted.
+    /// The optional `expires_at` indicates when claimed tokens no longer grant access,
+    /// see `has_access`, so tokens cannot be claimed after it either.
+    /// If given, `starts_at` must be before both `claims_end_at` and `expires_at`.
+    ///
+    /// Only accounts holding the `CollectibleMinter` role can create collectibles.
+    ///
+    /// The caller must attach enough deposit to cover the storage used by the new collectible.
+    /// Any excess of the attached deposit is refunded.
+    ///
     /// See <https://github.com/epam/mintgate/issues/3>.
+    #[payable]
     pub fn create_collectible(
         &mut self,
         creator_id: ValidAccountId,
@@ -214,98 +430,37 @@ impl NftContract {
         media_hash: Option<String>,
         reference: Option<String>,
         reference_hash: Option<String>,
+        royalty_split: Option<Vec<RoyaltyShare>>,
+        starts_at: Option<Timestamp>,
+        claims_end_at: Option<Timestamp>,
+        expires_at: Option<Timestamp>,
     ) {
-        let gate_id = gate_id.to_string();
-
-        royalty.check();
-
-        if royalty.cmp(&self.min_royalty) == Ordering::Less {
-            Panic::RoyaltyMinThanAllowed { royalty, gate_id }.panic();
-        }
-        if royalty.cmp(&self.max_royalty) == Ordering::Greater {
-            Panic::RoyaltyMaxThanAllowed { royalty, gate_id }.panic();
-        }
-        let bn = 1_000_000_000_000_000_000_000;
-        if self.mintgate_fee.mult(bn) + royalty.mult(bn) >= bn {
-            Panic::RoyaltyTooLarge { royalty, mintgate_fee: self.mintgate_fee }.panic();
-        }
-        if self.collectibles.get(&gate_id).is_some() {
-            Panic::GateIdAlreadyExists { gate_id }.panic();
-        }
-        if supply == 0 {
-            Panic::ZeroSupplyNotAllowed { gate_id }.panic();
-        }
-        if title.len() > 140 {
-            Panic::InvalidArgument { gate_id, reason: "Title exceeds 140 chars".to_string() }
-                .panic();
-        }
-        if description.len() > 1024 {
-            Panic::InvalidArgument {
-                gate_id,
-                reason: "`description` exceeds 1024 chars".to_string(),
-            }
-            .panic();
-        }
-
-        macro_rules! check {
-            ($arg:ident) => {{
-                if let Some(val) = &$arg {
-                    if val.len() > 1024 {
-                        Panic::InvalidArgument {
-                            gate_id,
-                            reason: concat!("`", stringify!($arg), "` exceeds 1024 chars")
-                                .to_string(),
-                        }
-                        .panic();
-                    }
-                }
-            }};
-        }
-
-        check!(media);
-        check!(media_hash);
-        check!(reference);
-        check!(reference_hash);
-
-        if env::predecessor_account_id() != self.admin_id {
-            Panic::AdminRestrictedOperation.panic();
-        }
-
-        let creator_id = AccountId::from(creator_id);
         let now = env::block_timestamp() / 1_000_000;
-
-        let collectible = Collectible {
-            gate_id,
-            creator_id,
-            current_supply: supply,
-            minted_tokens: Vec::new(),
-            royalty,
-            metadata: Metadata {
-                title: Some(title),
-                description: Some(description),
-                media,
-                media_hash,
-                copies: Some(supply),
-                issued_at: Some(now),
-                expires_at: None,
-                starts_at: Some(now),
-                updated_at: None,
-                extra: None,
-                reference,
-                reference_hash,
-            },
+        let metadata = Metadata {
+            title: Some(title),
+            description: Some(description),
+            media,
+            media_hash,
+            copies: Some(supply),
+            issued_at: Some(now),
+            expires_at,
+            starts_at: Some(starts_at.unwrap_or(now)),
+            updated_at: None,
+            extra: None,
+            reference,
+            reference_hash,
         };
-        self.collectibles.insert(&collectible.gate_id, &collectible);
+        let mut collectible =
+            new_collectible(creator_id.into(), gate_id.into(), supply, royalty, metadata);
+        collectible.royalty_split = royalty_split.unwrap_or_default();
+        collectible.claims_end_at = claims_end_at;
+        self.check_new_collectible(&collectible);
 
-        let mut gids =
-            self.collectibles_by_creator.get(&collectible.creator_id).unwrap_or_else(|| {
-                UnorderedSet::new(Keys::CollectiblesByCreatorValue {
-                    creator_id_hash: crypto_hash(&collectible.creator_id),
-                })
-            });
-        gids.insert(&collectible.gate_id);
+        self.assert_role(Role::CollectibleMinter);
 
-        self.collectibles_by_creator.insert(&collectible.creator_id, &gids);
+        let initial_storage_usage = env::storage_usage();
+        self.insert_collectible(&collectible);
+        refund_storage_deposit(initial_storage_usage);
     }
 
     /// Returns the `Collectible` with the given `gate_id`.
@@ -324,6 +479,18 @@ impl NftContract {
         }
     }
 
+    /// Returns whether tokens of the `Collectible` with the given `gate_id`
+    /// can be claimed now (`live`), only later (`upcoming`), or not anymore (`ended`).
+    /// Note that a `live` collectible may have already exhausted its supply.
+    pub fn get_claim_status(&self, gate_id: ValidGateId) -> ClaimStatus {
+        let gate_id = gate_id.to_string();
+
+        match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(collectible) => ClaimStatus::at(&collectible, env::block_timestamp() / 1_000_000),
+        }
+    }
+
     /// Returns all `Collectible`s created by `creator_id`.
     ///
     /// See <https://github.com/epam/mintgate/issues/15>.
@@ -345,7 +512,7 @@ impl NftContract {
     /// Deletes the given `Collectible` by `gate_id`.
     /// The collectible can only be deleted if there are no minted tokens.
     /// Moreover, only the `creator_id` of the collectible or
-    /// the contract `admin_id` are allowed to delete the collectible.
+    /// an account holding the `Admin` role are allowed to delete the collectible.
     pub fn delete_collectible(&mut self, gate_id: ValidGateId) {
         let gate_id: GateId = From::from(gate_id);
         match self.collectibles.get(&gate_id) {
@@ -356,208 +523,1753 @@ impl NftContract {
                 if !collectible.minted_tokens.is_empty() {
                     Panic::GateIdHasTokens { gate_id }.panic();
                 }
+                if self.raffles.get(&gate_id).map_or(false, |raffle| raffle.entries > 0) {
+                    Panic::RaffleHasEntries { gate_id }.panic();
+                }
 
                 let pred_id = env::predecessor_account_id();
-                if pred_id == collectible.creator_id || pred_id == self.admin_id {
+                if pred_id == collectible.creator_id || self.has_role(&pred_id, Role::Admin) {
                     self.collectibles.remove(&gate_id).unwrap();
 
                     let mut cs = self.collectibles_by_creator.get(&collectible.creator_id).unwrap();
                     let removed = cs.remove(&gate_id);
                     assert!(removed);
                     self.collectibles_by_creator.insert(&collectible.creator_id, &cs);
+
+                    if let Some(mut codes) = self.claim_codes.remove(&gate_id) {
+                        codes.clear();
+                    }
+                    self.raffles.remove(&gate_id);
+
+                    MintGateEvent::CollectibleDelete(vec![CollectibleDeleteData {
+                        creator_id: collectible.creator_id,
+                        gate_id,
+                    }])
+                    .emit();
                 } else {
-                    Panic::NotAuthorized { gate_id }.panic();
+                    Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
                 }
             }
         }
     }
 
-    /// Claims a `Token` for the `Collectible` indicated by `gate_id`.
-    /// The claim is on behalf the `predecessor_account_id`.
-    /// Returns a `TokenId` that represents this claim.
-    /// If the given `gate_id` has exhausted its supply, this call will panic.
+    /// Updates the metadata of the `Collectible` identified by `gate_id`.
+    /// The given fields replace the current ones, and `updated_at` is set to the current time.
+    /// The same length restrictions of `create_collectible` apply.
     ///
-    /// See <https://github.com/epam/mintgate/issues/6>.
-    pub fn claim_token(&mut self, gate_id: ValidGateId) -> TokenId {
+    /// Only the `creator_id` of the collectible or
+    /// an account holding the `Admin` role are allowed to update the collectible.
+    /// Moreover, the collectible must not be frozen.
+    ///
+    /// The caller must attach enough deposit to cover any additional storage used.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn update_collectible_metadata(
+        &mut self,
+        gate_id: ValidGateId,
+        title: String,
+        description: String,
+        media: Option<String>,
+        media_hash: Option<String>,
+        reference: Option<String>,
+        reference_hash: Option<String>,
+        extra: Option<String>,
+    ) {
+        let gate_id = gate_id.to_string();
+        let mut collectible = self.get_updatable_collectible(gate_id.clone());
+
+        let initial_storage_usage = env::storage_usage();
+
+        let metadata = HiddenMetadata {
+            title,
+            description,
+            media,
+            media_hash,
+            reference,
+            reference_hash,
+            extra,
+        };
+        update_metadata(&gate_id, &mut collectible.metadata, metadata);
+        self.collectibles.insert(&gate_id, &collectible);
+
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Freezes the metadata of the `Collectible` identified by `gate_id`,
+    /// so it cannot be updated anymore.
+    /// Note that a frozen collectible cannot be unfrozen.
+    ///
+    /// The same permissions of `update_collectible_metadata` apply.
+    pub fn freeze_collectible_metadata(&mut self, gate_id: ValidGateId) {
+        let gate_id = gate_id.to_string();
+        let mut collectible = self.get_updatable_collectible(gate_id.clone());
+
+        collectible.frozen = true;
+        self.collectibles.insert(&gate_id, &collectible);
+    }
+
+    /// Hides the real metadata of the `Collectible` identified by `gate_id` until it is revealed,
+    /// committing to it with the given `metadata_commitment`, see `mg_core::reveal`.
+    /// Meanwhile, its current `metadata` is just a placeholder,
+    /// and so it is for its tokens.
+    ///
+    /// Only the `creator_id` of the collectible is allowed to commit its metadata,
+    /// and only once, before any of its tokens is minted.
+    /// Its metadata must not be frozen yet, but it can be frozen afterwards,
+    /// since the revealed metadata is bound to the commitment anyway.
+    ///
+    /// The caller must attach enough deposit to cover the storage used by the commitment.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn commit_metadata(&mut self, gate_id: ValidGateId, metadata_commitment: Base58CryptoHash) {
+        let gate_id = gate_id.to_string();
+        let mut collectible = self.get_collectible_or_panic(&gate_id);
+
+        let pred_id = env::predecessor_account_id();
+        if pred_id != collectible.creator_id {
+            Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
+        }
+        if collectible.frozen {
+            Panic::CollectibleFrozen { gate_id }.panic();
+        }
+        if !collectible.minted_tokens.is_empty() {
+            Panic::GateIdHasTokens { gate_id }.panic();
+        }
+        if collectible.metadata_commitment.is_some() {
+            Panic::MetadataAlreadyCommitted { gate_id }.panic();
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        collectible.metadata_commitment = Some(metadata_commitment);
+        self.collectibles.insert(&gate_id, &collectible);
+
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Reveals the real `metadata` of the `Collectible` identified by `gate_id`,
+    /// replacing its placeholder as `update_collectible_metadata` does.
+    /// The given `metadata` and `salt` must be the preimage of its `metadata_commitment`,
+    /// which is removed afterwards.
+    ///
+    /// Only the `creator_id` of the collectible is allowed to reveal it,
+    /// even if its metadata is frozen.
+    ///
+    /// The caller must attach enough deposit to cover any additional storage used.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn reveal(&mut self, gate_id: ValidGateId, metadata: HiddenMetadata, salt: String) {
+        let gate_id = gate_id.to_string();
+        let mut collectible = self.get_collectible_or_panic(&gate_id);
+
+        let pred_id = env::predecessor_account_id();
+        if pred_id != collectible.creator_id {
+            Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
+        }
+        match collectible.metadata_commitment.take() {
+            None => Panic::MetadataNotCommitted { gate_id }.panic(),
+            Some(metadata_commitment) => {
+                if !reveal::verify(&metadata_commitment.into(), &metadata, &salt) {
+                    Panic::InvalidMetadataReveal { gate_id }.panic();
+                }
+            }
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        update_metadata(&gate_id, &mut collectible.metadata, metadata);
+        self.collectibles.insert(&gate_id, &collectible);
+
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Sets the `mint_price` to be paid when claiming tokens of the `Collectible` identified by `gate_id`.
+    /// A `mint_price` of `0` allows to claim tokens for free.
+    ///
+    /// Only the `creator_id` of the collectible is allowed to set its `mint_price`.
+    pub fn set_mint_price(&mut self, gate_id: ValidGateId, mint_price: U128) {
         let gate_id = gate_id.to_string();
 
         match self.collectibles.get(&gate_id) {
             None => Panic::GateIdNotFound { gate_id }.panic(),
             Some(mut collectible) => {
-                if collectible.current_supply == 0 {
-                    Panic::GateIdExhausted { gate_id }.panic()
+                let pred_id = env::predecessor_account_id();
+                if pred_id != collectible.creator_id {
+                    Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
                 }
 
-                let owner_id = env::predecessor_account_id();
-                let now = env::block_timestamp() / 1_000_000;
-
-                let token_id = self.tokens.len();
-                let token = Token {
-                    token_id: U64::from(token_id),
-                    gate_id: gate_id.clone(),
-                    owner_id,
-                    created_at: now,
-                    modified_at: now,
-                    approvals: HashMap::new(),
-                    approval_counter: U64::from(0),
-                    metadata: Metadata::default(),
-                };
-                self.insert_token(&token);
-
-                collectible.current_supply = collectible.current_supply - 1;
-                collectible.minted_tokens.push(U64(token_id));
+                collectible.mint_price = mint_price;
                 self.collectibles.insert(&gate_id, &collectible);
-
-                U64::from(token_id)
             }
         }
     }
 
-    /// Burns (deletes) the `Token` identifed by `token_id`.
-    /// Only the `owner_id` can burn the token.
+    /// Sets the `allowlist_root` of the `Collectible` identified by `gate_id`.
+    /// When set, tokens can only be claimed by the accounts in the allowlist using `claim_token_with_proof`.
+    /// An `allowlist_root` of `null` allows any account to claim tokens.
     ///
-    /// After succefully delete the token,
-    /// a cross-contract call  is made to `nft_on_revoke` for each approval
-    /// to delist from their marketplaces.
-    pub fn burn_token(&mut self, token_id: TokenId) {
-        let token = self.get_token_or_panic(token_id);
-        let gate_id = token.gate_id;
+    /// Only the `creator_id` of the collectible is allowed to set its `allowlist_root`.
+    ///
+    /// The caller must attach enough deposit to cover any additional storage used.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn set_allowlist_root(
+        &mut self,
+        gate_id: ValidGateId,
+        allowlist_root: Option<Base58CryptoHash>,
+    ) {
+        let gate_id = gate_id.to_string();
 
         match self.collectibles.get(&gate_id) {
             None => Panic::GateIdNotFound { gate_id }.panic(),
             Some(mut collectible) => {
-                let owner_id = env::predecessor_account_id();
-                self.delete_token_from(token_id, &owner_id);
-
-                if let Some(copies) = collectible.metadata.copies {
-                    collectible.metadata.copies = Some(copies - 1);
+                let pred_id = env::predecessor_account_id();
+                if pred_id != collectible.creator_id {
+                    Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
                 }
 
-                let mut i = 0;
-                for tid in &collectible.minted_tokens {
-                    if tid == &token_id {
-                        collectible.minted_tokens.remove(i);
-                        break;
-                    }
+                let initial_storage_usage = env::storage_usage();
 
-                    i += 1;
-                }
+                collectible.allowlist_root = allowlist_root;
                 self.collectibles.insert(&gate_id, &collectible);
 
-                for (market_id, _) in &token.approvals {
-                    mg_core::nep178::market::nft_on_revoke(
-                        token_id,
-                        market_id,
-                        0,
-                        env::prepaid_gas() / 2,
-                    );
-                }
+                refund_storage_deposit(initial_storage_usage);
             }
         }
     }
 
-    /// Returns all `Token`s owned by `owner_id`.
-    pub fn get_tokens_by_owner(&self, owner_id: ValidAccountId) -> Vec<Token> {
-        match self.tokens_by_owner.get(owner_id.as_ref()) {
-            None => Vec::new(),
-            Some(list) => list
-                .iter()
-                .map(|token_id| {
-                    let token = self.get_token(token_id).expect("Token not found");
-                    assert!(token.token_id == token_id);
-                    assert!(&token.owner_id == owner_id.as_ref());
-                    token
-                })
-                .collect(),
-        }
-    }
-
-    /// Returns all tokens claimed by `owner_id` belonging to `gate_id`.
+    /// Sets the `max_claims_per_account` of the `Collectible` identified by `gate_id`.
+    /// Tokens already claimed by an account count towards the new limit.
+    /// A `max_claims_per_account` of `null` removes the limit.
     ///
-    /// See <https://github.com/epam/mintgate/issues/14>.
-    pub fn get_tokens_by_owner_and_gate_id(
-        &self,
+    /// Only the `creator_id` of the collectible is allowed to set its `max_claims_per_account`.
+    ///
+    /// The caller must attach enough deposit to cover any additional storage used.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn set_max_claims_per_account(
+        &mut self,
         gate_id: ValidGateId,
-        owner_id: ValidAccountId,
-    ) -> Vec<Token> {
+        max_claims_per_account: Option<u16>,
+    ) {
         let gate_id = gate_id.to_string();
 
-        match self.tokens_by_owner.get(owner_id.as_ref()) {
-            None => Vec::new(),
-            Some(list) => list
-                .iter()
-                .map(|token_id| {
-                    let token = self.get_token(token_id).expect("Token not found");
-                    assert!(token.token_id == token_id);
-                    assert!(&token.owner_id == owner_id.as_ref());
-                    token
-                })
-                .filter(|token| token.gate_id == gate_id)
-                .collect(),
+        match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(mut collectible) => {
+                let pred_id = env::predecessor_account_id();
+                if pred_id != collectible.creator_id {
+                    Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
+                }
+
+                let initial_storage_usage = env::storage_usage();
+
+                collectible.max_claims_per_account = max_claims_per_account;
+                self.collectibles.insert(&gate_id, &collectible);
+
+                refund_storage_deposit(initial_storage_usage);
+            }
         }
     }
 
-    // pub fn get_token_by_id(&self, token_id: TokenId) -> Option<Token> {
-    //     self.get_token(token_id)
-    // }
+    /// Sets the `transferability` of the `Collectible` identified by `gate_id`.
+    /// It applies to tokens already claimed as well, but existing approvals are not revoked.
+    /// Markets refund their buyers when the transfer of such a token is refused.
+    ///
+    /// Only the `creator_id` of the collectible is allowed to set its `transferability`.
+    ///
+    /// The caller must attach enough deposit to cover any additional storage used.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn set_transferability(&mut self, gate_id: ValidGateId, transferability: Transferability) {
+        let gate_id = gate_id.to_string();
 
-    /// Returns the token given by `token_id`.
-    /// Otherwise returns `None`.
-    fn get_token(&self, token_id: TokenId) -> Option<Token> {
-        match self.tokens.get(&token_id) {
-            None => None,
-            Some(mut token) => {
-                assert!(token.token_id == token_id);
-                let collectible = self.collectibles.get(&token.gate_id).expect("Gate id not found");
-                token.metadata = collectible.metadata;
-                Some(token)
+        match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(mut collectible) => {
+                let pred_id = env::predecessor_account_id();
+                if pred_id != collectible.creator_id {
+                    Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
+                }
+
+                let initial_storage_usage = env::storage_usage();
+
+                collectible.transferability = transferability;
+                self.collectibles.insert(&gate_id, &collectible);
+
+                refund_storage_deposit(initial_storage_usage);
             }
         }
     }
 
-    /// Gets the `Token` with given `token_id`.
-    /// Panics otherwise.
-    fn get_token_or_panic(&self, token_id: TokenId) -> Token {
-        match self.get_token(token_id) {
-            None => Panic::TokenIdNotFound { token_id }.panic(),
-            Some(token) => token,
+    /// Adds the sha256 `code_hashes` of one-time claim codes to the `Collectible` identified by `gate_id`.
+    /// Once a collectible has claim codes, tokens can only be claimed with `claim_with_code`,
+    /// even after all of its codes have been used.
+    /// Hashes already added are ignored.
+    /// See `mg_core::claim_code` to generate codes along with their hashes.
+    ///
+    /// Only the `creator_id` of the collectible is allowed to add claim codes.
+    ///
+    /// The caller must attach enough deposit to cover the storage used by the new codes.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn add_claim_codes(&mut self, gate_id: ValidGateId, code_hashes: Vec<Base58CryptoHash>) {
+        let gate_id = gate_id.to_string();
+        let collectible = self.get_collectible_or_panic(&gate_id);
+
+        let pred_id = env::predecessor_account_id();
+        if pred_id != collectible.creator_id {
+            Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
         }
-    }
 
-    /// Inserts the given `Token` into `tokens` and `tokens_by_owner`.
-    fn insert_token(&mut self, token: &Token) {
-        self.tokens.insert(&token.token_id, token);
+        let initial_storage_usage = env::storage_usage();
 
-        let mut tids = self.tokens_by_owner.get(&token.owner_id).unwrap_or_else(|| {
-            UnorderedSet::new(Keys::TokensByOwnerValue {
-                owner_id_hash: crypto_hash(&token.owner_id),
-            })
+        let mut codes = self.claim_codes.get(&gate_id).unwrap_or_else(|| {
+            UnorderedSet::new(Keys::ClaimCodesValue { gate_id_hash: crypto_hash(&gate_id) })
         });
-        tids.insert(&token.token_id);
+        for code_hash in code_hashes {
+            codes.insert(&code_hash.into());
+        }
+        self.claim_codes.insert(&gate_id, &codes);
 
-        self.tokens_by_owner.insert(&token.owner_id, &tids);
+        refund_storage_deposit(initial_storage_usage);
     }
 
-    /// Internal method to delete the corgi with `id` owned by `owner`.
-    /// Panics if `owner` does not own the corgi with `id`.
-    fn delete_token_from(&mut self, token_id: TokenId, owner_id: &AccountId) {
-        match self.tokens_by_owner.get(&owner_id) {
-            None => Panic::TokenIdNotOwnedBy { token_id, owner_id: owner_id.clone() }.panic(),
-            Some(mut list) => {
-                if !list.remove(&token_id) {
-                    Panic::TokenIdNotOwnedBy { token_id, owner_id: owner_id.clone() }.panic();
-                }
-                self.tokens_by_owner.insert(&owner_id, &list);
+    /// Returns how many claim codes of the `Collectible` identified by `gate_id` have not been used yet.
+    pub fn get_claim_codes_left(&self, gate_id: ValidGateId) -> u64 {
+        self.claim_codes.get(gate_id.as_ref()).map_or(0, |codes| codes.len())
+    }
+
+    /// Returns whether the claim code with the given `code_hash` can still be used
+    /// to claim a `Token` of the `Collectible` identified by `gate_id`.
+    pub fn is_claim_code_available(
+        &self,
+        gate_id: ValidGateId,
+        code_hash: Base58CryptoHash,
+    ) -> bool {
+        self.claim_codes
+            .get(gate_id.as_ref())
+            .map_or(false, |codes| codes.contains(&code_hash.into()))
+    }
+
+    /// Returns how many `Token`s `account_id` has claimed out of the `Collectible` identified by `gate_id`,
+    /// including tokens already transferred or burned.
+    pub fn get_claims_by_account(&self, gate_id: ValidGateId, account_id: ValidAccountId) -> u16 {
+        self.claims_by_account.get(&(gate_id.into(), account_id.into())).unwrap_or(0)
+    }
+
+    /// Claims a `Token` for the `Collectible` indicated by `gate_id`.
+    /// The claim is on behalf the `predecessor_account_id`.
+    /// Returns a `TokenId` that represents this claim.
+    /// The new `Token` gets the next `edition` number of `gate_id`, starting from `1`.
+    /// If the given `gate_id` has exhausted its supply, this call will panic.
+    /// The same applies when claims of `gate_id` have not started yet or have already ended.
+    /// If the given `gate_id` has an `allowlist_root`, use `claim_token_with_proof` instead.
+    /// If it has claim codes, use `claim_with_code` instead.
+    /// If it has a raffle, only its winners can claim, see `create_raffle`.
+    /// If the `predecessor_account_id` has already claimed `max_claims_per_account` tokens,
+    /// this call will panic as well.
+    ///
+    /// The caller must attach the `mint_price` of the collectible,
+    /// plus enough deposit to cover the storage used by the new token.
+    /// The `mint_price` is split between the `creator_id` and
+    /// the `mintgate_fee_account_id` according to `mintgate_fee`.
+    /// Any excess of the attached deposit is refunded.
+    ///
+    /// See <https://github.com/epam/mintgate/issues/6>.
+    #[payable]
+    pub fn claim_token(&mut self, gate_id: ValidGateId) -> TokenId {
+        let gate_id = gate_id.to_string();
+
+        match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(collectible) => {
+                if collectible.allowlist_root.is_some() {
+                    Panic::AllowlistProofRequired { gate_id }.panic();
+                }
+                if self.claim_codes.contains_key(&gate_id) {
+                    Panic::ClaimCodeRequired { gate_id }.panic();
+                }
+
+                let initial_storage_usage = env::storage_usage();
+                self.claim(collectible, initial_storage_usage)
+            }
+        }
+    }
+
+    /// Claims a `Token` for the `Collectible` indicated by `gate_id`,
+    /// proving the `predecessor_account_id` is in its allowlist.
+    /// The `proof` is the list of sibling hashes from the account leaf up to the `allowlist_root`,
+    /// as built by `mg_core::merkle::MerkleTree`.
+    /// Each account in the allowlist can use its proof only once.
+    ///
+    /// Otherwise, the same rules of `claim_token` apply.
+    #[payable]
+    pub fn claim_token_with_proof(
+        &mut self,
+        gate_id: ValidGateId,
+        proof: Vec<Base58CryptoHash>,
+    ) -> TokenId {
+        let gate_id = gate_id.to_string();
+
+        match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(collectible) => {
+                if self.claim_codes.contains_key(&gate_id) {
+                    Panic::ClaimCodeRequired { gate_id }.panic();
+                }
+
+                let initial_storage_usage = env::storage_usage();
+
+                if let Some(allowlist_root) = collectible.allowlist_root {
+                    let account_id = env::predecessor_account_id();
+                    let proof = proof.into_iter().map(Into::into).collect::<Vec<CryptoHash>>();
+                    if !merkle::verify_proof(&allowlist_root.into(), &account_id, &proof) {
+                        Panic::InvalidAllowlistProof { account_id, gate_id }.panic();
+                    }
+                    if !self.allowlist_claims.insert(&(gate_id.clone(), account_id.clone())) {
+                        Panic::AllowlistProofAlreadyUsed { account_id, gate_id }.panic();
+                    }
+                }
+
+                self.claim(collectible, initial_storage_usage)
+            }
+        }
+    }
+
+    /// Claims a `Token` for the `Collectible` indicated by `gate_id`,
+    /// using one of the claim codes added with `add_claim_codes`.
+    /// The `code` is used up, so it cannot be used again.
+    ///
+    /// Otherwise, the same rules of `claim_token` apply,
+    /// except that the `allowlist_root` of the collectible is not checked.
+    #[payable]
+    pub fn claim_with_code(&mut self, gate_id: ValidGateId, code: String) -> TokenId {
+        let gate_id = gate_id.to_string();
+        let collectible = self.get_collectible_or_panic(&gate_id);
+
+        let initial_storage_usage = env::storage_usage();
+
+        let used = self.claim_codes.get(&gate_id).map_or(false, |mut codes| {
+            let removed = codes.remove(&crypto_hash(&code));
+            self.claim_codes.insert(&gate_id, &codes);
+            removed
+        });
+        if !used {
+            Panic::InvalidClaimCode { gate_id }.panic();
+        }
+
+        self.claim(collectible, initial_storage_usage)
+    }
+
+    /// Creates a raffle for the `Collectible` identified by `gate_id`.
+    /// Accounts can enter the raffle with `enter_raffle` until `ends_at`,
+    /// as a UNIX epoch datetime in milliseconds, attaching the refundable `entry_deposit`.
+    /// After that, its winners are drawn with `draw_raffle`.
+    /// Once a collectible has a raffle, only its winners can claim tokens,
+    /// and the rest of the rules of `claim_token` still apply.
+    /// Neither `mint_to`, `redeem_voucher` nor `add_linkdrop` can mint its tokens anymore,
+    /// so the supply drawn for the winners remains available to them.
+    ///
+    /// Only the `creator_id` of the collectible is allowed to create a raffle.
+    ///
+    /// The caller must attach enough deposit to cover the storage used by the raffle.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn create_raffle(&mut self, gate_id: ValidGateId, ends_at: Timestamp, entry_deposit: U128) {
+        let gate_id = gate_id.to_string();
+        let collectible = self.get_collectible_or_panic(&gate_id);
+
+        let pred_id = env::predecessor_account_id();
+        if pred_id != collectible.creator_id {
+            Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
+        }
+        if self.raffles.contains_key(&gate_id) {
+            Panic::RaffleAlreadyExists { gate_id }.panic();
+        }
+        let now = env::block_timestamp() / 1_000_000;
+        if ends_at <= now {
+            Panic::InvalidArgument {
+                gate_id,
+                reason: "`ends_at` must be in the future".to_string(),
+            }
+            .panic();
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        let raffle = Raffle { ends_at, entry_deposit, entries: 0, winners: None, drawn: 0 };
+        self.raffles.insert(&gate_id, &raffle);
+
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Returns the raffle of the `Collectible` identified by `gate_id`, if any.
+    pub fn get_raffle(&self, gate_id: ValidGateId) -> Option<Raffle> {
+        self.raffles.get(gate_id.as_ref())
+    }
+
+    /// Returns the entry of `account_id` in the raffle of the `Collectible` identified by `gate_id`,
+    /// as long as its deposit has been neither claimed nor withdrawn.
+    pub fn get_raffle_entry(
+        &self,
+        gate_id: ValidGateId,
+        account_id: ValidAccountId,
+    ) -> Option<RaffleEntry> {
+        self.raffle_entries.get(&(gate_id.into(), account_id.into()))
+    }
+
+    /// Enters the `predecessor_account_id` into the raffle of the `Collectible` identified by `gate_id`.
+    /// Each account can enter a raffle only once, and before it ends.
+    ///
+    /// The caller must attach the `entry_deposit` of the raffle,
+    /// plus enough deposit to cover the storage used by the entry.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn enter_raffle(&mut self, gate_id: ValidGateId) {
+        self.assert_not_paused(Pausable::Minting);
+
+        let gate_id = gate_id.to_string();
+        let mut raffle = self.get_raffle_or_panic(&gate_id);
+
+        let now = env::block_timestamp() / 1_000_000;
+        if now >= raffle.ends_at {
+            Panic::RaffleEnded { gate_id, ends_at: raffle.ends_at }.panic();
+        }
+
+        let account_id = env::predecessor_account_id();
+        let attached_deposit = env::attached_deposit();
+        if attached_deposit < raffle.entry_deposit.0 {
+            Panic::NotEnoughDepositForRaffleEntry {
+                attached_deposit: attached_deposit.into(),
+                entry_deposit: raffle.entry_deposit,
+            }
+            .panic();
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        let entry_key = (gate_id.clone(), account_id.clone());
+        if self.raffle_entries.insert(&entry_key, &RaffleEntry::Entered).is_some() {
+            Panic::RaffleAlreadyEntered { account_id, gate_id }.panic();
+        }
+        let mut entrants = self.raffle_entrants.get(&gate_id).unwrap_or_else(|| {
+            Vector::new(Keys::RaffleEntrantsValue { gate_id_hash: crypto_hash(&gate_id) })
+        });
+        entrants.push(&account_id);
+        self.raffle_entrants.insert(&gate_id, &entrants);
+
+        raffle.entries += 1;
+        self.raffles.insert(&gate_id, &raffle);
+
+        refund_deposit(initial_storage_usage, attached_deposit - raffle.entry_deposit.0);
+    }
+
+    /// Draws the winners of the raffle of the `Collectible` identified by `gate_id`,
+    /// using the random seed of the current block.
+    /// As many winners as entries are drawn, up to the `current_supply` of the collectible
+    /// when the draw starts.
+    /// Anyone can draw the winners once the raffle has ended.
+    ///
+    /// Each winner takes `GAS_FOR_DRAW` from the prepaid gas,
+    /// so the draw continues with the next call if the gas runs out.
+    /// Returns how many winners are left to be drawn.
+    pub fn draw_raffle(&mut self, gate_id: ValidGateId) -> u16 {
+        let gate_id = gate_id.to_string();
+        let mut raffle = self.get_raffle_or_panic(&gate_id);
+
+        let now = env::block_timestamp() / 1_000_000;
+        if now < raffle.ends_at {
+            Panic::RaffleNotEnded { gate_id, ends_at: raffle.ends_at }.panic();
+        }
+        if raffle.is_drawn() {
+            Panic::RaffleAlreadyDrawn { gate_id }.panic();
+        }
+
+        let winners = match raffle.winners {
+            Some(winners) => winners,
+            None => {
+                let collectible = self.get_collectible_or_panic(&gate_id);
+                std::cmp::min(collectible.current_supply as u64, raffle.entries) as u16
+            }
+        };
+
+        if let Some(mut entrants) = self.raffle_entrants.get(&gate_id) {
+            let seed = env::random_seed();
+            while raffle.drawn < winners && env::prepaid_gas() - env::used_gas() >= GAS_FOR_DRAW {
+                let i = raffle.drawn as u64;
+                let j = i + raffle::pick(&seed, i, raffle.entries - i);
+                let winner_id = entrants.get(j).unwrap();
+                if i != j {
+                    entrants.replace(j, &entrants.get(i).unwrap());
+                    entrants.replace(i, &winner_id);
+                }
+                self.raffle_entries.insert(&(gate_id.clone(), winner_id), &RaffleEntry::Won);
+                raffle.drawn += 1;
+            }
+        }
+
+        raffle.winners = Some(winners);
+        self.raffles.insert(&gate_id, &raffle);
+
+        winners - raffle.drawn
+    }
+
+    /// Withdraws the entry deposit of the `predecessor_account_id`
+    /// from the raffle of the `Collectible` identified by `gate_id`,
+    /// once all of its winners have been drawn.
+    /// Losers use it to get their deposit back.
+    /// Winners get their deposit back when claiming their token,
+    /// so a winner withdrawing its deposit gives up its token.
+    pub fn withdraw_raffle_entry(&mut self, gate_id: ValidGateId) {
+        let gate_id = gate_id.to_string();
+        let raffle = self.get_raffle_or_panic(&gate_id);
+        if !raffle.is_drawn() {
+            Panic::RaffleNotDrawn { gate_id }.panic();
+        }
+
+        let account_id = env::predecessor_account_id();
+        if self.raffle_entries.remove(&(gate_id.clone(), account_id.clone())).is_none() {
+            Panic::RaffleEntryNotFound { account_id, gate_id }.panic();
+        }
+
+        if raffle.entry_deposit.0 > 0 {
+            Promise::new(account_id).transfer(raffle.entry_deposit.0);
+        }
+    }
+
+    /// Mints a `Token` of the `Collectible` identified by `gate_id` to each account in `receiver_ids`.
+    /// An account can be listed more than once to receive several tokens.
+    /// Returns the `TokenId`s minted, in the same order as `receiver_ids`.
+    ///
+    /// Only the `creator_id` of the collectible or an `Admin` can mint tokens this way.
+    /// Unlike `claim_token`, neither the `mint_price`, the `allowlist_root`,
+    /// the claim window nor `max_claims_per_account` apply,
+    /// and the minted tokens do not count as claims of their receivers.
+    /// Tokens cannot be minted this way once the collectible has a raffle.
+    ///
+    /// At most `MAX_MINT_TO_LEN` tokens can be minted in a single call,
+    /// or fewer when the prepaid gas does not cover `GAS_FOR_MINT` for each of them.
+    ///
+    /// The caller must attach enough deposit to cover the storage used by the new tokens.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn mint_to(
+        &mut self,
+        gate_id: ValidGateId,
+        receiver_ids: Vec<ValidAccountId>,
+    ) -> Vec<TokenId> {
+        self.assert_not_paused(Pausable::Minting);
+
+        let gate_id = gate_id.to_string();
+        let mut collectible = self.get_collectible_or_panic(&gate_id);
+
+        let pred_id = env::predecessor_account_id();
+        if pred_id != collectible.creator_id && !self.has_role(&pred_id, Role::Admin) {
+            Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
+        }
+        self.assert_no_raffle(&gate_id);
+
+        let len = receiver_ids.len() as u64;
+        let max_len =
+            std::cmp::min(MAX_MINT_TO_LEN, (env::prepaid_gas() - env::used_gas()) / GAS_FOR_MINT);
+        if len > max_len {
+            Panic::MintToTooLong { len, max_len }.panic();
+        }
+        if len > collectible.current_supply as u64 {
+            Panic::NotEnoughSupply { gate_id, current_supply: collectible.current_supply }.panic();
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let now = env::block_timestamp() / 1_000_000;
+
+        let mut token_ids = Vec::new();
+        let mut mints: Vec<NftMintData> = Vec::new();
+        for receiver_id in receiver_ids {
+            let owner_id = AccountId::from(receiver_id);
+            let token_id = self.mint_token(&mut collectible, &owner_id, now);
+            token_ids.push(token_id);
+
+            match mints.iter().position(|data| data.owner_id == owner_id) {
+                Some(i) => mints[i].token_ids.push(token_id),
+                None => mints.push(NftMintData { owner_id, token_ids: vec![token_id], memo: None }),
+            }
+        }
+        self.collectibles.insert(&gate_id, &collectible);
+
+        refund_storage_deposit(initial_storage_usage);
+
+        if !mints.is_empty() {
+            Nep171Event::NftMint(mints).emit();
+        }
+
+        token_ids
+    }
+
+    /// Sets the ed25519 `public_key` the `predecessor_account_id` signs its vouchers with,
+    /// so tokens of its collectibles can be minted with `redeem_voucher`.
+    /// Vouchers signed with a former key can no longer be redeemed.
+    /// A `public_key` of `null` removes the key.
+    ///
+    /// The caller must attach enough deposit to cover any additional storage used.
+    /// Any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn set_voucher_key(&mut self, public_key: Option<Base58PublicKey>) {
+        let account_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+
+        match public_key {
+            None => {
+                self.voucher_keys.remove(&account_id);
+            }
+            Some(public_key) => {
+                if public_key.0.len() != 33 || public_key.0[0] != ED25519_CURVE {
+                    Panic::InvalidVoucherKey.panic();
+                }
+                self.voucher_keys.insert(&account_id, &public_key);
+            }
+        }
+
+        refund_storage_deposit(initial_storage_usage);
+    }
+
+    /// Returns the ed25519 key `account_id` signs its vouchers with, if any.
+    pub fn get_voucher_key(&self, account_id: ValidAccountId) -> Option<Base58PublicKey> {
+        self.voucher_keys.get(account_id.as_ref())
+    }
+
+    /// Returns whether a voucher of `creator_id` with the given `nonce` has already been redeemed.
+    pub fn is_voucher_redeemed(&self, creator_id: ValidAccountId, nonce: U64) -> bool {
+        self.redeemed_vouchers.contains(&(creator_id.into(), nonce.0))
+    }
+
+    /// Mints a `Token` of the `Collectible` identified by `voucher.gate_id`
+    /// to the `predecessor_account_id`, redeeming a `voucher` issued off-chain by its `creator_id`.
+    /// The `signature` is made with the voucher key of the creator, see `set_voucher_key`,
+    /// over the Borsh serialization of this contract account ID followed by `voucher`,
+    /// as `mg_core::voucher::VoucherSigner` does.
+    ///
+    /// The voucher can only be redeemed by its `receiver_id`, if any, and before its `expires_at`.
+    /// Each `nonce` of a creator can be redeemed only once.
+    /// If the collectible does not exist yet, it is created out of the `collectible` of the voucher,
+    /// whose `creator_id` signs the voucher and must hold the `CollectibleMinter` role.
+    /// Unlike `claim_token`, neither the `mint_price`, the `allowlist_root`,
+    /// the claim window nor `max_claims_per_account` apply,
+    /// and the minted token does not count as a claim of its receiver.
+    /// Vouchers cannot be redeemed once the collectible has a raffle.
+    ///
+    /// The caller must attach the `price` of the voucher,
+    /// plus enough deposit to cover the storage used by the new token, and collectible if created.
+    /// The `price` is split as the `mint_price` is in `claim_token`,
+    /// and any excess of the attached deposit is refunded.
+    #[payable]
+    pub fn redeem_voucher(&mut self, voucher: Voucher, signature: Base64VecU8) -> TokenId {
+        self.assert_not_paused(Pausable::Minting);
+
+        let gate_id = voucher.gate_id.to_string();
+        let collectible = self.collectibles.get(&gate_id);
+
+        let creator_id = match (&collectible, &voucher.collectible) {
+            (Some(collectible), _) => collectible.creator_id.clone(),
+            (None, Some(data)) => data.creator_id.clone(),
+            (None, None) => Panic::GateIdNotFound { gate_id }.panic(),
+        };
+        let public_key = match self.voucher_keys.get(&creator_id) {
+            None => Panic::VoucherKeyNotFound { account_id: creator_id }.panic(),
+            Some(public_key) => public_key,
+        };
+        if !voucher.verify(&env::current_account_id(), &public_key.0[1..], &signature.0) {
+            Panic::InvalidVoucherSignature { gate_id }.panic();
+        }
+
+        let owner_id = env::predecessor_account_id();
+        if voucher.receiver_id.as_ref().map_or(false, |receiver_id| receiver_id != &owner_id) {
+            Panic::VoucherNotForAccount { account_id: owner_id, gate_id }.panic();
+        }
+        let now = env::block_timestamp() / 1_000_000;
+        if now >= voucher.expires_at {
+            Panic::VoucherExpired { gate_id, expires_at: voucher.expires_at }.panic();
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        let mut collectible = match collectible {
+            Some(collectible) => collectible,
+            None => self.create_voucher_collectible(gate_id.clone(), voucher.collectible.unwrap()),
+        };
+        self.assert_no_raffle(&gate_id);
+        if collectible.current_supply == 0 {
+            Panic::GateIdExhausted { gate_id }.panic()
+        }
+
+        let attached_deposit = env::attached_deposit();
+        let price = voucher.price.0;
+        if attached_deposit < price {
+            Panic::NotEnoughDepositForMintPrice {
+                attached_deposit: attached_deposit.into(),
+                mint_price: voucher.price,
+            }
+            .panic();
+        }
+
+        if !self.redeemed_vouchers.insert(&(creator_id.clone(), voucher.nonce.0)) {
+            Panic::VoucherAlreadyRedeemed { nonce: voucher.nonce, creator_id }.panic();
+        }
+
+        let token_id = self.mint_token(&mut collectible, &owner_id, now);
+        self.collectibles.insert(&gate_id, &collectible);
+
+        self.pay_mint_price(&creator_id, price);
+        refund_deposit(initial_storage_usage, attached_deposit - price);
+
+        Nep171Event::NftMint(vec![NftMintData { owner_id, token_ids: vec![token_id], memo: None }])
+            .emit();
+
+        token_id
+    }
+
+    /// Funds a linkdrop of a new `Token` of the `Collectible` identified by `gate_id`.
+    /// The token is minted right away, and this contract holds it until the linkdrop is claimed.
+    ///
+    /// The given `public_key` is added as a function-call access key of this contract,
+    /// only allowed to call `claim_linkdrop` and `create_account_and_claim_linkdrop`.
+    /// The secret key of `public_key` is then shared with the claimer, *e.g.*, as a link,
+    /// so accounts without any NEAR can claim the token.
+    /// The linkdrop can only be claimed once its access key has been added.
+    /// If it cannot be added, *e.g.*, because this contract already holds `public_key`,
+    /// the token and the attached deposit are given back to the funder.
+    /// The returned promise resolves to the `TokenId` of the new token,
+    /// or `null` when the access key could not be added.
+    ///
+    /// Only the `creator_id` of the collectible or an `Admin` can fund linkdrops.
+    /// Like `mint_to`, neither the `mint_price`, the `allowlist_root`,
+    /// the claim window nor `max_claims_per_account` apply,
+    /// and linkdrops cannot be funded once the collectible has a raffle.
+    ///
+    /// The caller must attach enough deposit to cover the storage used by the new token and linkdrop,
+    /// plus `LINKDROP_ALLOWANCE` to pay for the gas of the claim.
+    /// The rest of the attached deposit is given to the claimer along with the token.
+    #[payable]
+    pub fn add_linkdrop(&mut self, gate_id: ValidGateId, public_key: Base58PublicKey) -> Promise {
+        self.assert_not_paused(Pausable::Minting);
+
+        let gate_id = gate_id.to_string();
+        let mut collectible = self.get_collectible_or_panic(&gate_id);
+
+        let funder_id = env::predecessor_account_id();
+        if funder_id != collectible.creator_id && !self.has_role(&funder_id, Role::Admin) {
+            Panic::NotCollectibleCreator { account_id: funder_id, gate_id }.panic();
+        }
+        if self.linkdrops.contains_key(&public_key.0)
+            || self.pending_linkdrops.contains_key(&public_key.0)
+        {
+            Panic::LinkdropAlreadyExists.panic();
+        }
+        self.assert_no_raffle(&gate_id);
+        if collectible.current_supply == 0 {
+            Panic::GateIdExhausted { gate_id }.panic()
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let contract_id = env::current_account_id();
+        let now = env::block_timestamp() / 1_000_000;
+
+        let token_id = self.mint_token(&mut collectible, &contract_id, now);
+        self.collectibles.insert(&gate_id, &collectible);
+
+        let mut linkdrop = Linkdrop { token_id, funder_id, amount: U128(0) };
+        self.pending_linkdrops.insert(&public_key.0, &linkdrop);
+
+        let storage_usage = env::storage_usage() - initial_storage_usage;
+        let min_deposit =
+            Balance::from(storage_usage) * env::storage_byte_cost() + LINKDROP_ALLOWANCE;
+        let attached_deposit = env::attached_deposit();
+        if attached_deposit < min_deposit {
+            Panic::NotEnoughDepositForLinkdrop {
+                attached_deposit: attached_deposit.into(),
+                min_deposit: min_deposit.into(),
+            }
+            .panic();
+        }
+        linkdrop.amount = U128(attached_deposit - min_deposit);
+        self.pending_linkdrops.insert(&public_key.0, &linkdrop);
+
+        Nep171Event::NftMint(vec![NftMintData {
+            owner_id: contract_id.clone(),
+            token_ids: vec![token_id],
+            memo: None,
+        }])
+        .emit();
+
+        Promise::new(contract_id.clone())
+            .add_access_key(
+                public_key.0.clone(),
+                LINKDROP_ALLOWANCE,
+                contract_id.clone(),
+                b"claim_linkdrop,create_account_and_claim_linkdrop".to_vec(),
+            )
+            .then(self_callback::resolve_add_linkdrop(
+                public_key,
+                &contract_id,
+                NO_DEPOSIT,
+                GAS_FOR_RESOLVE_ADD_LINKDROP,
+            ))
+    }
+
+    /// Returns the linkdrop funded for `public_key`, if its access key has been added
+    /// and it has not been claimed yet.
+    pub fn get_linkdrop(&self, public_key: Base58PublicKey) -> Option<Linkdrop> {
+        self.linkdrops.get(&public_key.0)
+    }
+
+    /// Claims the linkdrop of the signer public key into the existing `account_id`,
+    /// giving it both the token and the linkdrop `amount`.
+    /// The access key of the linkdrop is deleted, so it cannot be claimed again.
+    ///
+    /// It must be signed with the access key of the linkdrop.
+    #[private]
+    pub fn claim_linkdrop(&mut self, account_id: ValidAccountId) -> TokenId {
+        self.assert_not_paused(Pausable::Minting);
+
+        let public_key = env::signer_account_pk();
+        let linkdrop = self.take_linkdrop(&public_key);
+
+        let account_id = AccountId::from(account_id);
+        self.deliver_linkdrop_token(linkdrop.token_id, &account_id);
+        if linkdrop.amount.0 > 0 {
+            Promise::new(account_id).transfer(linkdrop.amount.0);
+        }
+        Promise::new(env::current_account_id()).delete_key(public_key);
+
+        linkdrop.token_id
+    }
+
+    /// Claims the linkdrop of the signer public key into `new_account_id`,
+    /// a new account with `new_public_key` as its full access key.
+    /// The new account is funded with the linkdrop `amount`, which must cover its storage.
+    ///
+    /// The `new_account_id` must be either a sub-account of this contract,
+    /// or a sub-account of a top-level account, *e.g.*, `testnet` or `near`,
+    /// which is then created by the linkdrop contract deployed there.
+    /// Once the account is created, it gets the token and the access key of the linkdrop is deleted.
+    /// If the account cannot be created, the linkdrop can be claimed again.
+    ///
+    /// It must be signed with the access key of the linkdrop.
+    #[private]
+    pub fn create_account_and_claim_linkdrop(
+        &mut self,
+        new_account_id: ValidAccountId,
+        new_public_key: Base58PublicKey,
+    ) -> Promise {
+        self.assert_not_paused(Pausable::Minting);
+
+        let public_key = env::signer_account_pk();
+        let linkdrop = self.take_linkdrop(&public_key);
+
+        let contract_id = env::current_account_id();
+        let new_account_id = AccountId::from(new_account_id);
+        let amount = linkdrop.amount.0;
+        let create_account = if new_account_id.ends_with(&format!(".{}", contract_id)) {
+            Promise::new(new_account_id.clone())
+                .create_account()
+                .add_full_access_key(new_public_key.0)
+                .transfer(amount)
+        } else {
+            let top_level_id = new_account_id.rsplit('.').next().unwrap().to_string();
+            if top_level_id == new_account_id {
+                Panic::InvalidNewAccountId { account_id: new_account_id }.panic();
+            }
+            linkdrop::registrar::create_account(
+                new_account_id.clone(),
+                new_public_key,
+                &top_level_id,
+                amount,
+                GAS_FOR_CREATE_ACCOUNT,
+            )
+        };
+
+        create_account.then(self_callback::resolve_linkdrop_account(
+            Base58PublicKey(public_key),
+            linkdrop,
+            new_account_id,
+            &contract_id,
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_LINKDROP_ACCOUNT,
+        ))
+    }
+
+    /// Removes the linkdrop funded for `public_key` before it is claimed,
+    /// giving back the token and the linkdrop `amount` to its `funder_id`.
+    /// The access key of the linkdrop is deleted as well.
+    ///
+    /// Only the `funder_id` of the linkdrop can remove it.
+    pub fn remove_linkdrop(&mut self, public_key: Base58PublicKey) {
+        let linkdrop = match self.linkdrops.get(&public_key.0) {
+            None => Panic::LinkdropNotFound.panic(),
+            Some(linkdrop) => linkdrop,
+        };
+        let account_id = env::predecessor_account_id();
+        if account_id != linkdrop.funder_id {
+            Panic::NotLinkdropFunder { account_id }.panic();
+        }
+
+        self.linkdrops.remove(&public_key.0);
+        self.deliver_linkdrop_token(linkdrop.token_id, &account_id);
+        if linkdrop.amount.0 > 0 {
+            Promise::new(account_id).transfer(linkdrop.amount.0);
+        }
+        Promise::new(env::current_account_id()).delete_key(public_key.0);
+    }
+
+    /// Burns (deletes) the `Token` identifed by `token_id`.
+    /// Only the `owner_id` can burn the token.
+    ///
+    /// After succefully delete the token,
+    /// a cross-contract call  is made to `nft_on_revoke` for each approval
+    /// to delist from their marketplaces.
+    pub fn burn_token(&mut self, token_id: TokenId) {
+        let token = self.get_token_or_panic(token_id);
+        let gate_id = token.gate_id;
+
+        match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(mut collectible) => {
+                let owner_id = env::predecessor_account_id();
+                self.delete_token_from(token_id, &owner_id);
+
+                if let Some(copies) = collectible.metadata.copies {
+                    collectible.metadata.copies = Some(copies - 1);
+                }
+
+                let mut i = 0;
+                for tid in &collectible.minted_tokens {
+                    if tid == &token_id {
+                        collectible.minted_tokens.remove(i);
+                        break;
+                    }
+
+                    i += 1;
+                }
+                self.collectibles.insert(&gate_id, &collectible);
+
+                Nep171Event::NftBurn(vec![NftBurnData {
+                    authorized_id: None,
+                    owner_id,
+                    token_ids: vec![token_id],
+                    memo: None,
+                }])
+                .emit();
+
+                revoke_approvals(token_id, &token.approvals, None);
+            }
+        }
+    }
+
+    /// Returns all `Token`s owned by `owner_id`.
+    pub fn get_tokens_by_owner(&self, owner_id: ValidAccountId) -> Vec<Token> {
+        match self.tokens_by_owner.get(owner_id.as_ref()) {
+            None => Vec::new(),
+            Some(list) => list
+                .iter()
+                .map(|token_id| {
+                    let token = self.get_token(token_id).expect("Token not found");
+                    assert!(token.token_id == token_id);
+                    assert!(&token.owner_id == owner_id.as_ref());
+                    token
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns all tokens claimed by `owner_id` belonging to `gate_id`.
+    ///
+    /// See <https://github.com/epam/mintgate/issues/14>.
+    pub fn get_tokens_by_owner_and_gate_id(
+        &self,
+        gate_id: ValidGateId,
+        owner_id: ValidAccountId,
+    ) -> Vec<Token> {
+        let gate_id = gate_id.to_string();
+
+        match self.tokens_by_owner.get(owner_id.as_ref()) {
+            None => Vec::new(),
+            Some(list) => list
+                .iter()
+                .map(|token_id| {
+                    let token = self.get_token(token_id).expect("Token not found");
+                    assert!(token.token_id == token_id);
+                    assert!(&token.owner_id == owner_id.as_ref());
+                    token
+                })
+                .filter(|token| token.gate_id == gate_id)
+                .collect(),
+        }
+    }
+
+    /// Returns the `TokenId`s owned by `account_id` that grant access to the content gated by `gate_id`.
+    /// A token grants access as long as the `expires_at` of its collectible has not passed,
+    /// even after claims have ended.
+    /// An empty list means `account_id` has no access.
+    ///
+    /// Only the tokens owned by `account_id` are visited,
+    /// so it does not depend on the supply of `gate_id`.
+    pub fn has_access(&self, account_id: ValidAccountId, gate_id: ValidGateId) -> Vec<TokenId> {
+        let gate_id = gate_id.to_string();
+
+        let collectible = match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(collectible) => collectible,
+        };
+        let now = env::block_timestamp() / 1_000_000;
+        if collectible.metadata.expires_at.map_or(false, |expires_at| now >= expires_at) {
+            return Vec::new();
+        }
+
+        match self.tokens_by_owner.get(account_id.as_ref()) {
+            None => Vec::new(),
+            Some(list) => list
+                .iter()
+                .filter(|token_id| {
+                    let token = self.tokens.get(token_id).expect("Token not found");
+                    token.gate_id == gate_id
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the current royalty and fee parameters of this contract.
+    pub fn get_config(&self) -> NftConfig {
+        NftConfig {
+            min_royalty: self.min_royalty,
+            max_royalty: self.max_royalty,
+            mintgate_fee: self.mintgate_fee,
+            mintgate_fee_account_id: self.mintgate_fee_account_id.clone(),
+        }
+    }
+
+    /// Updates the `min_royalty` and `max_royalty` allowed when creating new collectibles.
+    /// Existing collectibles keep their `royalty`.
+    /// Only accounts holding the `Admin` role can update them.
+    ///
+    /// The same rules of `init` apply.
+    /// Moreover, the sum of `max_royalty` and `mintgate_fee` should be less than `1`.
+    pub fn set_royalty_bounds(&mut self, min_royalty: Fraction, max_royalty: Fraction) {
+        self.assert_role(Role::Admin);
+
+        check_royalty_bounds(min_royalty, max_royalty);
+        check_royalty_with_fee(max_royalty, self.mintgate_fee);
+
+        self.min_royalty = min_royalty;
+        self.max_royalty = max_royalty;
+
+        MintGateEvent::ConfigUpdate(vec![self.get_config()]).emit();
+    }
+
+    /// Updates the `mintgate_fee` paid on each sale and the `mintgate_fee_account_id` receiving it.
+    /// Only accounts holding the `Admin` role can update them.
+    ///
+    /// The sum of `max_royalty` and `mintgate_fee` should be less than `1`.
+    /// Likewise, the sum of the total royalty of any existing collectible and `mintgate_fee`
+    /// should be less than `1`, even if it was created under a greater `max_royalty`.
+    /// This is to be able to make payouts all participants.
+    pub fn set_mintgate_fee(
+        &mut self,
+        mintgate_fee: Fraction,
+        mintgate_fee_account_id: ValidAccountId,
+    ) {
+        self.assert_role(Role::Admin);
+
+        mintgate_fee.check();
+        check_royalty_with_fee(self.max_royalty, mintgate_fee);
+        if self.highest_royalty + mintgate_fee.mult(ROYALTY_SCALE) >= ROYALTY_SCALE {
+            Panic::MintgateFeeTooLarge { mintgate_fee }.panic();
+        }
+
+        self.mintgate_fee = mintgate_fee;
+        self.mintgate_fee_account_id = mintgate_fee_account_id.into();
+
+        MintGateEvent::ConfigUpdate(vec![self.get_config()]).emit();
+    }
+
+    /// Grants `role` to `account_id`.
+    /// Only the owner can grant the `Admin` role,
+    /// whereas any `Admin` can grant the remaining roles.
+    pub fn grant_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_role_manager(role);
+
+        let mut holders =
+            self.roles.get(&role).unwrap_or_else(|| UnorderedSet::new(Keys::RolesValue { role }));
+        if holders.insert(account_id.as_ref()) {
+            self.roles.insert(&role, &holders);
+
+            MintGateEvent::RoleGrant(vec![RoleData { account_id: account_id.into(), role }]).emit();
+        }
+    }
+
+    /// Revokes `role` from `account_id`.
+    /// The same rules of `grant_role` apply.
+    pub fn revoke_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_role_manager(role);
+
+        if let Some(mut holders) = self.roles.get(&role) {
+            if holders.remove(account_id.as_ref()) {
+                self.roles.insert(&role, &holders);
+
+                MintGateEvent::RoleRevoke(vec![RoleData { account_id: account_id.into(), role }])
+                    .emit();
+            }
+        }
+    }
+
+    /// Transfers the ownership of this contract to `new_owner_id`.
+    /// Only the current owner can transfer the ownership.
+    pub fn transfer_ownership(&mut self, new_owner_id: ValidAccountId) {
+        let owner_id = self.assert_role(Role::Owner);
+        self.owner_id = new_owner_id.into();
+
+        MintGateEvent::RoleRevoke(vec![RoleData { account_id: owner_id, role: Role::Owner }])
+            .emit();
+        MintGateEvent::RoleGrant(vec![RoleData {
+            account_id: self.owner_id.clone(),
+            role: Role::Owner,
+        }])
+        .emit();
+    }
+
+    /// Returns the accounts explicitly holding `role`.
+    /// Note that the owner holds every role, but it is listed only for the `Owner` role.
+    pub fn get_role_holders(&self, role: Role) -> Vec<AccountId> {
+        match role {
+            Role::Owner => vec![self.owner_id.clone()],
+            role => self.roles.get(&role).map_or_else(Vec::new, |holders| holders.to_vec()),
+        }
+    }
+
+    /// Pauses `feature` in case of an emergency, until it is unpaused.
+    /// This contract checks the `minting` and `transfers` features,
+    /// *i.e.*, claiming tokens, and transferring or approving tokens respectively.
+    /// View methods are never paused.
+    /// Revoking approvals is deliberately not paused with `transfers`,
+    /// so owners can still delist their tokens from marketplaces during an emergency.
+    /// Only accounts holding the `Pauser` role can pause.
+    pub fn pause(&mut self, feature: Pausable) {
+        let account_id = self.assert_role(Role::Pauser);
+
+        if !self.paused.contains(&feature) {
+            self.paused.push(feature);
+
+            MintGateEvent::Pause(vec![PauseData { account_id, feature }]).emit();
+        }
+    }
+
+    /// Unpauses `feature` previously paused with `pause`.
+    /// Only accounts holding the `Pauser` role can unpause.
+    pub fn unpause(&mut self, feature: Pausable) {
+        let account_id = self.assert_role(Role::Pauser);
+
+        if self.paused.contains(&feature) {
+            self.paused.retain(|paused| paused != &feature);
+
+            MintGateEvent::Unpause(vec![PauseData { account_id, feature }]).emit();
+        }
+    }
+
+    /// Returns the features currently paused.
+    pub fn get_paused(&self) -> Vec<Pausable> {
+        self.paused.clone()
+    }
+
+    /// Ensures `feature` is not paused.
+    fn assert_not_paused(&self, feature: Pausable) {
+        if self.paused.contains(&feature) {
+            Panic::Paused { feature }.panic();
+        }
+    }
+
+    /// Returns whether `account_id` holds `role`, either explicitly or implied by another role.
+    fn has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        if account_id == &self.owner_id {
+            return true;
+        }
+
+        let holds =
+            |role| self.roles.get(&role).map_or(false, |holders| holders.contains(account_id));
+        match role {
+            Role::Owner => false,
+            Role::Admin => holds(Role::Admin),
+            role => holds(Role::Admin) || holds(role),
+        }
+    }
+
+    /// Ensures the `predecessor_account_id` holds `role`.
+    /// Returns the `predecessor_account_id` when it does, panics otherwise.
+    fn assert_role(&self, role: Role) -> AccountId {
+        let account_id = env::predecessor_account_id();
+        if !self.has_role(&account_id, role) {
+            Panic::Unauthorized { account_id, role }.panic();
+        }
+        account_id
+    }
+
+    /// Ensures the `predecessor_account_id` is allowed to grant and revoke `role`.
+    fn assert_role_manager(&self, role: Role) {
+        match role {
+            Role::Owner => Panic::OwnerRoleNotGrantable.panic(),
+            Role::Admin => self.assert_role(Role::Owner),
+            _ => self.assert_role(Role::Admin),
+        };
+    }
+
+    /// Returns the `Collectible` identified by `gate_id`,
+    /// ensuring its metadata can be updated by the `predecessor_account_id`.
+    fn get_updatable_collectible(&self, gate_id: GateId) -> Collectible {
+        match self.collectibles.get(&gate_id) {
+            None => Panic::GateIdNotFound { gate_id }.panic(),
+            Some(collectible) => {
+                let pred_id = env::predecessor_account_id();
+                if pred_id != collectible.creator_id && !self.has_role(&pred_id, Role::Admin) {
+                    Panic::NotCollectibleCreator { account_id: pred_id, gate_id }.panic();
+                }
+                if collectible.frozen {
+                    Panic::CollectibleFrozen { gate_id }.panic();
+                }
+                collectible
+            }
+        }
+    }
+
+    /// Mints a new `Token` out of `collectible` to the `predecessor_account_id`.
+    /// The attached deposit must cover the `mint_price` of `collectible`,
+    /// and the storage used since `initial_storage_usage`.
+    fn claim(
+        &mut self,
+        mut collectible: Collectible,
+        initial_storage_usage: StorageUsage,
+    ) -> TokenId {
+        self.assert_not_paused(Pausable::Minting);
+
+        let gate_id = collectible.gate_id.clone();
+        let now = env::block_timestamp() / 1_000_000;
+        match ClaimStatus::at(&collectible, now) {
+            ClaimStatus::Upcoming => Panic::ClaimNotStarted {
+                gate_id,
+                starts_at: collectible.metadata.starts_at.unwrap(),
+            }
+            .panic(),
+            ClaimStatus::Ended => Panic::ClaimEnded {
+                gate_id,
+                claims_end_at: ClaimStatus::ends_at(&collectible).unwrap(),
+            }
+            .panic(),
+            ClaimStatus::Live => {}
+        }
+        if collectible.current_supply == 0 {
+            Panic::GateIdExhausted { gate_id }.panic()
+        }
+
+        let attached_deposit = env::attached_deposit();
+        let mint_price = collectible.mint_price.0;
+        if attached_deposit < mint_price {
+            Panic::NotEnoughDepositForMintPrice {
+                attached_deposit: attached_deposit.into(),
+                mint_price: mint_price.into(),
+            }
+            .panic();
+        }
+
+        let owner_id = env::predecessor_account_id();
+        let mut entry_deposit = 0;
+        if let Some(raffle) = self.raffles.get(&gate_id) {
+            let entry_key = (gate_id.clone(), owner_id.clone());
+            if self.raffle_entries.remove(&entry_key) != Some(RaffleEntry::Won) {
+                Panic::NotRaffleWinner { account_id: owner_id, gate_id }.panic();
+            }
+            entry_deposit = raffle.entry_deposit.0;
+        }
+
+        let claims_key = (gate_id.clone(), owner_id.clone());
+        let claims = self.claims_by_account.get(&claims_key).unwrap_or(0);
+        if let Some(max_claims_per_account) = collectible.max_claims_per_account {
+            if claims >= max_claims_per_account {
+                Panic::ClaimLimitReached { account_id: owner_id, max_claims_per_account, gate_id }
+                    .panic();
+            }
+        }
+        self.claims_by_account.insert(&claims_key, &(claims + 1));
+
+        let token_id = self.mint_token(&mut collectible, &owner_id, now);
+        self.collectibles.insert(&gate_id, &collectible);
+
+        self.pay_mint_price(&collectible.creator_id, mint_price);
+        refund_deposit(initial_storage_usage, attached_deposit - mint_price + entry_deposit);
+
+        Nep171Event::NftMint(vec![NftMintData { owner_id, token_ids: vec![token_id], memo: None }])
+            .emit();
+
+        token_id
+    }
+
+    /// Mints the next `Token` of `collectible` to `owner_id`,
+    /// updating the supply and `minted_tokens` of `collectible`.
+    /// The caller must check there is supply left, and store `collectible` afterwards.
+    fn mint_token(
+        &mut self,
+        collectible: &mut Collectible,
+        owner_id: &AccountId,
+        now: Timestamp,
+    ) -> TokenId {
+        let token_id = U64::from(self.next_token_id);
+        self.next_token_id += 1;
+        let token = Token {
+            token_id,
+            gate_id: collectible.gate_id.clone(),
+            edition: collectible.supply - collectible.current_supply + 1,
+            owner_id: owner_id.clone(),
+            created_at: now,
+            modified_at: now,
+            approvals: HashMap::new(),
+            approval_counter: U64::from(0),
+            metadata: Metadata::default(),
+        };
+        self.insert_token(&token);
+
+        collectible.current_supply = collectible.current_supply - 1;
+        collectible.minted_tokens.push(token_id);
+        token_id
+    }
+
+    /// Gets the `Raffle` of the collectible with given `gate_id`.
+    /// Panics otherwise.
+    fn get_raffle_or_panic(&self, gate_id: &GateId) -> Raffle {
+        match self.raffles.get(gate_id) {
+            None => Panic::RaffleNotFound { gate_id: gate_id.clone() }.panic(),
+            Some(raffle) => raffle,
+        }
+    }
+
+    /// Creates the `Collectible` identified by `gate_id` out of the `data` of a voucher,
+    /// when the voucher is redeemed for the first time.
+    fn create_voucher_collectible(
+        &mut self,
+        gate_id: GateId,
+        data: VoucherCollectible,
+    ) -> Collectible {
+        if !self.has_role(&data.creator_id, Role::CollectibleMinter) {
+            Panic::Unauthorized { account_id: data.creator_id, role: Role::CollectibleMinter }
+                .panic();
+        }
+
+        let now = env::block_timestamp() / 1_000_000;
+        let metadata = Metadata {
+            title: Some(data.title),
+            description: Some(data.description),
+            media: data.media,
+            media_hash: data.media_hash,
+            copies: Some(data.supply),
+            issued_at: Some(now),
+            expires_at: None,
+            starts_at: Some(now),
+            updated_at: None,
+            extra: None,
+            reference: data.reference,
+            reference_hash: data.reference_hash,
+        };
+        let collectible =
+            new_collectible(data.creator_id, gate_id, data.supply, data.royalty, metadata);
+        self.check_new_collectible(&collectible);
+        self.insert_collectible(&collectible);
+        collectible
+    }
+
+    /// Panics when the collectible identified by `gate_id` has a raffle,
+    /// since only its winners can claim its tokens.
+    fn assert_no_raffle(&self, gate_id: &GateId) {
+        if self.raffles.contains_key(gate_id) {
+            Panic::RaffleWinnersOnly { gate_id: gate_id.clone() }.panic();
+        }
+    }
+
+    /// Removes and returns the linkdrop funded for `public_key`.
+    fn take_linkdrop(&mut self, public_key: &PublicKey) -> Linkdrop {
+        match self.linkdrops.remove(public_key) {
+            None => Panic::LinkdropNotFound.panic(),
+            Some(linkdrop) => linkdrop,
+        }
+    }
+
+    /// Transfers the `Token` of a linkdrop from this contract to `receiver_id`.
+    /// Unlike `nft_transfer`, the `transferability` of its collectible does not apply,
+    /// since the token is just being claimed.
+    fn deliver_linkdrop_token(&mut self, token_id: TokenId, receiver_id: &AccountId) {
+        let contract_id = env::current_account_id();
+        let mut token = self.get_token_or_panic(token_id);
+        self.delete_token_from(token_id, &contract_id);
+
+        token.owner_id = receiver_id.clone();
+        token.modified_at = env::block_timestamp() / 1_000_000;
+        self.insert_token(&token);
+
+        Nep171Event::NftTransfer(vec![NftTransferData {
+            authorized_id: None,
+            old_owner_id: contract_id,
+            new_owner_id: receiver_id.clone(),
+            token_ids: vec![token_id],
+            memo: None,
+        }])
+        .emit();
+    }
+
+    /// Pays the `mint_price` of a claimed token to `creator_id`,
+    /// keeping the `mintgate_fee` for the `mintgate_fee_account_id`.
+    fn pay_mint_price(&self, creator_id: &AccountId, mint_price: Balance) {
+        let fee_amount = self.mintgate_fee.mult(mint_price);
+        let creator_amount = mint_price - fee_amount;
+        if creator_amount > 0 {
+            Promise::new(creator_id.clone()).transfer(creator_amount);
+        }
+        if fee_amount > 0 {
+            Promise::new(self.mintgate_fee_account_id.clone()).transfer(fee_amount);
+        }
+    }
+
+    /// Ensures the new `collectible` is valid, as described in `create_collectible`.
+    fn check_new_collectible(&self, collectible: &Collectible) {
+        let gate_id = collectible.gate_id.clone();
+        let royalty = collectible.royalty;
+
+        royalty.check();
+
+        if royalty.cmp(&self.min_royalty) == Ordering::Less {
+            Panic::RoyaltyMinThanAllowed { royalty, gate_id }.panic();
+        }
+        if royalty.cmp(&self.max_royalty) == Ordering::Greater {
+            Panic::RoyaltyMaxThanAllowed { royalty, gate_id }.panic();
+        }
+        check_royalty_with_fee(royalty, self.mintgate_fee);
+        self.check_royalty_split(&gate_id, royalty, &collectible.royalty_split);
+        if self.collectibles.get(&gate_id).is_some() {
+            Panic::GateIdAlreadyExists { gate_id }.panic();
+        }
+        if collectible.supply == 0 {
+            Panic::ZeroSupplyNotAllowed { gate_id }.panic();
+        }
+        check_metadata(&gate_id, &collectible.metadata);
+        if let Some(starts_at) = collectible.metadata.starts_at {
+            if collectible.claims_end_at.map_or(false, |claims_end_at| starts_at >= claims_end_at) {
+                Panic::InvalidArgument {
+                    gate_id,
+                    reason: "`starts_at` must be before `claims_end_at`".to_string(),
+                }
+                .panic();
+            }
+            if collectible.metadata.expires_at.map_or(false, |expires_at| starts_at >= expires_at) {
+                Panic::InvalidArgument {
+                    gate_id,
+                    reason: "`starts_at` must be before `expires_at`".to_string(),
+                }
+                .panic();
+            }
+        }
+    }
+
+    /// Stores the new `collectible`, already checked with `check_new_collectible`.
+    fn insert_collectible(&mut self, collectible: &Collectible) {
+        self.collectibles.insert(&collectible.gate_id, &collectible);
+        self.highest_royalty = self
+            .highest_royalty
+            .max(total_royalty(collectible.royalty, &collectible.royalty_split));
+
+        let mut gids =
+            self.collectibles_by_creator.get(&collectible.creator_id).unwrap_or_else(|| {
+                UnorderedSet::new(Keys::CollectiblesByCreatorValue {
+                    creator_id_hash: crypto_hash(&collectible.creator_id),
+                })
+            });
+        gids.insert(&collectible.gate_id);
+
+        self.collectibles_by_creator.insert(&collectible.creator_id, &gids);
+
+        MintGateEvent::CollectibleCreate(vec![CollectibleCreateData {
+            creator_id: collectible.creator_id.clone(),
+            gate_id: collectible.gate_id.clone(),
+            supply: collectible.supply,
+        }])
+        .emit();
+    }
+
+    /// Ensures the `royalty_split` of `gate_id` is valid,
+    /// *i.e.*, its total royalty, including the creator's `royalty`, is within bounds.
+    fn check_royalty_split(
+        &self,
+        gate_id: &GateId,
+        royalty: Fraction,
+        royalty_split: &[RoyaltyShare],
+    ) {
+        if royalty_split.len() > MAX_ROYALTY_SPLIT_LEN {
+            Panic::InvalidArgument {
+                gate_id: gate_id.clone(),
+                reason: format!("`royalty_split` exceeds {} shares", MAX_ROYALTY_SPLIT_LEN),
+            }
+            .panic();
+        }
+
+        for share in royalty_split {
+            share.share.check();
+        }
+
+        let total = total_royalty(royalty, royalty_split);
+        if total > self.max_royalty.mult(ROYALTY_SCALE) {
+            Panic::RoyaltySplitMaxThanAllowed {
+                gate_id: gate_id.clone(),
+                max_royalty: self.max_royalty,
+            }
+            .panic();
+        }
+        if total + self.mintgate_fee.mult(ROYALTY_SCALE) >= ROYALTY_SCALE {
+            Panic::RoyaltySplitTooLarge {
+                gate_id: gate_id.clone(),
+                mintgate_fee: self.mintgate_fee,
+            }
+            .panic();
+        }
+    }
+
+    // pub fn get_token_by_id(&self, token_id: TokenId) -> Option<Token> {
+    //     self.get_token(token_id)
+    // }
+
+    /// Returns the token given by `token_id`.
+    /// Otherwise returns `None`.
+    fn get_token(&self, token_id: TokenId) -> Option<Token> {
+        match self.tokens.get(&token_id) {
+            None => None,
+            Some(mut token) => {
+                assert!(token.token_id == token_id);
+                let collectible = self.collectibles.get(&token.gate_id).expect("Gate id not found");
+                token.metadata = edition_metadata(collectible.metadata, token.edition);
+                Some(token)
+            }
+        }
+    }
+
+    /// Gets the `Token` with given `token_id`.
+    /// Panics otherwise.
+    fn get_token_or_panic(&self, token_id: TokenId) -> Token {
+        match self.get_token(token_id) {
+            None => Panic::TokenIdNotFound { token_id }.panic(),
+            Some(token) => token,
+        }
+    }
+
+    /// Gets the `Collectible` with given `gate_id`.
+    /// Panics otherwise.
+    fn get_collectible_or_panic(&self, gate_id: &GateId) -> Collectible {
+        match self.collectibles.get(gate_id) {
+            None => Panic::GateIdNotFound { gate_id: gate_id.clone() }.panic(),
+            Some(collectible) => collectible,
+        }
+    }
+
+    /// Inserts the given `Token` into `tokens` and `tokens_by_owner`.
+    fn insert_token(&mut self, token: &Token) {
+        self.tokens.insert(&token.token_id, token);
+
+        let mut tids = self.tokens_by_owner.get(&token.owner_id).unwrap_or_else(|| {
+            UnorderedSet::new(Keys::TokensByOwnerValue {
+                owner_id_hash: crypto_hash(&token.owner_id),
+            })
+        });
+        tids.insert(&token.token_id);
+
+        self.tokens_by_owner.insert(&token.owner_id, &tids);
+    }
+
+    /// Internal method to delete the corgi with `id` owned by `owner`.
+    /// Panics if `owner` does not own the corgi with `id`.
+    fn delete_token_from(&mut self, token_id: TokenId, owner_id: &AccountId) {
+        match self.tokens_by_owner.get(&owner_id) {
+            None => Panic::TokenIdNotOwnedBy { token_id, owner_id: owner_id.clone() }.panic(),
+            Some(mut list) => {
+                if !list.remove(&token_id) {
+                    Panic::TokenIdNotOwnedBy { token_id, owner_id: owner_id.clone() }.panic();
+                }
+                self.tokens_by_owner.insert(&owner_id, &list);
+
+                let was_removed = self.tokens.remove(&token_id);
+                assert!(was_removed.is_some());
+            }
+        }
+    }
+
+    /// Transfers the token `token_id` from its owner to `receiver_id` on behalf of `sender_id`.
+    /// The `sender_id` must be either the owner or an approved account.
+    /// Returns the token as it was before the transfer, *i.e.*, with its previous owner and approvals.
+    fn transfer_token(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: TokenId,
+        enforce_approval_id: Option<U64>,
+        memo: Option<String>,
+    ) -> Token {
+        self.assert_not_paused(Pausable::Transfers);
+
+        let mut token = self.get_token_or_panic(token_id);
+        let collectible = self.get_collectible_or_panic(&token.gate_id);
+
+        if !collectible.is_transferable(&token.owner_id, receiver_id) {
+            Panic::TokenNotTransferable {
+                gate_id: token.gate_id,
+                sender_id: sender_id.clone(),
+                transferability: collectible.transferability,
+            }
+            .panic();
+        }
 
-                let was_removed = self.tokens.remove(&token_id);
-                assert!(was_removed.is_some());
+        if sender_id != &token.owner_id && token.approvals.get(sender_id).is_none() {
+            Panic::SenderNotAuthToTransfer { sender_id: sender_id.clone() }.panic();
+        }
+
+        if &token.owner_id == receiver_id {
+            Panic::ReceiverIsOwner.panic();
+        }
+
+        if let Some(enforce_approval_id) = enforce_approval_id {
+            if sender_id != &token.owner_id {
+                match token.approvals.get(sender_id) {
+                    Some(TokenApproval { approval_id, min_price: _ })
+                        if approval_id == &enforce_approval_id => {}
+                    _ => Panic::EnforceApprovalFailed.panic(),
+                }
             }
         }
+
+        let old_owner_id = token.owner_id.clone();
+        self.delete_token_from(token_id, &old_owner_id);
+
+        let old_approvals = std::mem::take(&mut token.approvals);
+        token.owner_id = receiver_id.clone();
+        token.modified_at = env::block_timestamp() / 1_000_000;
+        self.insert_token(&token);
+
+        Nep171Event::NftTransfer(vec![NftTransferData {
+            authorized_id: if sender_id != &old_owner_id { Some(sender_id.clone()) } else { None },
+            old_owner_id: old_owner_id.clone(),
+            new_owner_id: token.owner_id.clone(),
+            token_ids: vec![token_id],
+            memo,
+        }])
+        .emit();
+
+        token.owner_id = old_owner_id;
+        token.approvals = old_approvals;
+        token
     }
 
     /// Approves a batch of tokens, similar to `nft_approve`.
     /// Each approval contains the `TokenId` to approve and the minimum price to sell the token for.
     /// `account_id` indicates the market account contract where list these tokens.
+    /// Only tokens of `transferable` collectibles can be approved.
     pub fn batch_approve(
         &mut self,
         tokens: Vec<(TokenId, U128)>,
@@ -566,6 +2278,7 @@ impl NftContract {
         if tokens.len() > 10 {
             Panic::ExceedTokensToBatchApprove.panic();
         }
+        self.assert_not_paused(Pausable::Transfers);
 
         let owner_id = env::predecessor_account_id();
         let mut oks = Vec::new();
@@ -607,24 +2320,40 @@ impl NftContract {
         if owner_id != &token.owner_id {
             return Err(Panic::TokenIdNotOwnedBy { token_id, owner_id: owner_id.clone() });
         }
-        if token.approvals.len() > 0 {
-            return Err(Panic::OneApprovalAllowed);
+
+        let collectible = match self.collectibles.get(&token.gate_id) {
+            None => return Err(Panic::GateIdNotFound { gate_id: token.gate_id }),
+            Some(collectible) => collectible,
+        };
+        if collectible.transferability != Transferability::Transferable {
+            return Err(Panic::TokenNotTransferable {
+                gate_id: token.gate_id,
+                sender_id: owner_id.clone(),
+                transferability: collectible.transferability,
+            });
         }
 
         token.approval_counter.0 = token.approval_counter.0 + 1;
-        token
-            .approvals
-            .insert(account_id, TokenApproval { approval_id: token.approval_counter, min_price });
+        token.approvals.insert(
+            account_id.clone(),
+            TokenApproval { approval_id: token.approval_counter, min_price },
+        );
         self.tokens.insert(&token_id, &token);
 
-        match self.collectibles.get(&token.gate_id) {
-            None => Err(Panic::GateIdNotFound { gate_id: token.gate_id }),
-            Some(collectible) => Ok(MarketApproveMsg {
-                min_price,
-                gate_id: Some(token.gate_id.try_into().unwrap()),
-                creator_id: Some(collectible.creator_id),
-            }),
-        }
+        MintGateEvent::NftApprove(vec![NftApproveData {
+            owner_id: owner_id.clone(),
+            token_id,
+            account_id,
+            approval_id: token.approval_counter,
+            min_price,
+        }])
+        .emit();
+
+        Ok(MarketApproveMsg {
+            min_price,
+            gate_id: Some(token.gate_id.try_into().unwrap()),
+            creator_id: Some(collectible.creator_id),
+        })
     }
 }
 
@@ -637,6 +2366,11 @@ impl NftContract {
 #[near_bindgen]
 impl NonFungibleTokenCore for NftContract {
     /// Transfer the token `token_id` to the `receiver_id` account.
+    /// All approvals are cleared, and the token is delisted from every approved market
+    /// other than the sender.
+    ///
+    /// The `transferability` of the token's collectible must allow the sender to transfer it.
+    /// Tokens of a `creator_only` collectible can only be transferred to or from its `creator_id`.
     ///
     /// See <https://github.com/epam/mintgate/issues/18>.
     fn nft_transfer(
@@ -647,36 +2381,14 @@ impl NonFungibleTokenCore for NftContract {
         memo: Option<String>,
     ) {
         let sender_id = env::predecessor_account_id();
-        let mut token = self.get_token_or_panic(token_id);
-
-        if sender_id != token.owner_id && token.approvals.get(&sender_id).is_none() {
-            Panic::SenderNotAuthToTransfer { sender_id }.panic();
-        }
-
-        if &token.owner_id == receiver_id.as_ref() {
-            Panic::ReceiverIsOwner.panic();
-        }
-
-        if let Some(enforce_approval_id) = enforce_approval_id {
-            let TokenApproval { approval_id, min_price: _ } = token
-                .approvals
-                .get(receiver_id.as_ref())
-                .expect("Receiver not an approver of this token.");
-            if approval_id != &enforce_approval_id {
-                Panic::EnforceApprovalFailed.panic();
-            }
-        }
-
-        if let Some(memo) = memo {
-            log!("Memo: {}", memo);
-        }
-
-        self.delete_token_from(token_id, &token.owner_id);
-
-        token.owner_id = receiver_id.as_ref().to_string();
-        token.modified_at = env::block_timestamp() / 1_000_000;
-        token.approvals.clear();
-        self.insert_token(&token);
+        let token = self.transfer_token(
+            &sender_id,
+            receiver_id.as_ref(),
+            token_id,
+            enforce_approval_id,
+            memo,
+        );
+        revoke_approvals(token_id, &token.approvals, Some(&sender_id));
     }
 
     /// Query whom to be paid out for a given `token_id`, derived from some `balance`.
@@ -693,27 +2405,38 @@ impl NonFungibleTokenCore for NftContract {
     ///
     /// for any `token_id` claimed from `gate_id`.
     ///
-    /// This is part of an ongoing (yet not settled) NEP spec:
-    /// <https://github.com/thor314/NEPs/blob/patch-5/specs/Standards/NonFungibleToken/payouts.md>
-    fn nft_payout(&self, token_id: TokenId, balance: U128) -> Payout {
+    /// Each collaborator in the `royalty_split` of `gate_id` is paid its `share` as well.
+    /// All amounts are rounded down, and the remainder is paid to `token.owner_id`.
+    ///
+    /// Panics if the payout has more than `max_len_payout` accounts, when given.
+    ///
+    /// See NEP-199: <https://github.com/near/NEPs/blob/master/neps/nep-0199.md>
+    fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: Option<u32>) -> Payout {
         let token = self.get_token_or_panic(token_id);
         match self.collectibles.get(&token.gate_id) {
             None => Panic::GateIdNotFound { gate_id: token.gate_id }.panic(),
             Some(collectible) => {
-                let royalty_amount = collectible.royalty.mult(balance.0);
-                let fee_amount = self.mintgate_fee.mult(balance.0);
-                let owner_amount = balance.0 - royalty_amount - fee_amount;
-                let entries = vec![
-                    (collectible.creator_id, royalty_amount),
-                    (self.mintgate_fee_account_id.clone(), fee_amount),
-                    (token.owner_id, owner_amount),
+                let mut entries = vec![
+                    (collectible.creator_id, collectible.royalty.mult(balance.0)),
+                    (self.mintgate_fee_account_id.clone(), self.mintgate_fee.mult(balance.0)),
                 ];
+                for share in collectible.royalty_split {
+                    entries.push((share.account_id, share.share.mult(balance.0)));
+                }
+                let owner_amount =
+                    balance.0 - entries.iter().map(|(_, amount)| amount).sum::<u128>();
+                entries.push((token.owner_id, owner_amount));
 
                 let mut payout = HashMap::new();
                 for (account_id, amount) in entries {
                     payout.entry(account_id).or_insert(U128(0)).0 += amount;
                 }
-                payout
+                if let Some(max_len_payout) = max_len_payout {
+                    if payout.len() > max_len_payout as usize {
+                        Panic::PayoutTooLong { max_len_payout }.panic();
+                    }
+                }
+                Payout { payout }
             }
         }
     }
@@ -722,21 +2445,62 @@ impl NonFungibleTokenCore for NftContract {
     /// Afterwards returns the payout data.
     /// Effectively it is calling `nft_transfer` followed by `nft_payout`.
     ///
-    /// This is part of an ongoing (yet not settled) NEP spec:
-    /// <https://github.com/thor314/NEPs/blob/patch-5/specs/Standards/NonFungibleToken/payouts.md>
+    /// See NEP-199: <https://github.com/near/NEPs/blob/master/neps/nep-0199.md>
     fn nft_transfer_payout(
         &mut self,
         receiver_id: ValidAccountId,
         token_id: TokenId,
         approval_id: Option<U64>,
         memo: Option<String>,
-        balance: Option<U128>,
-    ) -> Option<Payout> {
-        let payout = balance.map(|balance| self.nft_payout(token_id, balance));
+        balance: U128,
+        max_len_payout: Option<u32>,
+    ) -> Payout {
+        let payout = self.nft_payout(token_id, balance, max_len_payout);
         self.nft_transfer(receiver_id, token_id, approval_id, memo);
         payout
     }
 
+    /// Transfers the token `token_id` to the `receiver_id` contract,
+    /// and then calls `nft_on_transfer` on it.
+    /// If `nft_on_transfer` returns `true` or fails,
+    /// the token is returned to its previous owner along with its approvals.
+    /// The returned promise resolves to whether the token was kept by `receiver_id`.
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<U64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> Promise {
+        if env::prepaid_gas() < GAS_FOR_NFT_TRANSFER_CALL + GAS_FOR_RESOLVE_TRANSFER {
+            Panic::NotEnoughGasForTransferCall.panic();
+        }
+
+        let sender_id = env::predecessor_account_id();
+        let token =
+            self.transfer_token(&sender_id, receiver_id.as_ref(), token_id, approval_id, memo);
+
+        nft_receiver::nft_on_transfer(
+            sender_id,
+            token.owner_id.clone(),
+            token_id,
+            msg,
+            receiver_id.as_ref(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_NFT_TRANSFER_CALL,
+        )
+        .then(nft_resolver::nft_resolve_transfer(
+            token.owner_id,
+            receiver_id.into(),
+            token_id,
+            Some(token.approvals),
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+    }
+
     /// Returns the token identified by `token_id`.
     /// Or `null` if the `token_id` was not found.
     ///
@@ -746,6 +2510,58 @@ impl NonFungibleTokenCore for NftContract {
     }
 }
 
+/// Resolves the `nft_transfer_call` made by this contract.
+#[near_log(skip_args, only_pub)]
+#[near_bindgen]
+impl NonFungibleTokenResolver for NftContract {
+    /// Returns the token `token_id` to `owner_id` (restoring its `approvals`)
+    /// when `receiver_id::nft_on_transfer` either asked to return it or failed.
+    /// Otherwise, the token is delisted from the markets in `approvals`.
+    /// Returns `true` when the token was kept by `receiver_id`.
+    #[private]
+    fn nft_resolve_transfer(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approvals: Option<HashMap<AccountId, TokenApproval>>,
+    ) -> bool {
+        let must_return = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Failed => true,
+            PromiseResult::Successful(value) => serde_json::from_slice(&value).unwrap_or(true),
+        };
+        let mut token = match self.tokens.get(&token_id) {
+            Some(token) if must_return && token.owner_id == receiver_id => token,
+            // Either the receiver kept the token, or has already burned or transferred it.
+            _ => {
+                if let Some(approvals) = approvals {
+                    revoke_approvals(token_id, &approvals, None);
+                }
+                return true;
+            }
+        };
+
+        self.delete_token_from(token_id, &receiver_id);
+
+        token.owner_id = owner_id;
+        token.modified_at = env::block_timestamp() / 1_000_000;
+        token.approvals = approvals.unwrap_or_default();
+        self.insert_token(&token);
+
+        Nep171Event::NftTransfer(vec![NftTransferData {
+            authorized_id: None,
+            old_owner_id: receiver_id,
+            new_owner_id: token.owner_id,
+            token_ids: vec![token_id],
+            memo: None,
+        }])
+        .emit();
+
+        false
+    }
+}
+
 /// Non-Fungible Token Metadata (NEP-177) v1.0.0
 ///
 /// <https://nomicon.io/Standards/NonFungibleToken/Metadata.html>
@@ -768,6 +2584,7 @@ impl NonFungibleTokenApprovalMgmt for NftContract {
     /// The `msg` argument allows the caller to pass into additional information.
     /// A contract implementing the `nft_on_approve` methods must be
     /// deployed into `account_id`.
+    /// Only tokens of `transferable` collectibles can be approved.
     fn nft_approve(
         &mut self,
         token_id: TokenId,
@@ -785,13 +2602,22 @@ impl NonFungibleTokenApprovalMgmt for NftContract {
             }
         };
 
+        self.assert_not_paused(Pausable::Transfers);
+
         let owner_id = env::predecessor_account_id();
         let mut token = self.get_token_or_panic(token_id);
         if &owner_id != &token.owner_id {
             Panic::TokenIdNotOwnedBy { token_id, owner_id }.panic();
         }
-        if token.approvals.len() > 0 {
-            Panic::OneApprovalAllowed.panic();
+
+        let collectible = self.get_collectible_or_panic(&token.gate_id);
+        if collectible.transferability != Transferability::Transferable {
+            Panic::TokenNotTransferable {
+                gate_id: token.gate_id,
+                sender_id: owner_id,
+                transferability: collectible.transferability,
+            }
+            .panic();
         }
 
         token.approval_counter.0 = token.approval_counter.0 + 1;
@@ -801,28 +2627,55 @@ impl NonFungibleTokenApprovalMgmt for NftContract {
         );
         self.tokens.insert(&token_id, &token);
 
-        match self.collectibles.get(&token.gate_id) {
-            None => Panic::GateIdNotFound { gate_id: token.gate_id }.panic(),
-            Some(collectible) => {
-                let market_msg = MarketApproveMsg {
-                    min_price,
-                    gate_id: Some(token.gate_id.try_into().unwrap()),
-                    creator_id: Some(collectible.creator_id),
-                };
-                mg_core::nep178::market::nft_on_approve(
-                    token_id,
-                    owner_id.try_into().unwrap(),
-                    U64::from(token.approval_counter),
-                    serde_json::to_string(&market_msg).unwrap(),
-                    account_id.as_ref(),
-                    0,
-                    env::prepaid_gas() / 2,
-                )
+        MintGateEvent::NftApprove(vec![NftApproveData {
+            owner_id: owner_id.clone(),
+            token_id,
+            account_id: account_id.to_string(),
+            approval_id: token.approval_counter,
+            min_price,
+        }])
+        .emit();
+
+        let market_msg = MarketApproveMsg {
+            min_price,
+            gate_id: Some(token.gate_id.try_into().unwrap()),
+            creator_id: Some(collectible.creator_id),
+        };
+        mg_core::nep178::market::nft_on_approve(
+            token_id,
+            owner_id.try_into().unwrap(),
+            U64::from(token.approval_counter),
+            serde_json::to_string(&market_msg).unwrap(),
+            account_id.as_ref(),
+            0,
+            env::prepaid_gas() / 2,
+        )
+    }
+
+    /// Returns whether `approved_account_id` is allowed to transfer `token_id`.
+    /// When `approval_id` is given, it must also match the approval ID of `approved_account_id`.
+    /// Tokens of collectibles that are no longer `Transferable` cannot be transferred by anyone.
+    fn nft_is_approved(
+        &self,
+        token_id: TokenId,
+        approved_account_id: ValidAccountId,
+        approval_id: Option<U64>,
+    ) -> bool {
+        let token = self.get_token_or_panic(token_id);
+        let collectible = self.get_collectible_or_panic(&token.gate_id);
+        if collectible.transferability != Transferability::Transferable {
+            return false;
+        }
+        match token.approvals.get(approved_account_id.as_ref()) {
+            None => false,
+            Some(approval) => {
+                approval_id.map_or(true, |approval_id| approval.approval_id == approval_id)
             }
         }
     }
 
     /// Revokes approval for `token_id` from `account_id`.
+    /// It can be called while `transfers` are paused, see `pause`.
     fn nft_revoke(&mut self, token_id: TokenId, account_id: ValidAccountId) -> Promise {
         let owner_id = env::predecessor_account_id();
         let mut token = self.get_token_or_panic(token_id);
@@ -833,6 +2686,13 @@ impl NonFungibleTokenApprovalMgmt for NftContract {
             Panic::RevokeApprovalFailed { account_id: account_id.to_string() }.panic();
         }
         self.tokens.insert(&token_id, &token);
+
+        MintGateEvent::NftRevoke(vec![NftRevokeData {
+            owner_id,
+            token_id,
+            account_id: account_id.to_string(),
+        }])
+        .emit();
         mg_core::nep178::market::nft_on_revoke(
             token_id,
             account_id.as_ref(),
@@ -842,14 +2702,28 @@ impl NonFungibleTokenApprovalMgmt for NftContract {
     }
 
     /// Revokes all approval for `token_id`.
+    /// Like `nft_revoke`, it is allowed while `transfers` are paused.
     fn nft_revoke_all(&mut self, token_id: TokenId) {
         let owner_id = env::predecessor_account_id();
         let mut token = self.get_token_or_panic(token_id);
         if &owner_id != &token.owner_id {
             Panic::TokenIdNotOwnedBy { token_id, owner_id }.panic();
         }
-        for (nft_id, _) in &token.approvals {
-            mg_core::nep178::market::nft_on_revoke(token_id, nft_id, 0, env::prepaid_gas() / 2);
+        revoke_approvals(token_id, &token.approvals, None);
+
+        if !token.approvals.is_empty() {
+            MintGateEvent::NftRevoke(
+                token
+                    .approvals
+                    .keys()
+                    .map(|account_id| NftRevokeData {
+                        owner_id: owner_id.clone(),
+                        token_id,
+                        account_id: account_id.clone(),
+                    })
+                    .collect(),
+            )
+            .emit();
         }
 
         token.approvals.clear();
@@ -879,7 +2753,7 @@ impl NonFungibleTokenEnumeration for NftContract {
         while result.len() < limit.unwrap_or(u32::MAX) as usize {
             if let Some(mut token) = self.tokens.values_as_vector().get(i) {
                 let collectible = self.collectibles.get(&token.gate_id).expect("Gate id not found");
-                token.metadata = collectible.metadata;
+                token.metadata = edition_metadata(collectible.metadata, token.edition);
                 result.push(token);
                 i += 1
             } else {
@@ -945,13 +2819,209 @@ impl NonFungibleTokenEnumeration for NftContract {
     }
 }
 
+/// Notifies every market in `approvals` but `except_id` that `token_id` is no longer for sale.
+fn revoke_approvals(
+    token_id: TokenId,
+    approvals: &HashMap<AccountId, TokenApproval>,
+    except_id: Option<&AccountId>,
+) {
+    for market_id in approvals.keys() {
+        if Some(market_id) != except_id {
+            mg_core::nep178::market::nft_on_revoke(
+                token_id,
+                market_id,
+                NO_DEPOSIT,
+                GAS_FOR_NFT_ON_REVOKE,
+            );
+        }
+    }
+}
+
+/// Charges the `predecessor_account_id` for the storage used since `initial_storage_usage`.
+/// The attached deposit must cover the storage cost, and any excess is refunded.
+fn refund_storage_deposit(initial_storage_usage: StorageUsage) {
+    refund_deposit(initial_storage_usage, env::attached_deposit());
+}
+
+/// Same as `refund_storage_deposit`,
+/// but only the given `attached_deposit` is available to cover the storage cost.
+fn refund_deposit(initial_storage_usage: StorageUsage, attached_deposit: Balance) {
+    let storage_usage = env::storage_usage().saturating_sub(initial_storage_usage);
+    let storage_cost = Balance::from(storage_usage) * env::storage_byte_cost();
+    if attached_deposit < storage_cost {
+        Panic::NotEnoughDepositForStorage {
+            attached_deposit: attached_deposit.into(),
+            storage_cost: storage_cost.into(),
+        }
+        .panic();
+    }
+
+    let refund = attached_deposit - storage_cost;
+    if refund > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(refund);
+    }
+}
+
+/// Returns the `metadata` of a collectible as seen by its token with the given `edition`,
+/// *i.e.*, with the `edition` appended to its `title`.
+fn edition_metadata(mut metadata: Metadata, edition: u16) -> Metadata {
+    metadata.title = metadata.title.map(|title| format!("{} #{}", title, edition));
+    metadata
+}
+
+/// Replaces the fields of `metadata` provided by the creator of `gate_id`,
+/// setting its `updated_at` to the current time.
+fn update_metadata(gate_id: &GateId, metadata: &mut Metadata, hidden: HiddenMetadata) {
+    metadata.title = Some(hidden.title);
+    metadata.description = Some(hidden.description);
+    metadata.media = hidden.media;
+    metadata.media_hash = hidden.media_hash;
+    metadata.reference = hidden.reference;
+    metadata.reference_hash = hidden.reference_hash;
+    metadata.extra = hidden.extra;
+    metadata.updated_at = Some(env::block_timestamp() / 1_000_000);
+    check_metadata(gate_id, metadata);
+}
+
+/// Returns a new `Collectible` with no tokens minted yet and the defaults of `create_collectible`.
+fn new_collectible(
+    creator_id: AccountId,
+    gate_id: GateId,
+    supply: u16,
+    royalty: Fraction,
+    metadata: Metadata,
+) -> Collectible {
+    Collectible {
+        gate_id,
+        creator_id,
+        current_supply: supply,
+        supply,
+        minted_tokens: Vec::new(),
+        royalty,
+        royalty_split: Vec::new(),
+        mint_price: U128(0),
+        allowlist_root: None,
+        max_claims_per_account: None,
+        transferability: Transferability::Transferable,
+        metadata,
+        frozen: false,
+        metadata_commitment: None,
+        claims_end_at: None,
+    }
+}
+
+/// Ensures the `Metadata` fields provided by the creator of `gate_id` do not exceed their max length.
+fn check_metadata(gate_id: &GateId, metadata: &Metadata) {
+    let title = metadata.title.as_deref().unwrap_or_default();
+    let description = metadata.description.as_deref().unwrap_or_default();
+    if title.len() > 140 {
+        Panic::InvalidArgument {
+            gate_id: gate_id.clone(),
+            reason: "Title exceeds 140 chars".to_string(),
+        }
+        .panic();
+    }
+    if description.len() > 1024 {
+        Panic::InvalidArgument {
+            gate_id: gate_id.clone(),
+            reason: "`description` exceeds 1024 chars".to_string(),
+        }
+        .panic();
+    }
+
+    macro_rules! check {
+        ($arg:ident) => {{
+            if let Some(val) = &metadata.$arg {
+                if val.len() > 1024 {
+                    Panic::InvalidArgument {
+                        gate_id: gate_id.clone(),
+                        reason: concat!("`", stringify!($arg), "` exceeds 1024 chars").to_string(),
+                    }
+                    .panic();
+                }
+            }
+        }};
+    }
+
+    check!(media);
+    check!(media_hash);
+    check!(reference);
+    check!(reference_hash);
+    check!(extra);
+}
+
+/// Ensures both royalties are valid `Fraction`s,
+/// and `min_royalty` is less or equal to `max_royalty`.
+fn check_royalty_bounds(min_royalty: Fraction, max_royalty: Fraction) {
+    min_royalty.check();
+    max_royalty.check();
+
+    if max_royalty.cmp(&min_royalty) == Ordering::Less {
+        Panic::MaxRoyaltyLessThanMinRoyalty { min_royalty, max_royalty }.panic();
+    }
+}
+
+/// Returns the `royalty` plus every share in `royalty_split`, multiplied by `ROYALTY_SCALE`.
+fn total_royalty(royalty: Fraction, royalty_split: &[RoyaltyShare]) -> Balance {
+    royalty_split
+        .iter()
+        .fold(royalty.mult(ROYALTY_SCALE), |total, share| total + share.share.mult(ROYALTY_SCALE))
+}
+
+/// Ensures the sum of `royalty` and `mintgate_fee` is less than `1`.
+fn check_royalty_with_fee(royalty: Fraction, mintgate_fee: Fraction) {
+    let bn = 1_000_000_000_000_000_000_000;
+    if mintgate_fee.mult(bn) + royalty.mult(bn) >= bn {
+        Panic::RoyaltyTooLarge { royalty, mintgate_fee }.panic();
+    }
+}
+
+/// The current version of the `NftContract` state layout.
+/// Bump it whenever the layout changes, and add its migration into `migrate`.
+const STATE_VERSION: StateVersion = 3;
+
+/// Scale of total royalties, see `total_royalty`.
+const ROYALTY_SCALE: Balance = 1_000_000_000_000_000_000_000_000;
+
+/// Maximum number of collaborators in the `royalty_split` of a `Collectible`.
+const MAX_ROYALTY_SPLIT_LEN: usize = 10;
+
+/// Maximum number of tokens minted by a single `mint_to` call.
+const MAX_MINT_TO_LEN: u64 = 100;
+
+/// Gas reserved to mint each token in `mint_to`.
+const GAS_FOR_MINT: Gas = 3_000_000_000_000;
+
+/// Gas reserved to draw each winner in `draw_raffle`.
+const GAS_FOR_DRAW: Gas = 5_000_000_000_000;
+
 const GAS_FOR_ROYALTIES: Gas = 120_000_000_000_000;
+const GAS_FOR_NFT_ON_REVOKE: Gas = 10_000_000_000_000;
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_NFT_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER;
 const NO_DEPOSIT: Balance = 0;
 
+/// Allowance of the access key of each linkdrop, to pay for the gas of its claim.
+const LINKDROP_ALLOWANCE: Balance = 100_000_000_000_000_000_000_000;
+
+const GAS_FOR_RESOLVE_ADD_LINKDROP: Gas = 20_000_000_000_000;
+const GAS_FOR_CREATE_ACCOUNT: Gas = 40_000_000_000_000;
+const GAS_FOR_RESOLVE_LINKDROP_ACCOUNT: Gas = 20_000_000_000_000;
+
+/// Callbacks of this contract, only callable by the contract itself.
 #[near_ext]
 #[ext_contract(self_callback)]
-trait SelfCallback {
+pub trait SelfCallback {
     fn resolve_batch_approve(&mut self, errs: Vec<(TokenId, Panic)>);
+
+    fn resolve_add_linkdrop(&mut self, public_key: Base58PublicKey) -> Option<TokenId>;
+
+    fn resolve_linkdrop_account(
+        &mut self,
+        public_key: Base58PublicKey,
+        linkdrop: Linkdrop,
+        new_account_id: AccountId,
+    ) -> bool;
 }
 
 #[near_log(skip_args, only_pub)]
@@ -969,4 +3039,347 @@ impl SelfCallback for NftContract {
             }
         }
     }
+
+    /// Moves the pending linkdrop of `public_key` to `linkdrops` once its access key has been added,
+    /// so it can be claimed.
+    /// Otherwise, its token, `amount` and `LINKDROP_ALLOWANCE` are given back to its `funder_id`.
+    /// Returns the `TokenId` of the linkdrop when it was added.
+    #[private]
+    fn resolve_add_linkdrop(&mut self, public_key: Base58PublicKey) -> Option<TokenId> {
+        let linkdrop = match self.pending_linkdrops.remove(&public_key.0) {
+            None => Panic::LinkdropNotFound.panic(),
+            Some(linkdrop) => linkdrop,
+        };
+
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                self.linkdrops.insert(&public_key.0, &linkdrop);
+                Some(linkdrop.token_id)
+            }
+            PromiseResult::Failed => {
+                self.deliver_linkdrop_token(linkdrop.token_id, &linkdrop.funder_id);
+                Promise::new(linkdrop.funder_id).transfer(linkdrop.amount.0 + LINKDROP_ALLOWANCE);
+                None
+            }
+        }
+    }
+
+    /// Gives the token of `linkdrop` to `new_account_id` once the account has been created,
+    /// and deletes the access key of the linkdrop.
+    /// Otherwise, the linkdrop is restored so it can be claimed again.
+    /// Returns whether the linkdrop was claimed.
+    #[private]
+    fn resolve_linkdrop_account(
+        &mut self,
+        public_key: Base58PublicKey,
+        linkdrop: Linkdrop,
+        new_account_id: AccountId,
+    ) -> bool {
+        let created = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Failed => false,
+            // Top-level linkdrop contracts return whether the account was created.
+            PromiseResult::Successful(value) => {
+                value.is_empty() || serde_json::from_slice(&value).unwrap_or(false)
+            }
+        };
+
+        if created {
+            self.deliver_linkdrop_token(linkdrop.token_id, &new_account_id);
+            Promise::new(env::current_account_id()).delete_key(public_key.0);
+        } else {
+            self.linkdrops.insert(&public_key.0, &linkdrop);
+        }
+        created
+    }
+}
+
+/// Layouts of previous versions of `NftContract`, used to `migrate` deployed contracts.
+mod legacy {
+
+    use super::*;
+
+    /// The storage key of the contract state, as used by `env::state_write`.
+    const STATE_KEY: &[u8] = b"STATE";
+
+    /// The storage key of the `Migration` in progress.
+    const MIGRATION_KEY: &[u8] = b"MIGRATION";
+
+    /// Layout before state versioning was introduced.
+    /// It had a single `admin_id` account instead of roles.
+    #[derive(BorshDeserialize)]
+    pub struct NftContractV1 {
+        collectibles: UnorderedMap<GateId, CollectibleV1>,
+        collectibles_by_creator: LookupMap<AccountId, UnorderedSet<GateId>>,
+        tokens: UnorderedMap<TokenId, TokenV1>,
+        tokens_by_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+        admin_id: AccountId,
+        metadata: NFTContractMetadata,
+        min_royalty: Fraction,
+        max_royalty: Fraction,
+        mintgate_fee: Fraction,
+        mintgate_fee_account_id: AccountId,
+    }
+
+    /// Layout of version `2`, when state versioning was introduced.
+    /// Version `3` added every field since then to `NftContract`, `Collectible` and `Token`.
+    #[derive(BorshDeserialize)]
+    pub struct NftContractV2 {
+        collectibles: UnorderedMap<GateId, CollectibleV1>,
+        collectibles_by_creator: LookupMap<AccountId, UnorderedSet<GateId>>,
+        tokens: UnorderedMap<TokenId, TokenV1>,
+        tokens_by_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+        owner_id: AccountId,
+        roles: LookupMap<Role, UnorderedSet<AccountId>>,
+        metadata: NFTContractMetadata,
+        min_royalty: Fraction,
+        max_royalty: Fraction,
+        mintgate_fee: Fraction,
+        mintgate_fee_account_id: AccountId,
+    }
+
+    /// `Collectible` layout up to version `2`.
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct CollectibleV1 {
+        gate_id: GateId,
+        creator_id: AccountId,
+        current_supply: u16,
+        minted_tokens: Vec<TokenId>,
+        royalty: Fraction,
+        metadata: Metadata,
+    }
+
+    /// `Token` layout up to version `2`.
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct TokenV1 {
+        token_id: TokenId,
+        gate_id: GateId,
+        owner_id: AccountId,
+        created_at: Timestamp,
+        modified_at: Timestamp,
+        approvals: HashMap<AccountId, TokenApproval>,
+        approval_counter: U64,
+    }
+
+    impl TokenV1 {
+        fn into_token(self, edition: u16) -> Token {
+            Token {
+                token_id: self.token_id,
+                gate_id: self.gate_id,
+                edition,
+                owner_id: self.owner_id,
+                created_at: self.created_at,
+                modified_at: self.modified_at,
+                approvals: self.approvals,
+                approval_counter: self.approval_counter,
+                metadata: Metadata::default(),
+            }
+        }
+    }
+
+    /// The original `supply` is unknown, since burned tokens were removed from `minted_tokens`.
+    /// Thus, the `supply` is taken from the tokens still in existence.
+    impl From<CollectibleV1> for Collectible {
+        fn from(old: CollectibleV1) -> Self {
+            Self {
+                gate_id: old.gate_id,
+                creator_id: old.creator_id,
+                current_supply: old.current_supply,
+                supply: old.current_supply + old.minted_tokens.len() as u16,
+                minted_tokens: old.minted_tokens,
+                royalty: old.royalty,
+                royalty_split: Vec::new(),
+                mint_price: U128(0),
+                allowlist_root: None,
+                max_claims_per_account: None,
+                transferability: Transferability::Transferable,
+                metadata: old.metadata,
+                frozen: false,
+                metadata_commitment: None,
+                claims_end_at: None,
+            }
+        }
+    }
+
+    /// Returns `map` with its values typed as `W`, without reading nor rewriting them.
+    fn retype<K, V, W>(map: UnorderedMap<K, V>) -> UnorderedMap<K, W>
+    where
+        K: BorshSerialize + BorshDeserialize,
+        V: BorshSerialize + BorshDeserialize,
+        W: BorshSerialize + BorshDeserialize,
+    {
+        UnorderedMap::try_from_slice(&map.try_to_vec().unwrap()).unwrap()
+    }
+
+    fn read_state<T: BorshDeserialize>() -> T {
+        env::state_read().unwrap_or_else(|| Panic::StateNotFound.panic())
+    }
+
+    /// The former `admin_id` becomes the owner of the contract.
+    impl From<NftContractV1> for NftContractV2 {
+        fn from(old: NftContractV1) -> Self {
+            Self {
+                collectibles: old.collectibles,
+                collectibles_by_creator: old.collectibles_by_creator,
+                tokens: old.tokens,
+                tokens_by_owner: old.tokens_by_owner,
+                owner_id: old.admin_id,
+                roles: LookupMap::new(Keys::Roles),
+                metadata: old.metadata,
+                min_royalty: old.min_royalty,
+                max_royalty: old.max_royalty,
+                mintgate_fee: old.mintgate_fee,
+                mintgate_fee_account_id: old.mintgate_fee_account_id,
+            }
+        }
+    }
+
+    /// Collectibles and tokens keep their old layout, see `Migration`.
+    /// Royalties could neither be split nor exceed the `max_royalty`, which was fixed,
+    /// so the `max_royalty` bounds the royalty of every existing collectible.
+    impl From<NftContractV2> for NftContract {
+        fn from(old: NftContractV2) -> Self {
+            Self {
+                collectibles: retype(old.collectibles),
+                collectibles_by_creator: old.collectibles_by_creator,
+                tokens: retype(old.tokens),
+                tokens_by_owner: old.tokens_by_owner,
+                next_token_id: 0,
+                owner_id: old.owner_id,
+                roles: old.roles,
+                metadata: old.metadata,
+                min_royalty: old.min_royalty,
+                max_royalty: old.max_royalty,
+                mintgate_fee: old.mintgate_fee,
+                mintgate_fee_account_id: old.mintgate_fee_account_id,
+                highest_royalty: old.max_royalty.mult(ROYALTY_SCALE),
+                allowlist_claims: LookupSet::new(Keys::AllowlistClaims),
+                claims_by_account: LookupMap::new(Keys::ClaimsByAccount),
+                paused: Vec::new(),
+                voucher_keys: LookupMap::new(Keys::VoucherSigners),
+                redeemed_vouchers: LookupSet::new(Keys::RedeemedVouchers),
+                claim_codes: LookupMap::new(Keys::ClaimCodes),
+                linkdrops: LookupMap::new(Keys::Linkdrops),
+                pending_linkdrops: LookupMap::new(Keys::PendingLinkdrops),
+                raffles: LookupMap::new(Keys::Raffles),
+                raffle_entrants: LookupMap::new(Keys::RaffleEntrants),
+                raffle_entries: LookupMap::new(Keys::RaffleEntries),
+            }
+        }
+    }
+
+    /// A migration started by `migrate` whose collectibles and tokens
+    /// are migrated in batches by `migrate_batch`.
+    /// While in progress, it is stored in place of the contract state,
+    /// so no other method can be called until it completes.
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct Migration {
+        /// The version of the layout being migrated from.
+        from: StateVersion,
+        /// The contract in its current layout,
+        /// except for the values of `collectibles` and `tokens` not migrated yet.
+        contract: NftContract,
+        /// The index of the next collectible to migrate.
+        collectibles_index: u64,
+        /// The index of the next token to migrate.
+        tokens_index: u64,
+    }
+
+    impl Migration {
+        /// Starts migrating the contract state stored in version `from`.
+        pub fn start(from: StateVersion) -> Self {
+            let old: NftContractV2 = match from {
+                1 => read_state::<NftContractV1>().into(),
+                2 => read_state(),
+                from => Panic::UnsupportedStateMigration { from, to: STATE_VERSION }.panic(),
+            };
+            env::storage_remove(STATE_KEY);
+
+            Self { from, contract: old.into(), collectibles_index: 0, tokens_index: 0 }
+        }
+
+        /// Whether a migration has been started and it is not complete yet.
+        pub fn exists() -> bool {
+            env::storage_has_key(MIGRATION_KEY)
+        }
+
+        pub fn read() -> Self {
+            env::storage_read(MIGRATION_KEY)
+                .map(|bytes| Self::try_from_slice(&bytes).unwrap())
+                .unwrap_or_else(|| Panic::MigrationNotStarted.panic())
+        }
+
+        pub fn write(&self) {
+            env::storage_write(MIGRATION_KEY, &self.try_to_vec().unwrap());
+        }
+
+        /// Migrates at most `limit` collectibles and tokens, collectibles first.
+        /// Once all of them have been migrated,
+        /// the contract is stored back as the contract state.
+        /// Returns whether the migration is complete.
+        pub fn migrate_batch(mut self, limit: u32) -> bool {
+            let mut limit = limit as u64;
+
+            while limit > 0 && self.collectibles_index < self.contract.collectibles.len() {
+                let index = self.collectibles_index;
+                let key = self.contract.collectibles.keys_as_vector().get_raw(index).unwrap();
+                let value = self.contract.collectibles.values_as_vector().get_raw(index).unwrap();
+                let collectible = self.migrate_collectible(&value);
+                // `insert` would try to deserialize the replaced value using the new layout.
+                self.contract.collectibles.insert_raw(&key, &collectible.try_to_vec().unwrap());
+
+                self.collectibles_index += 1;
+                limit -= 1;
+            }
+
+            while limit > 0 && self.tokens_index < self.contract.tokens.len() {
+                let index = self.tokens_index;
+                let key = self.contract.tokens.keys_as_vector().get_raw(index).unwrap();
+                let value = self.contract.tokens.values_as_vector().get_raw(index).unwrap();
+                let token = self.migrate_token(&value);
+                // Token IDs were assigned from the number of tokens,
+                // so the next token ID follows the greatest existing one.
+                self.contract.next_token_id = self.contract.next_token_id.max(token.token_id.0 + 1);
+                self.contract.tokens.insert_raw(&key, &token.try_to_vec().unwrap());
+
+                self.tokens_index += 1;
+                limit -= 1;
+            }
+
+            if self.collectibles_index < self.contract.collectibles.len()
+                || self.tokens_index < self.contract.tokens.len()
+            {
+                self.write();
+                false
+            } else {
+                env::storage_remove(MIGRATION_KEY);
+                env::state_write(&self.contract);
+                true
+            }
+        }
+
+        /// Versions `1` and `2` share the same `Collectible` layout.
+        fn migrate_collectible(&self, value: &[u8]) -> Collectible {
+            debug_assert!(self.from <= 2);
+            CollectibleV1::try_from_slice(value).unwrap().into()
+        }
+
+        /// Versions `1` and `2` share the same `Token` layout.
+        /// The edition of each token is given by its position in the `minted_tokens` of its collectible,
+        /// which has already been migrated.
+        fn migrate_token(&self, value: &[u8]) -> Token {
+            debug_assert!(self.from <= 2);
+            let token = TokenV1::try_from_slice(value).unwrap();
+            let edition = self
+                .contract
+                .collectibles
+                .get(&token.gate_id)
+                .and_then(|collectible| {
+                    collectible.minted_tokens.iter().position(|tid| tid == &token.token_id)
+                })
+                .map_or(0, |i| i as u16 + 1);
+            token.into_token(edition)
+        }
+    }
 }
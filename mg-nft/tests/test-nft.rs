@@ -11,7 +11,7 @@ use mg_core::{
     nep177::NonFungibleTokenMetadata,
     nep178::NonFungibleTokenApprovalMgmt,
     nep181::NonFungibleTokenEnumeration,
-    NftApproveMsg, TokenApproval, TokenId,
+    NftApproveMsg, RoyaltyShare, TokenApproval, TokenId, Transferability,
 };
 use mg_nft::NftContract;
 use near_sdk::{
@@ -25,6 +25,9 @@ use std::{
 
 mock_context!();
 
+/// Deposit attached to every call, enough to cover storage of new collectibles and tokens.
+const STORAGE_DEPOSIT: u128 = 100_000_000_000_000_000_000_000;
+
 struct NftContractChecker {
     contract: NftContract,
     claimed_tokens: Vec<TokenId>,
@@ -50,12 +53,30 @@ impl MockedContext<NftContractChecker> {
         gate_id: ValidGateId,
         supply: u16,
         royalty: &str,
+    ) {
+        self.create_split_collectible(creator_id, gate_id, supply, royalty, &[]);
+    }
+
+    fn create_split_collectible(
+        &mut self,
+        creator_id: ValidAccountId,
+        gate_id: ValidGateId,
+        supply: u16,
+        royalty: &str,
+        royalty_split: &[(ValidAccountId, &str)],
     ) {
         let collectibles_by_owner = self.get_collectibles_by_creator(creator_id.clone());
 
         println!("Creating Collectible `{}` with supply {}", gate_id, supply);
 
         let royalty = royalty.parse().unwrap();
+        let royalty_split = royalty_split
+            .iter()
+            .map(|(account_id, share)| RoyaltyShare {
+                account_id: account_id.to_string(),
+                share: share.parse().unwrap(),
+            })
+            .collect::<Vec<_>>();
         self.contract.create_collectible(
             creator_id.clone(),
             gate_id.clone(),
@@ -67,14 +88,20 @@ impl MockedContext<NftContractChecker> {
             Some("123".to_string()),
             Some("ref".to_string()),
             Some("456".to_string()),
+            if royalty_split.is_empty() { None } else { Some(royalty_split.clone()) },
+            None,
+            None,
+            None,
         );
 
         let collectible = self.contract.get_collectible_by_gate_id(gate_id.clone()).unwrap();
         assert_eq!(collectible.creator_id, creator_id.to_string());
         assert_eq!(&collectible.gate_id, gate_id.as_ref());
         assert_eq!(collectible.current_supply, supply);
+        assert_eq!(collectible.supply, supply);
         assert_eq!(collectible.minted_tokens.len(), 0);
         assert_eq!(collectible.royalty, royalty);
+        assert_eq!(collectible.royalty_split, royalty_split);
         assert_eq!(collectible.metadata.media, Some("media".to_string()));
         assert_eq!(collectible.metadata.media_hash, Some("123".to_string()));
         assert_eq!(collectible.metadata.reference, Some("ref".to_string()));
@@ -121,7 +148,17 @@ impl MockedContext<NftContractChecker> {
         assert_eq!(token.approval_counter, U64(0));
 
         let collectible = self.contract.get_collectible_by_gate_id(gate_id.clone()).unwrap();
-        assert_eq!(token.metadata, collectible.metadata);
+        assert_eq!(token.edition, collectible.supply - collectible.current_supply);
+        assert_eq!(
+            token.metadata.title,
+            collectible
+                .metadata
+                .title
+                .as_ref()
+                .map(|title| format!("{} #{}", title, token.edition))
+        );
+        assert_eq!(token.metadata.description, collectible.metadata.description);
+        assert_eq!(token.metadata.media, collectible.metadata.media);
 
         assert!(self
             .contract
@@ -180,7 +217,7 @@ fn init_contract(
     max_royalty: &str,
     metadata: NFTContractMetadata,
 ) -> MockedContext<NftContractChecker> {
-    MockedContext::new(|| NftContractChecker {
+    let mut context = MockedContext::new(|| NftContractChecker {
         contract: NftContract::init(
             mintgate_admin(),
             metadata,
@@ -190,13 +227,21 @@ fn init_contract(
             mintgate_fee_account_id(),
         ),
         claimed_tokens: Vec::new(),
-    })
+    });
+    context.attach_deposit(STORAGE_DEPOSIT);
+    context
 }
 
 fn init() -> MockedContext<NftContractChecker> {
     init_contract("5/100", "30/100", metadata(base_uri()))
 }
 
+/// Sets the current block time to `now`, in miliseconds.
+fn travel_to(contract: &mut MockedContext<NftContractChecker>, now: u64) {
+    contract.context.block_timestamp = now * 1_000_000;
+    contract.update_context();
+}
+
 fn metadata(base_uri: Option<String>) -> NFTContractMetadata {
     NFTContractMetadata {
         spec: "mg-nft-1.0.0".to_string(),
@@ -266,6 +311,186 @@ mod initial_state {
     }
 }
 
+mod migration {
+
+    use super::*;
+    use mg_core::{
+        fraction::Fraction, mocked_context::nft, state::write_state_version, Metadata, Role,
+        Timestamp,
+    };
+    use near_sdk::{
+        borsh::{self, BorshDeserialize, BorshSerialize},
+        collections::{LookupMap, UnorderedMap, UnorderedSet},
+        env, AccountId,
+    };
+    use std::collections::HashMap;
+
+    /// `NftContract` layout of version `2`.
+    #[derive(BorshDeserialize, BorshSerialize)]
+    struct NftContractV2 {
+        collectibles: UnorderedMap<GateId, CollectibleV2>,
+        collectibles_by_creator: LookupMap<AccountId, UnorderedSet<GateId>>,
+        tokens: UnorderedMap<TokenId, TokenV2>,
+        tokens_by_owner: LookupMap<AccountId, UnorderedSet<TokenId>>,
+        owner_id: AccountId,
+        roles: LookupMap<Role, UnorderedSet<AccountId>>,
+        metadata: NFTContractMetadata,
+        min_royalty: Fraction,
+        max_royalty: Fraction,
+        mintgate_fee: Fraction,
+        mintgate_fee_account_id: AccountId,
+    }
+
+    /// `Collectible` layout of version `2`.
+    #[derive(BorshDeserialize, BorshSerialize)]
+    struct CollectibleV2 {
+        gate_id: GateId,
+        creator_id: AccountId,
+        current_supply: u16,
+        minted_tokens: Vec<TokenId>,
+        royalty: Fraction,
+        metadata: Metadata,
+    }
+
+    /// `Token` layout of version `2`.
+    #[derive(BorshDeserialize, BorshSerialize)]
+    struct TokenV2 {
+        token_id: TokenId,
+        gate_id: GateId,
+        owner_id: AccountId,
+        created_at: Timestamp,
+        modified_at: Timestamp,
+        approvals: HashMap<AccountId, TokenApproval>,
+        approval_counter: U64,
+    }
+
+    /// Stores a contract in version `2` owned by `alice`,
+    /// with `collectibles` collectibles of supply `10`, each one with `tokens` claimed by `bob`.
+    fn init_v2(collectibles: u16, tokens: u16) -> MockedContext<()> {
+        let context = MockedContext::new(|| ());
+
+        let mut state = NftContractV2 {
+            collectibles: UnorderedMap::new(b"v2c".to_vec()),
+            collectibles_by_creator: LookupMap::new(b"v2cc".to_vec()),
+            tokens: UnorderedMap::new(b"v2t".to_vec()),
+            tokens_by_owner: LookupMap::new(b"v2to".to_vec()),
+            owner_id: alice().to_string(),
+            roles: LookupMap::new(b"v2r".to_vec()),
+            metadata: metadata(base_uri()),
+            min_royalty: "5/100".parse().unwrap(),
+            max_royalty: "30/100".parse().unwrap(),
+            mintgate_fee: "25/1000".parse().unwrap(),
+            mintgate_fee_account_id: mintgate_fee_account_id().to_string(),
+        };
+        let mut token_id = 0;
+        for k in 0..collectibles {
+            let mut minted_tokens = Vec::new();
+            for _ in 0..tokens {
+                let token = TokenV2 {
+                    token_id: U64(token_id),
+                    gate_id: gate_id(k).to_string(),
+                    owner_id: bob().to_string(),
+                    created_at: 0,
+                    modified_at: 0,
+                    approvals: HashMap::new(),
+                    approval_counter: U64(0),
+                };
+                state.tokens.insert(&U64(token_id), &token);
+                minted_tokens.push(U64(token_id));
+                token_id += 1;
+            }
+            let collectible = CollectibleV2 {
+                gate_id: gate_id(k).to_string(),
+                creator_id: alice().to_string(),
+                current_supply: 10 - tokens,
+                minted_tokens,
+                royalty: "5/100".parse().unwrap(),
+                metadata: Metadata::default(),
+            };
+            state.collectibles.insert(&gate_id(k).to_string(), &collectible);
+        }
+        env::state_write(&state);
+        write_state_version(2);
+
+        context
+    }
+
+    fn read_contract() -> NftContract {
+        env::state_read().unwrap()
+    }
+
+    #[test]
+    fn migrate_collectibles_without_tokens() {
+        init_v2(5, 0).run_as(nft(), |_| {
+            NftContract::migrate();
+            assert!(!NftContract::migrate_batch(2));
+            assert!(!NftContract::migrate_batch(2));
+            assert!(NftContract::migrate_batch(2));
+
+            let contract = read_contract();
+            for k in 0..5 {
+                let collectible = contract.get_collectible_by_gate_id(gate_id(k)).unwrap();
+                assert_eq!(collectible.supply, 10);
+                assert_eq!(collectible.current_supply, 10);
+            }
+            assert_eq!(contract.nft_total_supply(), U64(0));
+            assert_eq!(contract.get_role_holders(Role::Owner), vec![alice().to_string()]);
+        });
+    }
+
+    #[test]
+    fn migrate_more_collectibles_than_limit() {
+        init_v2(3, 2).run_as(nft(), |_| {
+            NftContract::migrate();
+            let done = (0..5).map(|_| NftContract::migrate_batch(2)).collect::<Vec<_>>();
+            assert_eq!(done, vec![false, false, false, false, true]);
+
+            let contract = read_contract();
+            for k in 0..3 {
+                let collectible = contract.get_collectible_by_gate_id(gate_id(k)).unwrap();
+                assert_eq!(collectible.supply, 10);
+                assert_eq!(collectible.current_supply, 8);
+            }
+            let editions = contract
+                .nft_tokens(None, None)
+                .iter()
+                .map(|token| (token.token_id, token.edition))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                editions,
+                vec![(U64(0), 1), (U64(1), 2), (U64(2), 1), (U64(3), 2), (U64(4), 1), (U64(5), 2)]
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "There is no migration in progress")]
+    fn migrate_batch_after_migration_should_panic() {
+        init_v2(1, 1).run_as(nft(), |_| {
+            NftContract::migrate();
+            assert!(NftContract::migrate_batch(2));
+            NftContract::migrate_batch(2);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "A migration is in progress, call `migrate_batch` to complete it")]
+    fn init_while_migrating_should_panic() {
+        init_v2(3, 1).run_as(bob(), |_| {
+            NftContract::migrate();
+            assert!(!NftContract::migrate_batch(2));
+            NftContract::init(
+                bob(),
+                metadata(base_uri()),
+                "5/100".parse().unwrap(),
+                "30/100".parse().unwrap(),
+                "25/1000".parse().unwrap(),
+                bob(),
+            );
+        });
+    }
+}
+
 mod create_collectible {
 
     use super::*;
@@ -334,6 +559,70 @@ mod create_collectible {
         });
     }
 
+    #[test]
+    fn create_a_collectible_with_royalty_split() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_split_collectible(
+                alice(),
+                gate_id(1),
+                10,
+                "10/100",
+                &[(bob(), "5/100"), (charlie(), "15/100")],
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The fraction must be less or equal to 1")]
+    fn create_a_collectible_with_invalid_royalty_share_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_split_collectible(alice(), gate_id(1), 10, "10/100", &[(bob(), "2/1")]);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Royalty split of `GPZkspuVGaZxwWoP6bJoWU` is greater than max royalty `30/100`"
+    )]
+    fn create_a_collectible_with_greater_than_max_royalty_split_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_split_collectible(
+                alice(),
+                gate_id(1),
+                10,
+                "10/100",
+                &[(bob(), "10/100"), (charlie(), "11/100")],
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Royalty split of `GPZkspuVGaZxwWoP6bJoWU` is too large for the given NFT fee `25/1000`"
+    )]
+    fn create_a_collectible_with_full_royalty_split_should_panic() {
+        init_contract("0/10", "30/30", metadata(base_uri())).run_as(mintgate_admin(), |contract| {
+            contract.create_split_collectible(
+                alice(),
+                gate_id(1),
+                10,
+                "50/100",
+                &[(bob(), "49/100")],
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid argument for gate ID `GPZkspuVGaZxwWoP6bJoWU`: `royalty_split` exceeds 10 shares"
+    )]
+    fn create_a_collectible_with_too_many_royalty_shares_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            let royalty_split = vec![(bob(), "1/100"); 11];
+            contract.create_split_collectible(alice(), gate_id(1), 10, "5/100", &royalty_split);
+        });
+    }
+
     #[test]
     #[should_panic(
         expected = "Invalid argument for gate ID `GPZkspuVGaZxwWoP6bJoWU`: Title exceeds 140 chars"
@@ -351,6 +640,10 @@ mod create_collectible {
                 None,
                 None,
                 None,
+                None,
+                None,
+                None,
+                None,
             );
         });
     }
@@ -372,6 +665,10 @@ mod create_collectible {
                 None,
                 None,
                 None,
+                None,
+                None,
+                None,
+                None,
             );
         });
     }
@@ -393,6 +690,10 @@ mod create_collectible {
                 None,
                 None,
                 None,
+                None,
+                None,
+                None,
+                None,
             );
         });
     }
@@ -414,6 +715,10 @@ mod create_collectible {
                 Some(String::from_utf8(vec![b'X'; 1025]).unwrap()),
                 None,
                 None,
+                None,
+                None,
+                None,
+                None,
             );
         });
     }
@@ -435,6 +740,10 @@ mod create_collectible {
                 None,
                 Some(String::from_utf8(vec![b'X'; 1025]).unwrap()),
                 None,
+                None,
+                None,
+                None,
+                None,
             );
         });
     }
@@ -456,12 +765,16 @@ mod create_collectible {
                 None,
                 None,
                 Some(String::from_utf8(vec![b'X'; 1025]).unwrap()),
+                None,
+                None,
+                None,
+                None,
             );
         });
     }
 
     #[test]
-    #[should_panic(expected = "Operation is allowed only for admin")]
+    #[should_panic(expected = "Account `alice` does not have the `collectible_minter` role")]
     fn create_a_collectible_by_no_admin_should_panic() {
         init_contract("0/10", "30/30", metadata(base_uri())).run_as(alice(), |contract| {
             contract.contract.create_collectible(
@@ -475,6 +788,10 @@ mod create_collectible {
                 None,
                 None,
                 None,
+                None,
+                None,
+                None,
+                None,
             );
         });
     }
@@ -536,7 +853,9 @@ mod delete_collectible {
     }
 
     #[test]
-    #[should_panic(expected = "Unable to delete gate ID `GPZkspuVGaZxwWoP6bJoWU`")]
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
     fn delete_a_collectible_from_non_creator_should_panic() {
         init()
             .run_as(mintgate_admin(), |contract| {
@@ -574,128 +893,1122 @@ mod delete_collectible {
     }
 }
 
-mod claim_token {
+mod update_collectible_metadata {
 
     use super::*;
 
+    fn update(contract: &mut MockedContext<NftContractChecker>, gate_id: ValidGateId, title: &str) {
+        contract.update_collectible_metadata(
+            gate_id,
+            title.to_string(),
+            "New description".to_string(),
+            Some("new media".to_string()),
+            None,
+            Some("new ref".to_string()),
+            None,
+            Some("{}".to_string()),
+        );
+    }
+
     #[test]
-    fn claim_a_token() {
+    fn update_collectible_metadata_from_creator() {
         init()
             .run_as(mintgate_admin(), |contract| {
                 contract.create_test_collectible(alice(), gate_id(1), 10);
-                assert_eq!(contract.get_collectibles_by_creator(alice()).len(), 1);
             })
             .run_as(bob(), |contract| {
-                let uri = format!("{}{}", base_uri().unwrap(), gate_id(1));
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(alice(), |contract| {
+                update(contract, gate_id(1), "New title");
+
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                let metadata = &collectible.metadata;
+                assert_eq!(metadata.title, Some("New title".to_string()));
+                assert_eq!(metadata.description, Some("New description".to_string()));
+                assert_eq!(metadata.media, Some("new media".to_string()));
+                assert_eq!(metadata.media_hash, None);
+                assert_eq!(metadata.reference, Some("new ref".to_string()));
+                assert_eq!(metadata.reference_hash, None);
+                assert_eq!(metadata.extra, Some("{}".to_string()));
+                assert_eq!(metadata.copies, Some(10));
+                assert!(metadata.updated_at.is_some());
+                assert!(!collectible.frozen);
 
-                for _i in 0..3 {
-                    let token_id = contract.claim_token(gate_id(1));
-                    assert_eq!(contract.nft_token_uri(token_id).unwrap(), uri);
-                }
+                let token_id = contract.last_claimed_token();
+                let metadata = contract.nft_token(token_id).unwrap().metadata;
+                assert_eq!(metadata.title, Some("New title #1".to_string()));
+                assert_eq!(metadata.description, collectible.metadata.description);
+                assert_eq!(metadata.media, collectible.metadata.media);
+            });
+    }
 
-                let tokens = contract.get_tokens_by_owner(bob());
-                assert_eq!(tokens.len(), 3);
+    #[test]
+    fn update_collectible_metadata_from_admin() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            update(contract, gate_id(1), "New title");
 
-                let c = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
-                assert_eq!(c.current_supply, 7);
-            });
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.metadata.title, Some("New title".to_string()));
+        });
     }
 
     #[test]
-    fn claim_a_few_tokens() {
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn update_collectible_metadata_from_non_creator_should_panic() {
         init()
             .run_as(mintgate_admin(), |contract| {
                 contract.create_test_collectible(alice(), gate_id(1), 10);
-                assert_eq!(contract.get_collectibles_by_creator(alice()).len(), 1);
             })
-            .run_as(mintgate_admin(), |contract| {
-                contract.create_test_collectible(bob(), gate_id(2), 15);
-                assert_eq!(contract.get_collectibles_by_creator(bob()).len(), 1);
-                contract.claim_token(gate_id(2));
+            .run_as(bob(), |contract| {
+                update(contract, gate_id(1), "New title");
             });
     }
 
     #[test]
-    #[should_panic(expected = "Gate ID `Nekq22i3rvzDe7c51Yc8hU` was not found")]
-    fn claim_a_token_of_non_existent_gate_id_should_panic() {
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` was not found")]
+    fn update_a_non_existent_collectible_should_panic() {
         init().run_as(alice(), |contract| {
-            contract.claim_token(gate_id(0));
+            update(contract, gate_id(1), "New title");
         });
     }
 
     #[test]
-    #[should_panic(
-        expected = "Tokens for gate id `GPZkspuVGaZxwWoP6bJoWU` have already been claimed"
-    )]
-    fn claim_a_token_with_no_supply_should_panic() {
+    #[should_panic(expected = "Title exceeds 140 chars")]
+    fn update_collectible_metadata_with_long_title_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            update(contract, gate_id(1), &"a".repeat(141));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "`extra` exceeds 1024 chars")]
+    fn update_collectible_metadata_with_long_extra_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            contract.update_collectible_metadata(
+                gate_id(1),
+                "title".to_string(),
+                "desc".to_string(),
+                None,
+                None,
+                None,
+                None,
+                Some("a".repeat(1025)),
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata of gate ID `GPZkspuVGaZxwWoP6bJoWU` is frozen")]
+    fn update_a_frozen_collectible_should_panic() {
         init()
             .run_as(mintgate_admin(), |contract| {
-                contract.create_test_collectible(alice(), gate_id(1), 1);
-                contract.claim_token(gate_id(1));
+                contract.create_test_collectible(alice(), gate_id(1), 10);
             })
-            .run_as(bob(), |contract| {
-                contract.claim_token(gate_id(1));
+            .run_as(alice(), |contract| {
+                contract.freeze_collectible_metadata(gate_id(1));
+                assert!(contract.get_collectible_by_gate_id(gate_id(1)).unwrap().frozen);
+            })
+            .run_as(mintgate_admin(), |contract| {
+                update(contract, gate_id(1), "New title");
             });
     }
 
     #[test]
-    fn claim_and_get_a_few_tokens() {
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn freeze_collectible_metadata_from_non_creator_should_panic() {
         init()
             .run_as(mintgate_admin(), |contract| {
-                contract.create_test_collectible(alice(), gate_id(1), 100);
+                contract.create_test_collectible(alice(), gate_id(1), 10);
             })
-            .run_as(alice(), |contract| {
-                assert_eq!(contract.get_collectibles_by_creator(alice()).len(), 1);
+            .run_as(bob(), |contract| {
+                contract.freeze_collectible_metadata(gate_id(1));
+            });
+    }
+}
 
-                assert_eq!(contract.nft_tokens(None, Some(0)).len(), 0);
-                assert_eq!(contract.nft_tokens(None, Some(10)).len(), 0);
-                assert_eq!(contract.nft_tokens(Some(50.into()), None).len(), 0);
+mod reveal {
 
-                for _i in 0..20 {
-                    contract.claim_token(gate_id(1));
-                }
-            })
+    use super::*;
+    use mg_core::reveal::{self, HiddenMetadata};
+
+    fn hidden_metadata() -> HiddenMetadata {
+        HiddenMetadata {
+            title: "Revealed title".to_string(),
+            description: "Revealed description".to_string(),
+            media: Some("revealed media".to_string()),
+            media_hash: Some("789".to_string()),
+            reference: None,
+            reference_hash: None,
+            extra: None,
+        }
+    }
+
+    fn init_committed() -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
             .run_as(mintgate_admin(), |contract| {
-                contract.create_test_collectible(bob(), gate_id(2), 15);
+                contract.create_test_collectible(alice(), gate_id(1), 10);
             })
-            .run_as(bob(), |contract| {
-                assert_eq!(contract.get_collectibles_by_creator(bob()).len(), 1);
-
-                for _i in 0..10 {
-                    contract.claim_token(gate_id(2));
-                }
+            .run_as(alice(), |contract| {
+                let commitment = reveal::commitment(&hidden_metadata(), "salt");
+                contract.commit_metadata(gate_id(1), commitment.into());
 
-                assert_eq!(contract.nft_total_supply(), U64(30));
-                assert_eq!(contract.nft_supply_for_owner(alice()), U64(20));
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.metadata_commitment, Some(commitment.into()));
+            });
+        context
+    }
 
-                assert_eq!(contract.nft_tokens(None, None).len(), 30);
-                assert_eq!(contract.nft_tokens_for_owner(alice(), None, None).len(), 20);
+    #[test]
+    fn views_expose_the_placeholder_until_revealed() {
+        init_committed()
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
 
-                assert_eq!(contract.nft_tokens(None, Some(10)).len(), 10);
-                assert_eq!(contract.nft_tokens_for_owner(alice(), None, Some(10)).len(), 10);
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.metadata.title, Some("My collectible".to_string()));
+                assert_eq!(collectible.metadata.media, Some("media".to_string()));
+                let metadata = contract.nft_token(token_id).unwrap().metadata;
+                assert_eq!(metadata.title, Some("My collectible #1".to_string()));
+                assert_eq!(metadata.media, Some("media".to_string()));
+            })
+            .run_as(alice(), |contract| {
+                contract.reveal(gate_id(1), hidden_metadata(), "salt".to_string());
+
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.metadata_commitment, None);
+                let metadata = &collectible.metadata;
+                assert_eq!(metadata.title, Some("Revealed title".to_string()));
+                assert_eq!(metadata.description, Some("Revealed description".to_string()));
+                assert_eq!(metadata.media, Some("revealed media".to_string()));
+                assert_eq!(metadata.media_hash, Some("789".to_string()));
+                assert_eq!(metadata.reference, None);
+                assert_eq!(metadata.copies, Some(10));
+                assert!(metadata.updated_at.is_some());
 
-                assert_eq!(contract.nft_tokens(Some(25.into()), Some(10)).len(), 5);
-                assert_eq!(
-                    contract.nft_tokens_for_owner(alice(), Some(15.into()), Some(10)).len(),
-                    5
-                );
+                let token_id = contract.last_claimed_token();
+                let metadata = contract.nft_token(token_id).unwrap().metadata;
+                assert_eq!(metadata.title, Some("Revealed title #1".to_string()));
+                assert_eq!(metadata.media, Some("revealed media".to_string()));
             });
     }
-}
 
-mod nft_token_uri {
+    #[test]
+    fn reveal_frozen_collectible() {
+        init_committed().run_as(alice(), |contract| {
+            contract.freeze_collectible_metadata(gate_id(1));
+            contract.reveal(gate_id(1), hidden_metadata(), "salt".to_string());
 
-    use super::*;
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.metadata.title, Some("Revealed title".to_string()));
+            assert!(collectible.frozen);
+        });
+    }
 
     #[test]
-    fn get_token_uri_with_base_uri() {
-        init_contract("5/100", "30/100", metadata(base_uri())).run_as(
-            mintgate_admin(),
-            |contract| {
-                assert_eq!(contract.nft_token_uri(U64(0)), None);
-
-                contract.create_test_collectible(alice(), gate_id(1), 10);
-                let uri = format!("{}{}", base_uri().unwrap(), gate_id(1));
+    #[should_panic(
+        expected = "Revealed metadata of gate ID `GPZkspuVGaZxwWoP6bJoWU` does not match its commitment"
+    )]
+    fn reveal_with_wrong_salt_should_panic() {
+        init_committed().run_as(alice(), |contract| {
+            contract.reveal(gate_id(1), hidden_metadata(), "pepper".to_string());
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Revealed metadata of gate ID `GPZkspuVGaZxwWoP6bJoWU` does not match its commitment"
+    )]
+    fn reveal_with_other_metadata_should_panic() {
+        init_committed().run_as(alice(), |contract| {
+            let mut metadata = hidden_metadata();
+            metadata.media = Some("another media".to_string());
+            contract.reveal(gate_id(1), metadata, "salt".to_string());
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn reveal_by_non_creator_should_panic() {
+        init_committed().run_as(bob(), |contract| {
+            contract.reveal(gate_id(1), hidden_metadata(), "salt".to_string());
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Metadata of gate ID `GPZkspuVGaZxwWoP6bJoWU` has no commitment to reveal"
+    )]
+    fn reveal_twice_should_panic() {
+        init_committed().run_as(alice(), |contract| {
+            contract.reveal(gate_id(1), hidden_metadata(), "salt".to_string());
+            contract.reveal(gate_id(1), hidden_metadata(), "salt".to_string());
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Metadata of gate ID `GPZkspuVGaZxwWoP6bJoWU` has already been committed"
+    )]
+    fn commit_twice_should_panic() {
+        init_committed().run_as(alice(), |contract| {
+            let commitment = reveal::commitment(&hidden_metadata(), "pepper");
+            contract.commit_metadata(gate_id(1), commitment.into());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` has already some claimed tokens")]
+    fn commit_after_claims_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(alice(), |contract| {
+                let commitment = reveal::commitment(&hidden_metadata(), "salt");
+                contract.commit_metadata(gate_id(1), commitment.into());
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Metadata of gate ID `GPZkspuVGaZxwWoP6bJoWU` is frozen")]
+    fn commit_frozen_collectible_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                contract.freeze_collectible_metadata(gate_id(1));
+                let commitment = reveal::commitment(&hidden_metadata(), "salt");
+                contract.commit_metadata(gate_id(1), commitment.into());
+            });
+    }
+}
+
+mod claim_token {
+
+    use super::*;
+
+    #[test]
+    fn claim_a_token() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                assert_eq!(contract.get_collectibles_by_creator(alice()).len(), 1);
+            })
+            .run_as(bob(), |contract| {
+                let uri = format!("{}{}", base_uri().unwrap(), gate_id(1));
+
+                for _i in 0..3 {
+                    let token_id = contract.claim_token(gate_id(1));
+                    assert_eq!(contract.nft_token_uri(token_id).unwrap(), uri);
+                }
+
+                let tokens = contract.get_tokens_by_owner(bob());
+                assert_eq!(tokens.len(), 3);
+
+                let c = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(c.current_supply, 7);
+            });
+    }
+
+    #[test]
+    fn claim_a_few_tokens() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                assert_eq!(contract.get_collectibles_by_creator(alice()).len(), 1);
+            })
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(bob(), gate_id(2), 15);
+                assert_eq!(contract.get_collectibles_by_creator(bob()).len(), 1);
+                contract.claim_token(gate_id(2));
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `Nekq22i3rvzDe7c51Yc8hU` was not found")]
+    fn claim_a_token_of_non_existent_gate_id_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.claim_token(gate_id(0));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens for gate id `GPZkspuVGaZxwWoP6bJoWU` have already been claimed"
+    )]
+    fn claim_a_token_with_no_supply_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 1);
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            });
+    }
+
+    #[test]
+    fn claim_and_get_a_few_tokens() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 100);
+            })
+            .run_as(alice(), |contract| {
+                assert_eq!(contract.get_collectibles_by_creator(alice()).len(), 1);
+
+                assert_eq!(contract.nft_tokens(None, Some(0)).len(), 0);
+                assert_eq!(contract.nft_tokens(None, Some(10)).len(), 0);
+                assert_eq!(contract.nft_tokens(Some(50.into()), None).len(), 0);
+
+                for _i in 0..20 {
+                    contract.claim_token(gate_id(1));
+                }
+            })
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(bob(), gate_id(2), 15);
+            })
+            .run_as(bob(), |contract| {
+                assert_eq!(contract.get_collectibles_by_creator(bob()).len(), 1);
+
+                for _i in 0..10 {
+                    contract.claim_token(gate_id(2));
+                }
+
+                assert_eq!(contract.nft_total_supply(), U64(30));
+                assert_eq!(contract.nft_supply_for_owner(alice()), U64(20));
+
+                assert_eq!(contract.nft_tokens(None, None).len(), 30);
+                assert_eq!(contract.nft_tokens_for_owner(alice(), None, None).len(), 20);
+
+                assert_eq!(contract.nft_tokens(None, Some(10)).len(), 10);
+                assert_eq!(contract.nft_tokens_for_owner(alice(), None, Some(10)).len(), 10);
+
+                assert_eq!(contract.nft_tokens(Some(25.into()), Some(10)).len(), 5);
+                assert_eq!(
+                    contract.nft_tokens_for_owner(alice(), Some(15.into()), Some(10)).len(),
+                    5
+                );
+            });
+    }
+}
+
+mod mint_to {
+
+    use super::*;
+    use mg_core::Role;
+
+    #[test]
+    fn mint_tokens_to_receivers() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                let token_ids = contract.mint_to(gate_id(1), vec![bob(), charlie(), bob()]);
+                assert_eq!(token_ids, vec![U64(0), U64(1), U64(2)]);
+
+                for (token_id, owner_id, edition) in
+                    vec![(0, bob(), 1), (1, charlie(), 2), (2, bob(), 3)]
+                {
+                    let token = contract.nft_token(U64(token_id)).unwrap();
+                    assert_eq!(token.owner_id, owner_id.to_string());
+                    assert_eq!(token.edition, edition);
+                }
+                assert_eq!(contract.nft_supply_for_owner(bob()), U64(2));
+                assert_eq!(contract.nft_supply_for_owner(charlie()), U64(1));
+                assert_eq!(contract.nft_total_supply(), U64(3));
+
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.current_supply, 7);
+                assert_eq!(collectible.minted_tokens, token_ids);
+                assert_eq!(contract.get_claims_by_account(gate_id(1), bob()), 0);
+            })
+            .run_as(bob(), |contract| {
+                assert_eq!(contract.claim_token(gate_id(1)), U64(3));
+            });
+    }
+
+    #[test]
+    fn mint_tokens_from_admin_ignoring_claim_rules() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                contract.set_mint_price(gate_id(1), U128(1000));
+                contract.set_max_claims_per_account(gate_id(1), Some(1));
+            })
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(charlie(), Role::Admin);
+            })
+            .run_as(charlie(), |contract| {
+                let token_ids = contract.mint_to(gate_id(1), vec![bob(), bob()]);
+                assert_eq!(token_ids.len(), 2);
+                assert_eq!(contract.nft_supply_for_owner(bob()), U64(2));
+            });
+    }
+
+    #[test]
+    fn mint_all_the_remaining_supply() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 3);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(alice(), |contract| {
+                contract.mint_to(gate_id(1), vec![charlie(), charlie()]);
+
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.current_supply, 0);
+                assert_eq!(collectible.minted_tokens.len(), 3);
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn mint_tokens_from_non_creator_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                contract.mint_to(gate_id(1), vec![bob()]);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` has only `2` tokens left to mint")]
+    fn mint_more_tokens_than_supply_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 3);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(alice(), |contract| {
+                contract.mint_to(gate_id(1), vec![bob(), charlie(), alice()]);
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Unable to mint `101` tokens in a single call, at most `100` are allowed with the attached gas"
+    )]
+    fn mint_more_tokens_than_allowed_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 200);
+            })
+            .run_as(alice(), |contract| {
+                contract.context.prepaid_gas = 1_000_000_000_000_000;
+                contract.mint_to(gate_id(1), vec![bob(); 101]);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to mint `20` tokens in a single call")]
+    fn mint_more_tokens_than_gas_allows_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 200);
+            })
+            .run_as(alice(), |contract| {
+                contract.context.prepaid_gas = 30_000_000_000_000;
+                contract.mint_to(gate_id(1), vec![bob(); 20]);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit `U128(0)` is not enough to cover storage cost")]
+    fn mint_tokens_without_deposit_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .attach_deposit(0)
+            .run_as(alice(), |contract| {
+                contract.mint_to(gate_id(1), vec![bob(), charlie()]);
+            });
+    }
+}
+
+mod vouchers {
+
+    use super::*;
+    use mg_core::{
+        voucher::{Voucher, VoucherCollectible, VoucherSigner},
+        Pausable, Role,
+    };
+    use near_sdk::json_types::Base58PublicKey;
+
+    fn signer() -> VoucherSigner {
+        VoucherSigner::from_seed(&[1; 32])
+    }
+
+    fn voucher(nonce: u64, receiver_id: Option<ValidAccountId>, price: u128) -> Voucher {
+        Voucher {
+            gate_id: gate_id(1),
+            collectible: None,
+            receiver_id: receiver_id.map(Into::into),
+            price: U128(price),
+            expires_at: 1000,
+            nonce: U64(nonce),
+        }
+    }
+
+    fn init_vouchers() -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                contract.set_voucher_key(Some(signer().public_key()));
+                assert_eq!(contract.get_voucher_key(alice()), Some(signer().public_key()));
+            });
+        context
+    }
+
+    fn redeem(contract: &mut MockedContext<NftContractChecker>, voucher: Voucher) -> TokenId {
+        let signature = signer().sign(&contract.context.current_account_id, &voucher);
+        contract.redeem_voucher(voucher, signature)
+    }
+
+    fn voucher_collectible(title: &str) -> VoucherCollectible {
+        VoucherCollectible {
+            creator_id: alice().to_string(),
+            title: title.to_string(),
+            description: "NFT description".to_string(),
+            supply: 10,
+            royalty: "5/100".parse().unwrap(),
+            media: None,
+            media_hash: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    fn init_lazy_vouchers() -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(alice(), Role::CollectibleMinter);
+            })
+            .run_as(alice(), |contract| {
+                contract.set_voucher_key(Some(signer().public_key()));
+            });
+        context
+    }
+
+    #[test]
+    fn redeem_voucher_mints_token() {
+        init_vouchers().run_as(bob(), |contract| {
+            assert!(!contract.is_voucher_redeemed(alice(), U64(7)));
+
+            let token_id = redeem(contract, voucher(7, None, 0));
+
+            let token = contract.nft_token(token_id).unwrap();
+            assert_eq!(token.owner_id, bob().to_string());
+            assert_eq!(token.edition, 1);
+            assert!(contract.is_voucher_redeemed(alice(), U64(7)));
+            assert_eq!(contract.get_claims_by_account(gate_id(1), bob()), 0);
+
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.current_supply, 9);
+            assert_eq!(collectible.minted_tokens, vec![token_id]);
+        });
+    }
+
+    #[test]
+    fn redeem_voucher_pays_creator_and_fee() {
+        init_vouchers().attach_deposit(STORAGE_DEPOSIT + 2000).run_as(bob(), |contract| {
+            redeem(contract, voucher(0, Some(bob()), 2000));
+
+            let transfers = super::mint_price::get_transfers();
+            assert_eq!(transfers.len(), 3);
+            assert_eq!(transfers[0], (alice().to_string(), 1950));
+            assert_eq!(transfers[1], (mintgate_fee_account_id().to_string(), 50));
+            assert_eq!(transfers[2].0, bob().to_string());
+        });
+    }
+
+    #[test]
+    fn redeem_vouchers_ignoring_claim_rules() {
+        init_vouchers()
+            .run_as(alice(), |contract| {
+                contract.set_mint_price(gate_id(1), U128(1000));
+                contract.set_max_claims_per_account(gate_id(1), Some(1));
+            })
+            .run_as(bob(), |contract| {
+                redeem(contract, voucher(0, None, 0));
+                redeem(contract, voucher(1, None, 0));
+                assert_eq!(contract.nft_supply_for_owner(bob()), U64(2));
+            });
+    }
+
+    #[test]
+    fn redeem_voucher_creates_its_collectible() {
+        init_lazy_vouchers().run_as(bob(), |contract| {
+            assert_eq!(contract.get_collectible_by_gate_id(gate_id(1)), None);
+
+            let collectible = Some(voucher_collectible("My collectible"));
+            let token_id = redeem(contract, Voucher { collectible, ..voucher(0, None, 0) });
+
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.creator_id, alice().to_string());
+            assert_eq!(collectible.supply, 10);
+            assert_eq!(collectible.current_supply, 9);
+            assert_eq!(collectible.minted_tokens, vec![token_id]);
+            assert_eq!(collectible.metadata.title, Some("My collectible".to_string()));
+            assert_eq!(contract.get_collectibles_by_creator(alice()), vec![collectible]);
+
+            let collectible = Some(voucher_collectible("Another title"));
+            let token_id = redeem(contract, Voucher { collectible, ..voucher(1, None, 0) });
+
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.current_supply, 8);
+            assert_eq!(collectible.metadata.title, Some("My collectible".to_string()));
+            assert_eq!(contract.nft_token(token_id).unwrap().edition, 2);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` was not found")]
+    fn redeem_voucher_without_collectible_should_panic() {
+        init_lazy_vouchers().run_as(bob(), |contract| {
+            redeem(contract, voucher(0, None, 0));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `collectible_minter` role")]
+    fn redeem_voucher_creating_collectible_without_role_should_panic() {
+        init()
+            .run_as(alice(), |contract| {
+                contract.set_voucher_key(Some(signer().public_key()));
+            })
+            .run_as(bob(), |contract| {
+                let collectible = Some(voucher_collectible("My collectible"));
+                redeem(contract, Voucher { collectible, ..voucher(0, None, 0) });
+            });
+    }
+
+    #[test]
+    fn remove_voucher_key() {
+        init_vouchers().run_as(alice(), |contract| {
+            contract.set_voucher_key(None);
+            assert_eq!(contract.get_voucher_key(alice()), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Voucher keys must be ed25519 public keys")]
+    fn set_non_ed25519_voucher_key_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.set_voucher_key(Some(Base58PublicKey(vec![1; 65])));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` has no voucher key set")]
+    fn redeem_voucher_without_creator_key_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                redeem(contract, voucher(0, None, 0));
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid voucher signature for gate ID `GPZkspuVGaZxwWoP6bJoWU`")]
+    fn redeem_voucher_signed_with_another_key_should_panic() {
+        init_vouchers().run_as(bob(), |contract| {
+            let voucher = voucher(0, None, 0);
+            let signature = VoucherSigner::from_seed(&[2; 32])
+                .sign(&contract.context.current_account_id, &voucher);
+            contract.redeem_voucher(voucher, signature);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid voucher signature for gate ID `GPZkspuVGaZxwWoP6bJoWU`")]
+    fn redeem_tampered_voucher_should_panic() {
+        init_vouchers().run_as(bob(), |contract| {
+            let mut voucher = voucher(0, None, 2000);
+            let signature = signer().sign(&contract.context.current_account_id, &voucher);
+            voucher.price = U128(0);
+            contract.redeem_voucher(voucher, signature);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid voucher signature for gate ID `GPZkspuVGaZxwWoP6bJoWU`")]
+    fn redeem_voucher_signed_for_another_contract_should_panic() {
+        init_vouchers().run_as(bob(), |contract| {
+            let voucher = voucher(0, None, 0);
+            let signature = signer().sign(&"other".to_string(), &voucher);
+            contract.redeem_voucher(voucher, signature);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `charlie` is not the receiver of the voucher for gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn redeem_voucher_of_another_receiver_should_panic() {
+        init_vouchers().run_as(charlie(), |contract| {
+            redeem(contract, voucher(0, Some(bob()), 0));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Voucher for gate ID `GPZkspuVGaZxwWoP6bJoWU` expired at `1000`")]
+    fn redeem_expired_voucher_should_panic() {
+        init_vouchers().run_as(bob(), |contract| {
+            travel_to(contract, 1000);
+            redeem(contract, voucher(0, None, 0));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Voucher nonce `U64(3)` of account `alice` has already been redeemed"
+    )]
+    fn redeem_voucher_nonce_twice_should_panic() {
+        init_vouchers()
+            .run_as(bob(), |contract| {
+                redeem(contract, voucher(3, None, 0));
+            })
+            .run_as(charlie(), |contract| {
+                redeem(contract, voucher(3, Some(charlie()), 0));
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attached deposit `U128(100000000000000000000000)` is not enough to pay the mint price `U128(100000000000000000000001)`"
+    )]
+    fn redeem_voucher_without_price_should_panic() {
+        init_vouchers().run_as(bob(), |contract| {
+            redeem(contract, voucher(0, None, STORAGE_DEPOSIT + 1));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The `minting` feature is paused")]
+    fn redeem_voucher_while_minting_is_paused_should_panic() {
+        init_vouchers()
+            .run_as(mintgate_admin(), |contract| {
+                contract.pause(Pausable::Minting);
+            })
+            .run_as(bob(), |contract| {
+                redeem(contract, voucher(0, None, 0));
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` can only be claimed by the winners of its raffle"
+    )]
+    fn redeem_voucher_with_raffle_should_panic() {
+        init_vouchers()
+            .run_as(alice(), |contract| {
+                contract.create_raffle(gate_id(1), 100, U128(0));
+            })
+            .run_as(bob(), |contract| {
+                redeem(contract, voucher(0, None, 0));
+            });
+    }
+}
+
+mod linkdrops {
+
+    use super::*;
+    use mg_core::mocked_context::nft;
+    use mg_nft::SelfCallback;
+    use near_sdk::{json_types::Base58PublicKey, PromiseResult};
+
+    /// Deposit attached to fund each linkdrop.
+    const LINKDROP_DEPOSIT: u128 = 1_000_000_000_000_000_000_000_000;
+
+    fn linkdrop_key(n: u8) -> Base58PublicKey {
+        let mut public_key = vec![0];
+        public_key.extend_from_slice(&[n; 32]);
+        Base58PublicKey(public_key)
+    }
+
+    /// Runs `action` as this contract, signed with the access key of the linkdrop `n`.
+    fn sign_with(
+        context: &mut MockedContext<NftContractChecker>,
+        n: u8,
+        action: impl FnOnce(&mut MockedContext<NftContractChecker>),
+    ) {
+        context.context.signer_account_pk = linkdrop_key(n).0;
+        context.run_as(nft(), action);
+    }
+
+    /// Resolves the access key of the linkdrop `n` as added by the given `result`.
+    fn resolve_add_linkdrop(
+        context: &mut MockedContext<NftContractChecker>,
+        n: u8,
+        result: PromiseResult,
+    ) -> Option<TokenId> {
+        let mut token_id = None;
+        context.promise_results(vec![result]).run_as(nft(), |contract| {
+            token_id = contract.resolve_add_linkdrop(linkdrop_key(n));
+        });
+        token_id
+    }
+
+    fn init_linkdrop() -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context.context.current_account_id = nft().to_string();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .attach_deposit(LINKDROP_DEPOSIT)
+            .run_as(alice(), |contract| {
+                contract.add_linkdrop(gate_id(1), linkdrop_key(1));
+                assert_eq!(contract.get_linkdrop(linkdrop_key(1)), None);
+            })
+            .attach_deposit(0);
+        let token_id = resolve_add_linkdrop(&mut context, 1, PromiseResult::Successful(Vec::new()));
+        assert_eq!(token_id, Some(U64(0)));
+        context
+    }
+
+    #[test]
+    fn add_linkdrop_holds_its_token() {
+        init_linkdrop().run_as(alice(), |contract| {
+            let linkdrop = contract.get_linkdrop(linkdrop_key(1)).unwrap();
+            assert_eq!(linkdrop.token_id, U64(0));
+            assert_eq!(linkdrop.funder_id, alice().to_string());
+            assert!(linkdrop.amount.0 > 0);
+            assert!(linkdrop.amount.0 < LINKDROP_DEPOSIT - 100_000_000_000_000_000_000_000);
+
+            let token = contract.nft_token(U64(0)).unwrap();
+            assert_eq!(token.owner_id, nft().to_string());
+            assert_eq!(token.edition, 1);
+
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.current_supply, 9);
+            assert_eq!(contract.get_linkdrop(linkdrop_key(2)), None);
+        });
+    }
+
+    #[test]
+    fn claim_linkdrop_into_existing_account() {
+        let mut context = init_linkdrop();
+        sign_with(&mut context, 1, |contract| {
+            assert_eq!(contract.claim_linkdrop(bob()), U64(0));
+
+            let token = contract.nft_token(U64(0)).unwrap();
+            assert_eq!(token.owner_id, bob().to_string());
+            assert_eq!(contract.nft_supply_for_owner(nft()), U64(0));
+            assert_eq!(contract.get_linkdrop(linkdrop_key(1)), None);
+        });
+    }
+
+    #[test]
+    fn claim_linkdrop_of_non_transferable_collectible() {
+        let mut context = init_linkdrop();
+        context.run_as(alice(), |contract| {
+            contract.set_transferability(gate_id(1), Transferability::NonTransferable);
+        });
+        sign_with(&mut context, 1, |contract| {
+            contract.claim_linkdrop(bob());
+            assert_eq!(contract.nft_token(U64(0)).unwrap().owner_id, bob().to_string());
+        });
+    }
+
+    #[test]
+    fn create_account_and_claim_linkdrop_takes_it() {
+        let mut context = init_linkdrop();
+        sign_with(&mut context, 1, |contract| {
+            contract
+                .create_account_and_claim_linkdrop("fan.nft".try_into().unwrap(), linkdrop_key(9));
+            assert_eq!(contract.get_linkdrop(linkdrop_key(1)), None);
+            assert_eq!(contract.nft_token(U64(0)).unwrap().owner_id, nft().to_string());
+        });
+    }
+
+    #[test]
+    fn remove_linkdrop_gives_back_its_token() {
+        init_linkdrop().run_as(alice(), |contract| {
+            contract.remove_linkdrop(linkdrop_key(1));
+            assert_eq!(contract.get_linkdrop(linkdrop_key(1)), None);
+            assert_eq!(contract.nft_token(U64(0)).unwrap().owner_id, alice().to_string());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "No linkdrop was found for the given public key")]
+    fn claim_linkdrop_twice_should_panic() {
+        let mut context = init_linkdrop();
+        sign_with(&mut context, 1, |contract| {
+            contract.claim_linkdrop(bob());
+        });
+        sign_with(&mut context, 1, |contract| {
+            contract.claim_linkdrop(charlie());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "No linkdrop was found for the given public key")]
+    fn claim_linkdrop_with_another_key_should_panic() {
+        let mut context = init_linkdrop();
+        sign_with(&mut context, 2, |contract| {
+            contract.claim_linkdrop(bob());
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `fan` cannot be created, it must be a sub-account of either this contract or a top-level account"
+    )]
+    fn create_top_level_account_should_panic() {
+        let mut context = init_linkdrop();
+        sign_with(&mut context, 1, |contract| {
+            contract.create_account_and_claim_linkdrop("fan".try_into().unwrap(), linkdrop_key(9));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "A linkdrop already exists for the given public key")]
+    fn add_linkdrop_with_same_key_should_panic() {
+        init_linkdrop().attach_deposit(LINKDROP_DEPOSIT).run_as(alice(), |contract| {
+            contract.add_linkdrop(gate_id(1), linkdrop_key(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn add_linkdrop_from_non_creator_should_panic() {
+        init_linkdrop().attach_deposit(LINKDROP_DEPOSIT).run_as(bob(), |contract| {
+            contract.add_linkdrop(gate_id(1), linkdrop_key(2));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "is not enough to fund a linkdrop")]
+    fn add_linkdrop_without_allowance_should_panic() {
+        init_linkdrop().attach_deposit(STORAGE_DEPOSIT).run_as(alice(), |contract| {
+            contract.add_linkdrop(gate_id(1), linkdrop_key(2));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `bob` did not fund the linkdrop")]
+    fn remove_linkdrop_from_non_funder_should_panic() {
+        init_linkdrop().run_as(bob(), |contract| {
+            contract.remove_linkdrop(linkdrop_key(1));
+        });
+    }
+
+    #[test]
+    fn add_linkdrop_of_held_key_gives_back_its_token() {
+        let mut context = init_linkdrop();
+        context.attach_deposit(LINKDROP_DEPOSIT).run_as(alice(), |contract| {
+            contract.add_linkdrop(gate_id(1), linkdrop_key(2));
+            assert_eq!(contract.nft_token(U64(1)).unwrap().owner_id, nft().to_string());
+        });
+        assert_eq!(resolve_add_linkdrop(&mut context, 2, PromiseResult::Failed), None);
+
+        context.run_as(alice(), |contract| {
+            assert_eq!(contract.get_linkdrop(linkdrop_key(2)), None);
+            assert_eq!(contract.nft_token(U64(1)).unwrap().owner_id, alice().to_string());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "No linkdrop was found for the given public key")]
+    fn remove_linkdrop_of_held_key_should_panic() {
+        let mut context = init_linkdrop();
+        context.attach_deposit(LINKDROP_DEPOSIT).run_as(alice(), |contract| {
+            contract.add_linkdrop(gate_id(1), linkdrop_key(2));
+        });
+        resolve_add_linkdrop(&mut context, 2, PromiseResult::Failed);
+
+        context.run_as(alice(), |contract| {
+            contract.remove_linkdrop(linkdrop_key(2));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "No linkdrop was found for the given public key")]
+    fn claim_linkdrop_before_its_key_is_added_should_panic() {
+        let mut context = init_linkdrop();
+        context.attach_deposit(LINKDROP_DEPOSIT).run_as(alice(), |contract| {
+            contract.add_linkdrop(gate_id(1), linkdrop_key(2));
+        });
+        sign_with(&mut context, 2, |contract| {
+            contract.claim_linkdrop(bob());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "A linkdrop already exists for the given public key")]
+    fn add_linkdrop_with_pending_key_should_panic() {
+        init_linkdrop().attach_deposit(LINKDROP_DEPOSIT).run_as(alice(), |contract| {
+            contract.add_linkdrop(gate_id(1), linkdrop_key(2));
+            contract.add_linkdrop(gate_id(1), linkdrop_key(2));
+        });
+    }
+}
+
+mod nft_token_uri {
+
+    use super::*;
+
+    #[test]
+    fn get_token_uri_with_base_uri() {
+        init_contract("5/100", "30/100", metadata(base_uri())).run_as(
+            mintgate_admin(),
+            |contract| {
+                assert_eq!(contract.nft_token_uri(U64(0)), None);
+
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                let uri = format!("{}{}", base_uri().unwrap(), gate_id(1));
+                let token_id = contract.claim_token(gate_id(1));
+                assert_eq!(contract.nft_token_uri(token_id).unwrap(), uri);
+            },
+        );
+    }
+
+    #[test]
+    fn get_token_uri_with_no_slash_base_uri() {
+        init_contract("5/100", "30/100", metadata(Some("https://mintgate/t".to_string()))).run_as(
+            mintgate_admin(),
+            |contract| {
+                assert_eq!(contract.nft_token_uri(U64(0)), None);
+
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                let uri = format!("{}/{}", "https://mintgate/t", gate_id(1));
                 let token_id = contract.claim_token(gate_id(1));
                 assert_eq!(contract.nft_token_uri(token_id).unwrap(), uri);
             },
@@ -703,73 +2016,1995 @@ mod nft_token_uri {
     }
 
     #[test]
-    fn get_token_uri_with_no_slash_base_uri() {
-        init_contract("5/100", "30/100", metadata(Some("https://mintgate/t".to_string()))).run_as(
-            mintgate_admin(),
-            |contract| {
-                assert_eq!(contract.nft_token_uri(U64(0)), None);
+    fn get_token_uri_with_none_base_uri() {
+        init_contract("5/100", "30/100", metadata(None)).run_as(mintgate_admin(), |contract| {
+            assert_eq!(contract.nft_token_uri(U64(0)), None);
+
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            let token_id = contract.claim_token(gate_id(1));
+            assert_eq!(contract.nft_token_uri(token_id), None);
+        });
+    }
+}
+mod burn_token {
+
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Token ID `U64(0)` was not found")]
+    fn burn_a_non_existent_token_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.burn_token(0.into());
+        });
+    }
+
+    #[test]
+    fn burn_a_few_tokens() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            let token_id = contract.claim_token(gate_id(1));
+            contract.burn_token(token_id);
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.metadata.copies.unwrap(), 9);
+
+            let token_id = contract.claim_token(gate_id(1));
+            contract.nft_approve(token_id, bob(), approve_msg(10));
+            contract.burn_token(token_id);
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.metadata.copies.unwrap(), 8);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Token ID `U64(0)` does not belong to account `bob`")]
+    fn transfer_a_non_approved_token_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.burn_token(token_id);
+            });
+    }
+}
+
+mod storage_deposit {
+
+    use super::*;
+    use near_sdk::{env, test_utils::get_created_receipts};
+
+    #[test]
+    #[should_panic(expected = "Attached deposit `U128(0)` is not enough to cover storage cost")]
+    fn create_collectible_without_deposit_should_panic() {
+        init().attach_deposit(0).run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit `U128(0)` is not enough to cover storage cost")]
+    fn claim_token_without_deposit_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .attach_deposit(0)
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            });
+    }
+
+    #[test]
+    fn claim_token_refunds_excess_deposit() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                contract.contract.claim_token(gate_id(1));
+                assert_eq!(get_created_receipts().len(), 1);
+            });
+    }
+
+    #[test]
+    fn claim_token_with_exact_deposit() {
+        let mut storage_cost = 0;
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                contract.contract.claim_token(gate_id(1));
+
+                let storage_usage = env::storage_usage();
+                contract.contract.claim_token(gate_id(1));
+                storage_cost =
+                    u128::from(env::storage_usage() - storage_usage) * env::storage_byte_cost();
+                assert!(storage_cost > 0);
+            })
+            .attach_deposit(storage_cost)
+            .run_as(bob(), |contract| {
+                contract.contract.claim_token(gate_id(1));
+                assert_eq!(get_created_receipts().len(), 0);
+            });
+    }
+}
+
+mod mint_price {
+
+    use super::*;
+    use near_sdk::{serde::Deserialize, test_utils::get_created_receipts};
+
+    #[derive(Deserialize)]
+    #[serde(crate = "near_sdk::serde")]
+    struct Receipt {
+        receiver_id: String,
+        actions: Vec<Action>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(crate = "near_sdk::serde")]
+    enum Action {
+        Transfer { deposit: u128 },
+    }
+
+    /// Returns the transfers made by the contract, in order.
+    pub fn get_transfers() -> Vec<(String, u128)> {
+        let receipts = serde_json::to_string(&get_created_receipts()).unwrap();
+        serde_json::from_str::<Vec<Receipt>>(&receipts)
+            .unwrap()
+            .into_iter()
+            .flat_map(|Receipt { receiver_id, actions }| {
+                actions
+                    .into_iter()
+                    .map(move |Action::Transfer { deposit }| (receiver_id.clone(), deposit))
+            })
+            .collect()
+    }
+
+    fn init_priced(mint_price: u128) -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                contract.set_mint_price(gate_id(1), mint_price.into());
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.mint_price, mint_price.into());
+            });
+        context
+    }
+
+    #[test]
+    fn claim_token_pays_creator_and_fee() {
+        init_priced(2000).attach_deposit(STORAGE_DEPOSIT + 2000).run_as(bob(), |contract| {
+            contract.contract.claim_token(gate_id(1));
+
+            let transfers = get_transfers();
+            assert_eq!(transfers.len(), 3);
+            assert_eq!(transfers[0], (alice().to_string(), 1950));
+            assert_eq!(transfers[1], (mintgate_fee_account_id().to_string(), 50));
+            assert_eq!(transfers[2].0, bob().to_string());
+            assert!(transfers[2].1 < STORAGE_DEPOSIT);
+        });
+    }
+
+    #[test]
+    fn claim_token_refunds_overpayment() {
+        let mut refund = 0;
+        init_priced(2000)
+            .attach_deposit(STORAGE_DEPOSIT + 2000)
+            .run_as(bob(), |contract| {
+                contract.contract.claim_token(gate_id(1));
+            })
+            .run_as(bob(), |contract| {
+                contract.contract.claim_token(gate_id(1));
+                refund = get_transfers()[2].1;
+            })
+            .attach_deposit(STORAGE_DEPOSIT + 2000 + 500)
+            .run_as(bob(), |contract| {
+                contract.contract.claim_token(gate_id(1));
+
+                let transfers = get_transfers();
+                assert_eq!(transfers[0], (alice().to_string(), 1950));
+                assert_eq!(transfers[2], (bob().to_string(), refund + 500));
+            });
+    }
+
+    #[test]
+    fn claim_token_after_price_is_changed() {
+        init_priced(2000)
+            .run_as(alice(), |contract| {
+                contract.set_mint_price(gate_id(1), 0.into());
+            })
+            .run_as(bob(), |contract| {
+                contract.contract.claim_token(gate_id(1));
+
+                let transfers = get_transfers();
+                assert_eq!(transfers.len(), 1);
+                assert_eq!(transfers[0].0, bob().to_string());
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attached deposit `U128(1999)` is not enough to pay the mint price `U128(2000)`"
+    )]
+    fn claim_token_without_paying_mint_price_should_panic() {
+        init_priced(2000).attach_deposit(1999).run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit `U128(0)` is not enough to cover storage cost")]
+    fn claim_token_paying_only_mint_price_should_panic() {
+        init_priced(2000).attach_deposit(2000).run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `admin` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn set_mint_price_from_non_creator_should_panic() {
+        init_priced(2000).run_as(mintgate_admin(), |contract| {
+            contract.set_mint_price(gate_id(1), 0.into());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` was not found")]
+    fn set_mint_price_of_non_existent_collectible_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.set_mint_price(gate_id(1), 0.into());
+        });
+    }
+}
+
+mod allowlist {
+
+    use super::*;
+    use mg_core::merkle::MerkleTree;
+    use near_sdk::json_types::Base58CryptoHash;
+
+    fn allowlist() -> MerkleTree {
+        MerkleTree::new(&[alice().to_string(), bob().to_string(), any().to_string()])
+    }
+
+    fn proof(account_id: ValidAccountId) -> Vec<Base58CryptoHash> {
+        allowlist().proof(account_id.as_ref()).unwrap().into_iter().map(Into::into).collect()
+    }
+
+    fn init_allowlisted() -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                contract.set_allowlist_root(gate_id(1), Some(allowlist().root().into()));
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.allowlist_root, Some(allowlist().root().into()));
+            });
+        context
+    }
+
+    #[test]
+    fn claim_token_with_valid_proof() {
+        init_allowlisted()
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token_with_proof(gate_id(1), proof(bob()));
+                assert_eq!(contract.nft_token(token_id).unwrap().owner_id, bob().to_string());
+            })
+            .run_as(any(), |contract| {
+                contract.claim_token_with_proof(gate_id(1), proof(any()));
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.current_supply, 8);
+            });
+    }
+
+    #[test]
+    fn claim_token_after_allowlist_is_removed() {
+        init_allowlisted()
+            .run_as(alice(), |contract| {
+                contract.set_allowlist_root(gate_id(1), None);
+            })
+            .run_as(charlie(), |contract| {
+                contract.claim_token(gate_id(1));
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` requires an allowlist proof to claim"
+    )]
+    fn claim_token_without_proof_should_panic() {
+        init_allowlisted().run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `charlie` is not in the allowlist of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_token_with_proof_of_another_account_should_panic() {
+        init_allowlisted().run_as(charlie(), |contract| {
+            contract.claim_token_with_proof(gate_id(1), proof(bob()));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` is not in the allowlist of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_token_with_invalid_proof_should_panic() {
+        init_allowlisted().run_as(bob(), |contract| {
+            let mut proof = proof(bob());
+            proof.pop();
+            contract.claim_token_with_proof(gate_id(1), proof);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has already claimed gate ID `GPZkspuVGaZxwWoP6bJoWU` with its allowlist proof"
+    )]
+    fn claim_token_with_reused_proof_should_panic() {
+        init_allowlisted()
+            .run_as(bob(), |contract| {
+                contract.claim_token_with_proof(gate_id(1), proof(bob()));
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token_with_proof(gate_id(1), proof(bob()));
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `admin` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn set_allowlist_root_from_non_creator_should_panic() {
+        init_allowlisted().run_as(mintgate_admin(), |contract| {
+            contract.set_allowlist_root(gate_id(1), None);
+        });
+    }
+}
+
+mod claim_codes {
+
+    use super::*;
+    use mg_core::claim_code;
+    use near_sdk::CryptoHash;
+
+    fn codes() -> Vec<(String, CryptoHash)> {
+        claim_code::generate(b"secret", 0, 3)
+    }
+
+    fn init_with_codes() -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                let code_hashes = codes().into_iter().map(|(_, hash)| hash.into()).collect();
+                contract.add_claim_codes(gate_id(1), code_hashes);
+                assert_eq!(contract.get_claim_codes_left(gate_id(1)), 3);
+            });
+        context
+    }
+
+    #[test]
+    fn claim_with_valid_codes() {
+        init_with_codes()
+            .run_as(bob(), |contract| {
+                let (code, hash) = codes().remove(0);
+                assert!(contract.is_claim_code_available(gate_id(1), hash.into()));
+
+                let token_id = contract.claim_with_code(gate_id(1), code);
+                assert_eq!(contract.nft_token(token_id).unwrap().owner_id, bob().to_string());
+                assert!(!contract.is_claim_code_available(gate_id(1), hash.into()));
+                assert_eq!(contract.get_claim_codes_left(gate_id(1)), 2);
+            })
+            .run_as(charlie(), |contract| {
+                contract.claim_with_code(gate_id(1), codes().remove(2).0);
+                assert_eq!(contract.get_claims_by_account(gate_id(1), charlie()), 1);
+
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.current_supply, 8);
+            });
+    }
+
+    #[test]
+    fn add_duplicated_codes() {
+        init_with_codes().run_as(alice(), |contract| {
+            let code_hashes = claim_code::generate(b"secret", 2, 2)
+                .into_iter()
+                .map(|(_, hash)| hash.into())
+                .collect();
+            contract.add_claim_codes(gate_id(1), code_hashes);
+            assert_eq!(contract.get_claim_codes_left(gate_id(1)), 4);
+        });
+    }
+
+    #[test]
+    fn delete_collectible_removes_its_codes() {
+        init_with_codes().run_as(alice(), |contract| {
+            contract.delete_collectible(gate_id(1));
+            assert_eq!(contract.get_claim_codes_left(gate_id(1)), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid or already used claim code for gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_with_used_code_should_panic() {
+        init_with_codes()
+            .run_as(bob(), |contract| {
+                contract.claim_with_code(gate_id(1), codes().remove(1).0);
+            })
+            .run_as(charlie(), |contract| {
+                contract.claim_with_code(gate_id(1), codes().remove(1).0);
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid or already used claim code for gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_with_unknown_code_should_panic() {
+        init_with_codes().run_as(bob(), |contract| {
+            let code = claim_code::generate(b"another secret", 0, 1).remove(0).0;
+            contract.claim_with_code(gate_id(1), code);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid or already used claim code for gate ID `Tj6wXP9tHBR2sGBEBkUyho`"
+    )]
+    fn claim_with_code_of_another_collectible_should_panic() {
+        init_with_codes()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(2), 10);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_with_code(gate_id(2), codes().remove(0).0);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` requires a claim code to claim")]
+    fn claim_token_without_code_should_panic() {
+        init_with_codes().run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn add_claim_codes_from_non_creator_should_panic() {
+        init_with_codes().run_as(bob(), |contract| {
+            contract.add_claim_codes(gate_id(1), vec![]);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit `U128(0)` is not enough to cover storage cost")]
+    fn add_claim_codes_without_deposit_should_panic() {
+        init_with_codes().attach_deposit(0).run_as(alice(), |contract| {
+            let code_hashes = claim_code::generate(b"secret", 3, 5)
+                .into_iter()
+                .map(|(_, hash)| hash.into())
+                .collect();
+            contract.add_claim_codes(gate_id(1), code_hashes);
+        });
+    }
+}
+
+mod raffles {
+
+    use super::*;
+    use mg_core::raffle::RaffleEntry;
+
+    const ENTRY_DEPOSIT: u128 = STORAGE_DEPOSIT;
+
+    fn init_raffle(supply: u16) -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), supply);
+            })
+            .run_as(alice(), |contract| {
+                contract.create_raffle(gate_id(1), 100, ENTRY_DEPOSIT.into());
+                let raffle = contract.get_raffle(gate_id(1)).unwrap();
+                assert_eq!(raffle.ends_at, 100);
+                assert_eq!(raffle.entry_deposit, ENTRY_DEPOSIT.into());
+                assert_eq!(raffle.entries, 0);
+                assert_eq!(raffle.winners, None);
+            });
+        context
+    }
+
+    fn enter(context: &mut MockedContext<NftContractChecker>, entrants: &[ValidAccountId]) {
+        context.attach_deposit(ENTRY_DEPOSIT + STORAGE_DEPOSIT);
+        for entrant in entrants {
+            context.run_as(entrant.clone(), |contract| {
+                contract.enter_raffle(gate_id(1));
+                let entry = contract.get_raffle_entry(gate_id(1), entrant.clone());
+                assert_eq!(entry, Some(RaffleEntry::Entered));
+            });
+        }
+    }
+
+    fn draw(context: &mut MockedContext<NftContractChecker>) -> Vec<ValidAccountId> {
+        travel_to(context, 100);
+        context.run_as(any(), |contract| {
+            assert_eq!(contract.draw_raffle(gate_id(1)), 0);
+        });
+        winners(context)
+    }
+
+    fn winners(context: &MockedContext<NftContractChecker>) -> Vec<ValidAccountId> {
+        vec![bob(), charlie(), market()]
+            .into_iter()
+            .filter(|account_id| {
+                context.get_raffle_entry(gate_id(1), account_id.clone()) == Some(RaffleEntry::Won)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draw_up_to_supply_winners() {
+        let mut context = init_raffle(2);
+        enter(&mut context, &[bob(), charlie(), market()]);
+        let winners = draw(&mut context);
+        assert_eq!(winners.len(), 2);
+
+        let raffle = context.get_raffle(gate_id(1)).unwrap();
+        assert_eq!(raffle.entries, 3);
+        assert_eq!(raffle.winners, Some(2));
+        assert_eq!(raffle.drawn, 2);
+    }
+
+    #[test]
+    fn draw_every_entry_when_there_is_enough_supply() {
+        let mut context = init_raffle(10);
+        enter(&mut context, &[bob(), charlie()]);
+        assert_eq!(draw(&mut context), vec![bob(), charlie()]);
+    }
+
+    #[test]
+    fn draw_without_entries() {
+        let mut context = init_raffle(10);
+        assert_eq!(draw(&mut context), vec![]);
+        assert!(context.get_raffle(gate_id(1)).unwrap().is_drawn());
+    }
+
+    #[test]
+    fn draw_continues_when_gas_runs_out() {
+        let mut context = init_raffle(2);
+        enter(&mut context, &[bob(), charlie(), market()]);
+        travel_to(&mut context, 100);
+
+        context.context.prepaid_gas = 3_000_000_000_000;
+        context.run_as(any(), |contract| {
+            assert_eq!(contract.draw_raffle(gate_id(1)), 2);
+            let raffle = contract.get_raffle(gate_id(1)).unwrap();
+            assert_eq!(raffle.winners, Some(2));
+            assert_eq!(raffle.drawn, 0);
+        });
+
+        context.context.prepaid_gas = 300_000_000_000_000;
+        context.run_as(any(), |contract| {
+            assert_eq!(contract.draw_raffle(gate_id(1)), 0);
+        });
+        assert_eq!(winners(&context).len(), 2);
+    }
+
+    #[test]
+    fn winners_claim_with_their_entry_deposit() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob(), charlie()]);
+        let winner = draw(&mut context).remove(0);
+
+        context.attach_deposit(0).run_as(winner.clone(), |contract| {
+            contract.contract.claim_token(gate_id(1));
+
+            let (receiver_id, refund) = super::mint_price::get_transfers().pop().unwrap();
+            assert_eq!(receiver_id, winner.to_string());
+            assert!(refund < ENTRY_DEPOSIT);
+            assert_eq!(contract.get_raffle_entry(gate_id(1), winner.clone()), None);
+        });
+    }
+
+    #[test]
+    fn losers_withdraw_their_entry_deposit() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob(), charlie()]);
+        let winner = draw(&mut context).remove(0);
+        let loser = if winner == bob() { charlie() } else { bob() };
+
+        context.run_as(loser.clone(), |contract| {
+            contract.withdraw_raffle_entry(gate_id(1));
+            assert_eq!(contract.get_raffle_entry(gate_id(1), loser.clone()), None);
+
+            let transfers = super::mint_price::get_transfers();
+            assert_eq!(transfers, vec![(loser.to_string(), ENTRY_DEPOSIT)]);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has not won the raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_before_draw_should_panic() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob()]);
+        context.run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "has not won the raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU`")]
+    fn loser_claim_should_panic() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob(), charlie()]);
+        let winner = draw(&mut context).remove(0);
+        let loser = if winner == bob() { charlie() } else { bob() };
+
+        context.run_as(loser, |contract| {
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has not won the raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_twice_should_panic() {
+        let mut context = init_raffle(10);
+        enter(&mut context, &[bob()]);
+        draw(&mut context);
+        context.run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has already entered the raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn enter_twice_should_panic() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob(), bob()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU` ended at `100`")]
+    fn enter_after_end_should_panic() {
+        let mut context = init_raffle(1);
+        travel_to(&mut context, 100);
+        enter(&mut context, &[bob()]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attached deposit `U128(1000)` is not enough to enter the raffle, `U128(100000000000000000000000)` is required"
+    )]
+    fn enter_without_deposit_should_panic() {
+        init_raffle(1).attach_deposit(1000).run_as(bob(), |contract| {
+            contract.enter_raffle(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU` ends at `100`, its winners cannot be drawn yet"
+    )]
+    fn draw_before_end_should_panic() {
+        init_raffle(1).run_as(any(), |contract| {
+            contract.draw_raffle(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Winners of the raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU` have already been drawn"
+    )]
+    fn draw_twice_should_panic() {
+        let mut context = init_raffle(1);
+        draw(&mut context);
+        draw(&mut context);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Winners of the raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU` have not been drawn yet"
+    )]
+    fn withdraw_before_draw_should_panic() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob()]);
+        context.run_as(bob(), |contract| {
+            contract.withdraw_raffle_entry(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `charlie` has no entry in the raffle of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn withdraw_without_entry_should_panic() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob()]);
+        draw(&mut context);
+        context.run_as(charlie(), |contract| {
+            contract.withdraw_raffle_entry(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn create_raffle_by_non_creator_should_panic() {
+        init_raffle(1).run_as(bob(), |contract| {
+            contract.create_raffle(gate_id(1), 200, ENTRY_DEPOSIT.into());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` already has a raffle")]
+    fn create_raffle_twice_should_panic() {
+        init_raffle(1).run_as(alice(), |contract| {
+            contract.create_raffle(gate_id(1), 200, ENTRY_DEPOSIT.into());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` has raffle entries")]
+    fn delete_collectible_with_entries_should_panic() {
+        let mut context = init_raffle(1);
+        enter(&mut context, &[bob()]);
+        context.run_as(alice(), |contract| {
+            contract.delete_collectible(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` can only be claimed by the winners of its raffle"
+    )]
+    fn mint_to_with_raffle_should_panic() {
+        let mut context = init_raffle(2);
+        enter(&mut context, &[bob(), charlie()]);
+        draw(&mut context);
+        context.run_as(alice(), |contract| {
+            contract.mint_to(gate_id(1), vec![alice()]);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` can only be claimed by the winners of its raffle"
+    )]
+    fn add_linkdrop_with_raffle_should_panic() {
+        init_raffle(2).run_as(alice(), |contract| {
+            let public_key = near_sdk::json_types::Base58PublicKey(vec![0; 33]);
+            contract.add_linkdrop(gate_id(1), public_key);
+        });
+    }
+}
+
+mod claim_limits {
+
+    use super::*;
+
+    fn init_limited(max_claims_per_account: u16) -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                contract.create_test_collectible(alice(), gate_id(2), 10);
+            })
+            .run_as(alice(), |contract| {
+                contract.set_max_claims_per_account(gate_id(1), Some(max_claims_per_account));
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.max_claims_per_account, Some(max_claims_per_account));
+            });
+        context
+    }
+
+    #[test]
+    fn claim_tokens_up_to_the_limit() {
+        init_limited(2)
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+                contract.claim_token(gate_id(1));
+                assert_eq!(contract.get_claims_by_account(gate_id(1), bob()), 2);
+
+                contract.claim_token(gate_id(2));
+                contract.claim_token(gate_id(2));
+                contract.claim_token(gate_id(2));
+                assert_eq!(contract.get_claims_by_account(gate_id(2), bob()), 3);
+            })
+            .run_as(charlie(), |contract| {
+                contract.claim_token(gate_id(1));
+                assert_eq!(contract.get_claims_by_account(gate_id(1), charlie()), 1);
+                assert_eq!(contract.get_claims_by_account(gate_id(1), any()), 0);
+            });
+    }
+
+    #[test]
+    fn claim_tokens_after_limit_is_removed() {
+        init_limited(1)
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(alice(), |contract| {
+                contract.set_max_claims_per_account(gate_id(1), None);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+                assert_eq!(contract.get_claims_by_account(gate_id(1), bob()), 2);
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has already claimed `2` tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_tokens_over_the_limit_should_panic() {
+        init_limited(2).run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+            contract.claim_token(gate_id(1));
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has already claimed `1` tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_a_token_after_transferring_it_should_panic() {
+        init_limited(1).run_as(bob(), |contract| {
+            let token_id = contract.claim_token(gate_id(1));
+            contract.nft_transfer(charlie(), token_id, None, None);
+            assert_eq!(contract.nft_supply_for_owner(bob()), U64(0));
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has already claimed `1` tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn claim_a_token_after_burning_it_should_panic() {
+        init_limited(1).run_as(bob(), |contract| {
+            let token_id = contract.claim_token(gate_id(1));
+            contract.burn_token(token_id);
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` has already claimed `1` tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn lower_the_limit_below_claimed_tokens_should_panic() {
+        init_limited(5)
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(alice(), |contract| {
+                contract.set_max_claims_per_account(gate_id(1), Some(1));
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `admin` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn set_max_claims_per_account_from_non_creator_should_panic() {
+        init_limited(1).run_as(mintgate_admin(), |contract| {
+            contract.set_max_claims_per_account(gate_id(1), None);
+        });
+    }
+}
+
+mod claim_window {
+
+    use super::*;
+    use mg_core::ClaimStatus;
+
+    fn create_scheduled_collectible(
+        contract: &mut MockedContext<NftContractChecker>,
+        starts_at: Option<u64>,
+        claims_end_at: Option<u64>,
+        expires_at: Option<u64>,
+    ) {
+        contract.contract.create_collectible(
+            alice(),
+            gate_id(1),
+            "My collectible".to_string(),
+            "NFT description".to_string(),
+            10,
+            "5/100".parse().unwrap(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            starts_at,
+            claims_end_at,
+            expires_at,
+        );
+    }
+
+    fn init_scheduled() -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context.run_as(mintgate_admin(), |contract| {
+            create_scheduled_collectible(contract, Some(10), Some(20), Some(30));
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.metadata.starts_at, Some(10));
+            assert_eq!(collectible.claims_end_at, Some(20));
+            assert_eq!(collectible.metadata.expires_at, Some(30));
+        });
+        context
+    }
+
+    #[test]
+    fn claim_status_follows_schedule() {
+        init_scheduled().run_as(bob(), |contract| {
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Upcoming);
+
+            travel_to(contract, 10);
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Live);
+            contract.claim_token(gate_id(1));
+
+            travel_to(contract, 19);
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Live);
+            contract.claim_token(gate_id(1));
+
+            travel_to(contract, 20);
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Ended);
+        });
+    }
+
+    #[test]
+    fn unscheduled_collectible_is_live_right_away() {
+        init().run_as(mintgate_admin(), |contract| {
+            travel_to(contract, 42);
+            create_scheduled_collectible(contract, None, None, None);
+            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+            assert_eq!(collectible.metadata.starts_at, Some(42));
+            assert_eq!(collectible.claims_end_at, None);
+            assert_eq!(collectible.metadata.expires_at, None);
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Live);
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Claims for gate ID `GPZkspuVGaZxwWoP6bJoWU` start at `10`")]
+    fn claim_a_token_before_start_should_panic() {
+        init_scheduled().run_as(bob(), |contract| {
+            travel_to(contract, 9);
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Claims for gate ID `GPZkspuVGaZxwWoP6bJoWU` ended at `20`")]
+    fn claim_a_token_after_end_should_panic() {
+        init_scheduled().run_as(bob(), |contract| {
+            travel_to(contract, 20);
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    fn claims_end_at_expiration() {
+        init().run_as(mintgate_admin(), |contract| {
+            create_scheduled_collectible(contract, Some(10), None, Some(30));
+            travel_to(contract, 29);
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Live);
+            contract.claim_token(gate_id(1));
+
+            travel_to(contract, 30);
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Ended);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Claims for gate ID `GPZkspuVGaZxwWoP6bJoWU` ended at `30`")]
+    fn claim_an_expired_token_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            create_scheduled_collectible(contract, Some(10), Some(40), Some(30));
+            travel_to(contract, 35);
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    fn has_access_after_claims_end() {
+        init_scheduled().run_as(bob(), |contract| {
+            travel_to(contract, 10);
+            let token_id = contract.claim_token(gate_id(1));
+
+            travel_to(contract, 20);
+            assert_eq!(contract.get_claim_status(gate_id(1)), ClaimStatus::Ended);
+            assert_eq!(contract.has_access(bob(), gate_id(1)), vec![token_id]);
+
+            travel_to(contract, 30);
+            assert_eq!(contract.has_access(bob(), gate_id(1)), Vec::<TokenId>::new());
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid argument for gate ID `GPZkspuVGaZxwWoP6bJoWU`: `starts_at` must be before `claims_end_at`"
+    )]
+    fn create_a_collectible_ending_before_start_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            create_scheduled_collectible(contract, Some(20), Some(20), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid argument for gate ID `GPZkspuVGaZxwWoP6bJoWU`: `starts_at` must be before `expires_at`"
+    )]
+    fn create_a_collectible_expiring_before_start_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            create_scheduled_collectible(contract, Some(20), None, Some(20));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` was not found")]
+    fn claim_status_of_non_existent_collectible_should_panic() {
+        init().run_as(bob(), |contract| {
+            contract.get_claim_status(gate_id(1));
+        });
+    }
+}
+
+mod has_access {
+
+    use super::*;
+
+    #[test]
+    fn has_access_with_claimed_tokens() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                contract.create_test_collectible(alice(), gate_id(2), 10);
+            })
+            .run_as(bob(), |contract| {
+                assert_eq!(contract.has_access(bob(), gate_id(1)), Vec::<TokenId>::new());
+
+                let token_id1 = contract.claim_token(gate_id(1));
+                contract.claim_token(gate_id(2));
+                let token_id3 = contract.claim_token(gate_id(1));
+
+                let mut token_ids = contract.has_access(bob(), gate_id(1));
+                token_ids.sort_by_key(|token_id| token_id.0);
+                assert_eq!(token_ids, vec![token_id1, token_id3]);
+                assert_eq!(contract.has_access(charlie(), gate_id(1)), Vec::<TokenId>::new());
+            });
+    }
+
+    #[test]
+    fn has_access_follows_transfers_and_burns() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                let token_id1 = contract.claim_token(gate_id(1));
+                let token_id2 = contract.claim_token(gate_id(1));
+
+                contract.nft_transfer(charlie(), token_id1, None, None);
+                assert_eq!(contract.has_access(bob(), gate_id(1)), vec![token_id2]);
+                assert_eq!(contract.has_access(charlie(), gate_id(1)), vec![token_id1]);
+
+                contract.burn_token(token_id2);
+                assert_eq!(contract.has_access(bob(), gate_id(1)), Vec::<TokenId>::new());
+            });
+    }
+
+    #[test]
+    fn has_no_access_after_expiration() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.contract.create_collectible(
+                alice(),
+                gate_id(1),
+                "My collectible".to_string(),
+                "NFT description".to_string(),
+                10,
+                "5/100".parse().unwrap(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(20),
+            );
+            let token_id = contract.claim_token(gate_id(1));
+
+            travel_to(contract, 19);
+            assert_eq!(contract.has_access(mintgate_admin(), gate_id(1)), vec![token_id]);
+
+            travel_to(contract, 20);
+            assert_eq!(contract.has_access(mintgate_admin(), gate_id(1)), Vec::<TokenId>::new());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Gate ID `GPZkspuVGaZxwWoP6bJoWU` was not found")]
+    fn has_access_to_non_existent_collectible_should_panic() {
+        init().run_as(bob(), |contract| {
+            contract.has_access(bob(), gate_id(1));
+        });
+    }
+}
+
+mod token_ids {
+
+    use super::*;
+
+    #[test]
+    fn burned_token_ids_are_not_reused() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                contract.create_test_collectible(alice(), gate_id(2), 10);
+                contract.create_test_collectible(bob(), gate_id(3), 10);
+            })
+            .run_as(bob(), |contract| {
+                assert_eq!(contract.claim_token(gate_id(1)), U64(0));
+                assert_eq!(contract.claim_token(gate_id(2)), U64(1));
+                assert_eq!(contract.claim_token(gate_id(1)), U64(2));
+
+                contract.burn_token(U64(1));
+                assert_eq!(contract.claim_token(gate_id(3)), U64(3));
+                assert_eq!(contract.nft_total_supply(), U64(3));
+            })
+            .run_as(charlie(), |contract| {
+                assert_eq!(contract.claim_token(gate_id(2)), U64(4));
+                contract.burn_token(U64(4));
+                assert_eq!(contract.claim_token(gate_id(2)), U64(5));
+            })
+            .run_as(bob(), |contract| {
+                contract.burn_token(U64(0));
+                contract.burn_token(U64(3));
+                assert_eq!(contract.claim_token(gate_id(3)), U64(6));
+                assert_eq!(contract.claim_token(gate_id(1)), U64(7));
+
+                for (token_id, owner_id, gate_key) in
+                    vec![(2, bob(), 1), (5, charlie(), 2), (6, bob(), 3), (7, bob(), 1)]
+                {
+                    let token = contract.nft_token(U64(token_id)).unwrap();
+                    assert_eq!(token.owner_id, owner_id.to_string());
+                    assert_eq!(&token.gate_id, gate_id(gate_key).as_ref());
+                }
+                for token_id in vec![0, 1, 3, 4] {
+                    assert!(contract.nft_token(U64(token_id)).is_none());
+                }
+                assert_eq!(contract.nft_total_supply(), U64(4));
+                assert_eq!(contract.nft_supply_for_owner(bob()), U64(3));
+                assert_eq!(contract.nft_supply_for_owner(charlie()), U64(1));
+
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.minted_tokens, vec![U64(2), U64(7)]);
+                assert_eq!(collectible.current_supply, 7);
+            });
+    }
+}
+
+mod editions {
+
+    use super::*;
+
+    #[test]
+    fn claimed_tokens_are_numbered_in_order() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 5);
+                contract.create_test_collectible(alice(), gate_id(2), 5);
+            })
+            .run_as(bob(), |contract| {
+                let first = contract.claim_token(gate_id(1));
+                let other = contract.claim_token(gate_id(2));
+                let second = contract.claim_token(gate_id(1));
+
+                assert_eq!(contract.nft_token(first).unwrap().edition, 1);
+                assert_eq!(contract.nft_token(other).unwrap().edition, 1);
+                assert_eq!(contract.nft_token(second).unwrap().edition, 2);
+            });
+    }
+
+    #[test]
+    fn editions_are_not_reused_after_burn() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 5);
+            })
+            .run_as(bob(), |contract| {
+                let first = contract.claim_token(gate_id(1));
+                contract.burn_token(first);
+                let second = contract.claim_token(gate_id(1));
+
+                let token = contract.nft_token(second).unwrap();
+                assert_eq!(token.edition, 2);
+                assert_eq!(token.metadata.title, Some("My collectible #2".to_string()));
+
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.supply, 5);
+                assert_eq!(collectible.current_supply, 3);
+            });
+    }
+
+    #[test]
+    fn nft_tokens_include_edition_in_title() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 5);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(charlie(), |contract| {
+                contract.claim_token(gate_id(1));
+
+                let tokens = contract.nft_tokens(None, None);
+                assert_eq!(
+                    tokens.iter().map(|token| token.edition).collect::<Vec<_>>(),
+                    vec![1, 2]
+                );
+                assert_eq!(
+                    tokens.iter().map(|token| token.metadata.title.clone()).collect::<Vec<_>>(),
+                    vec![
+                        Some("My collectible #1".to_string()),
+                        Some("My collectible #2".to_string())
+                    ]
+                );
+
+                let tokens = contract.nft_tokens_for_owner(charlie(), None, None);
+                assert_eq!(tokens[0].metadata.title, Some("My collectible #2".to_string()));
+            });
+    }
+}
+
+mod transferability {
+
+    use super::*;
+
+    fn init_with(transferability: Transferability) -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.transferability, Transferability::Transferable);
+
+                contract.set_transferability(gate_id(1), transferability);
+                let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
+                assert_eq!(collectible.transferability, transferability);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            });
+        context
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` cannot be transferred nor approved by `bob`, they are `non_transferable`"
+    )]
+    fn transfer_a_non_transferable_token_should_panic() {
+        init_with(Transferability::NonTransferable).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_transfer(charlie(), token_id, None, None);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` cannot be transferred nor approved by `alice`, they are `non_transferable`"
+    )]
+    fn creator_transfer_of_a_non_transferable_token_should_panic() {
+        init_with(Transferability::NonTransferable).run_as(alice(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_transfer(charlie(), token_id, None, None);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` cannot be transferred nor approved by `bob`, they are `non_transferable`"
+    )]
+    fn transfer_payout_a_non_transferable_token_should_panic() {
+        init_with(Transferability::NonTransferable).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_transfer_payout(charlie(), token_id, None, None, U128(100), Some(10));
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` cannot be transferred nor approved by `bob`, they are `non_transferable`"
+    )]
+    fn approve_a_non_transferable_token_should_panic() {
+        init_with(Transferability::NonTransferable).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_approve(token_id, market(), approve_msg(10));
+        });
+    }
+
+    #[test]
+    fn batch_approve_a_non_transferable_token_is_not_approved() {
+        init_with(Transferability::NonTransferable).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.batch_approve(vec![(token_id, U128(10))], market());
+            assert!(!contract.nft_is_approved(token_id, market(), None));
+        });
+    }
+
+    #[test]
+    fn approved_token_no_longer_transferable_is_not_approved() {
+        init_with(Transferability::Transferable)
+            .run_as(bob(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_approve(token_id, market(), approve_msg(10));
+                assert!(contract.nft_is_approved(token_id, market(), None));
+            })
+            .run_as(alice(), |contract| {
+                contract.set_transferability(gate_id(1), Transferability::NonTransferable);
+                let token_id = contract.last_claimed_token();
+                assert!(!contract.nft_is_approved(token_id, market(), None));
+            });
+    }
+
+    #[test]
+    fn transfer_a_creator_only_token_through_the_creator() {
+        init_with(Transferability::CreatorOnly)
+            .run_as(bob(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_transfer(alice(), token_id, None, None);
+                assert_eq!(contract.nft_supply_for_owner(bob()), U64(0));
+            })
+            .run_as(alice(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_transfer(charlie(), token_id, None, None);
+                assert_eq!(contract.nft_token(token_id).unwrap().owner_id, charlie().to_string());
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Sender `alice` is not authorized to make transfer")]
+    fn creator_takes_a_creator_only_token_should_panic() {
+        init_with(Transferability::CreatorOnly).run_as(alice(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_transfer(alice(), token_id, None, None);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` cannot be transferred nor approved by `alice`, they are `creator_only`"
+    )]
+    fn creator_transfer_of_an_owned_creator_only_token_should_panic() {
+        init_with(Transferability::CreatorOnly).run_as(alice(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_transfer(charlie(), token_id, None, None);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` cannot be transferred nor approved by `bob`, they are `creator_only`"
+    )]
+    fn owner_transfer_of_a_creator_only_token_should_panic() {
+        init_with(Transferability::CreatorOnly).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_transfer(charlie(), token_id, None, None);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Tokens of gate ID `GPZkspuVGaZxwWoP6bJoWU` cannot be transferred nor approved by `bob`, they are `creator_only`"
+    )]
+    fn approve_a_creator_only_token_should_panic() {
+        init_with(Transferability::CreatorOnly).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_approve(token_id, market(), approve_msg(10));
+        });
+    }
+
+    #[test]
+    fn transfer_again_once_transferable() {
+        init_with(Transferability::NonTransferable)
+            .run_as(alice(), |contract| {
+                contract.set_transferability(gate_id(1), Transferability::Transferable);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_transfer(charlie(), token_id, None, None);
+                assert_eq!(contract.nft_token(token_id).unwrap().owner_id, charlie().to_string());
+            });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Account `bob` is not the creator of gate ID `GPZkspuVGaZxwWoP6bJoWU`"
+    )]
+    fn set_transferability_from_non_creator_should_panic() {
+        init_with(Transferability::Transferable).run_as(bob(), |contract| {
+            contract.set_transferability(gate_id(1), Transferability::NonTransferable);
+        });
+    }
+}
+
+mod pause {
+
+    use super::*;
+    use mg_core::{
+        nep297::{Event, MintGateEvent, PauseData},
+        Pausable, Role,
+    };
+    use near_sdk::test_utils::get_logs;
+
+    fn pause_events() -> Vec<MintGateEvent> {
+        get_logs()
+            .iter()
+            .filter_map(|log| match Event::from_log(log) {
+                Some(Event::Mintgate { event, .. }) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn init_paused(feature: Pausable) -> MockedContext<NftContractChecker> {
+        let mut context = init();
+        context
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                contract.grant_role(charlie(), Role::Pauser);
+            })
+            .run_as(bob(), |contract| {
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(charlie(), |contract| {
+                contract.pause(feature);
+                assert_eq!(contract.get_paused(), vec![feature]);
+            });
+        context
+    }
+
+    #[test]
+    fn pause_and_unpause_emit_events() {
+        init().run_as(mintgate_admin(), |contract| {
+            assert!(contract.get_paused().is_empty());
+
+            contract.pause(Pausable::Minting);
+            assert_eq!(
+                pause_events(),
+                vec![MintGateEvent::Pause(vec![PauseData {
+                    account_id: mintgate_admin().to_string(),
+                    feature: Pausable::Minting,
+                }])]
+            );
+
+            contract.pause(Pausable::Minting);
+            assert!(pause_events().is_empty());
+
+            contract.unpause(Pausable::Minting);
+            assert_eq!(
+                pause_events(),
+                vec![MintGateEvent::Unpause(vec![PauseData {
+                    account_id: mintgate_admin().to_string(),
+                    feature: Pausable::Minting,
+                }])]
+            );
+            assert!(contract.get_paused().is_empty());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `bob` does not have the `pauser` role")]
+    fn pause_from_non_pauser_should_panic() {
+        init().run_as(bob(), |contract| {
+            contract.pause(Pausable::Transfers);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `bob` does not have the `pauser` role")]
+    fn unpause_from_non_pauser_should_panic() {
+        init_paused(Pausable::Transfers).run_as(bob(), |contract| {
+            contract.unpause(Pausable::Transfers);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The `minting` feature is paused")]
+    fn claim_token_while_minting_is_paused_should_panic() {
+        init_paused(Pausable::Minting).run_as(bob(), |contract| {
+            contract.claim_token(gate_id(1));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The `transfers` feature is paused")]
+    fn transfer_while_transfers_are_paused_should_panic() {
+        init_paused(Pausable::Transfers).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_transfer(charlie(), token_id, None, None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The `transfers` feature is paused")]
+    fn approve_while_transfers_are_paused_should_panic() {
+        init_paused(Pausable::Transfers).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.nft_approve(token_id, market(), approve_msg(10));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The `transfers` feature is paused")]
+    fn batch_approve_while_transfers_are_paused_should_panic() {
+        init_paused(Pausable::Transfers).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            contract.batch_approve(vec![(token_id, U128(10))], market());
+        });
+    }
+
+    #[test]
+    fn views_and_other_features_work_while_paused() {
+        init_paused(Pausable::Transfers).run_as(bob(), |contract| {
+            let token_id = contract.last_claimed_token();
+            assert_eq!(contract.nft_token(token_id).unwrap().owner_id, bob().to_string());
+            assert_eq!(contract.nft_tokens_for_owner(bob(), None, None).len(), 1);
+            contract.claim_token(gate_id(1));
+        });
+    }
 
+    #[test]
+    fn revoke_while_transfers_are_paused() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
                 contract.create_test_collectible(alice(), gate_id(1), 10);
-                let uri = format!("{}/{}", "https://mintgate/t", gate_id(1));
+                contract.grant_role(charlie(), Role::Pauser);
+            })
+            .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                assert_eq!(contract.nft_token_uri(token_id).unwrap(), uri);
-            },
-        );
+                contract.nft_approve(token_id, market(), approve_msg(10));
+                contract.nft_approve(token_id, alice(), approve_msg(10));
+            })
+            .run_as(charlie(), |contract| {
+                contract.pause(Pausable::Transfers);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_revoke(token_id, market());
+                assert!(!contract.nft_is_approved(token_id, market(), None));
+                assert!(contract.nft_is_approved(token_id, alice(), None));
+
+                contract.nft_revoke_all(token_id);
+                assert!(!contract.nft_is_approved(token_id, alice(), None));
+            });
     }
 
     #[test]
-    fn get_token_uri_with_none_base_uri() {
-        init_contract("5/100", "30/100", metadata(None)).run_as(mintgate_admin(), |contract| {
-            assert_eq!(contract.nft_token_uri(U64(0)), None);
+    fn transfer_once_unpaused() {
+        init_paused(Pausable::Transfers)
+            .run_as(charlie(), |contract| {
+                contract.unpause(Pausable::Transfers);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_transfer(charlie(), token_id, None, None);
+            });
+    }
+}
 
-            contract.create_test_collectible(alice(), gate_id(1), 10);
-            let token_id = contract.claim_token(gate_id(1));
-            assert_eq!(contract.nft_token_uri(token_id), None);
+mod roles {
+
+    use super::*;
+    use mg_core::{
+        nep297::{Event, MintGateEvent, RoleData},
+        Role,
+    };
+    use near_sdk::test_utils::get_logs;
+
+    fn role_events() -> Vec<MintGateEvent> {
+        get_logs()
+            .iter()
+            .filter_map(|log| match Event::from_log(log) {
+                Some(Event::Mintgate { event, .. }) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn owner_is_the_only_owner_role_holder() {
+        init().run_as(alice(), |contract| {
+            assert_eq!(contract.get_role_holders(Role::Owner), vec![mintgate_admin().to_string()]);
+            assert!(contract.get_role_holders(Role::Admin).is_empty());
+            assert!(contract.get_role_holders(Role::CollectibleMinter).is_empty());
+            assert!(contract.get_role_holders(Role::Pauser).is_empty());
+        });
+    }
+
+    #[test]
+    fn grant_collectible_minter_role() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(alice(), Role::CollectibleMinter);
+                assert_eq!(
+                    contract.get_role_holders(Role::CollectibleMinter),
+                    vec![alice().to_string()]
+                );
+            })
+            .run_as(alice(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `collectible_minter` role")]
+    fn create_a_collectible_after_revoke_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(alice(), Role::CollectibleMinter);
+                contract.revoke_role(alice(), Role::CollectibleMinter);
+                assert!(contract.get_role_holders(Role::CollectibleMinter).is_empty());
+            })
+            .run_as(alice(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            });
+    }
+
+    #[test]
+    fn admin_can_grant_and_revoke_other_roles() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(alice(), Role::Admin);
+            })
+            .run_as(alice(), |contract| {
+                contract.grant_role(bob(), Role::CollectibleMinter);
+                contract.grant_role(bob(), Role::Pauser);
+                contract.revoke_role(bob(), Role::Pauser);
+                contract.create_test_collectible(charlie(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                contract.create_test_collectible(charlie(), gate_id(2), 10);
+                assert!(contract.get_role_holders(Role::Pauser).is_empty());
+            });
+    }
+
+    #[test]
+    fn admin_can_delete_any_collectible() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                contract.grant_role(bob(), Role::Admin);
+            })
+            .run_as(bob(), |contract| {
+                contract.delete_collectible(gate_id(1));
+                assert!(contract.get_collectible_by_gate_id(gate_id(1)).is_none());
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `owner` role")]
+    fn admin_cannot_grant_admin_role() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(alice(), Role::Admin);
+            })
+            .run_as(alice(), |contract| {
+                contract.grant_role(bob(), Role::Admin);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `admin` role")]
+    fn grant_role_by_non_admin_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.grant_role(alice(), Role::CollectibleMinter);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `admin` role")]
+    fn revoke_role_by_non_admin_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.grant_role(bob(), Role::Pauser);
+            })
+            .run_as(alice(), |contract| {
+                contract.revoke_role(bob(), Role::Pauser);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "The owner role cannot be granted nor revoked")]
+    fn grant_owner_role_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.grant_role(alice(), Role::Owner);
+        });
+    }
+
+    #[test]
+    fn transfer_ownership() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.transfer_ownership(alice());
+                assert_eq!(contract.get_role_holders(Role::Owner), vec![alice().to_string()]);
+            })
+            .run_as(alice(), |contract| {
+                contract.grant_role(bob(), Role::Admin);
+                assert_eq!(contract.get_role_holders(Role::Admin), vec![bob().to_string()]);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `admin` does not have the `owner` role")]
+    fn transfer_ownership_twice_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.transfer_ownership(alice());
+            contract.transfer_ownership(bob());
+        });
+    }
+
+    #[test]
+    fn grant_revoke_and_transfer_emit_events() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.grant_role(alice(), Role::Pauser);
+            assert_eq!(
+                role_events(),
+                vec![MintGateEvent::RoleGrant(vec![RoleData {
+                    account_id: alice().to_string(),
+                    role: Role::Pauser,
+                }])]
+            );
+
+            contract.revoke_role(alice(), Role::Pauser);
+            assert_eq!(
+                role_events(),
+                vec![MintGateEvent::RoleRevoke(vec![RoleData {
+                    account_id: alice().to_string(),
+                    role: Role::Pauser,
+                }])]
+            );
+
+            contract.transfer_ownership(bob());
+            assert_eq!(
+                role_events(),
+                vec![
+                    MintGateEvent::RoleRevoke(vec![RoleData {
+                        account_id: mintgate_admin().to_string(),
+                        role: Role::Owner,
+                    }]),
+                    MintGateEvent::RoleGrant(vec![RoleData {
+                        account_id: bob().to_string(),
+                        role: Role::Owner,
+                    }]),
+                ]
+            );
         });
     }
 }
-mod burn_token {
+
+mod config {
 
     use super::*;
+    use mg_core::{
+        nep297::{Event, MintGateEvent},
+        NftConfig, Role,
+    };
+    use near_sdk::test_utils::get_logs;
+
+    fn config(
+        min_royalty: &str,
+        max_royalty: &str,
+        mintgate_fee: &str,
+        account: &str,
+    ) -> NftConfig {
+        NftConfig {
+            min_royalty: min_royalty.parse().unwrap(),
+            max_royalty: max_royalty.parse().unwrap(),
+            mintgate_fee: mintgate_fee.parse().unwrap(),
+            mintgate_fee_account_id: account.to_string(),
+        }
+    }
+
+    fn config_events() -> Vec<NftConfig> {
+        get_logs()
+            .iter()
+            .filter_map(|log| match Event::from_log(log) {
+                Some(Event::Mintgate { event: MintGateEvent::ConfigUpdate(data), .. }) => {
+                    Some(data)
+                }
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
 
     #[test]
-    #[should_panic(expected = "Token ID `U64(0)` was not found")]
-    fn burn_a_non_existent_token_should_panic() {
+    fn get_initial_config() {
         init().run_as(alice(), |contract| {
-            contract.burn_token(0.into());
+            assert_eq!(
+                contract.get_config(),
+                config("5/100", "30/100", "25/1000", mintgate_fee_account_id().as_ref())
+            );
         });
     }
 
     #[test]
-    fn burn_a_few_tokens() {
+    fn set_mintgate_fee() {
         init().run_as(mintgate_admin(), |contract| {
-            contract.create_test_collectible(alice(), gate_id(1), 10);
-            let token_id = contract.claim_token(gate_id(1));
-            contract.burn_token(token_id);
-            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
-            assert_eq!(collectible.metadata.copies.unwrap(), 9);
-
-            let token_id = contract.claim_token(gate_id(1));
-            contract.nft_approve(token_id, bob(), approve_msg(10));
-            contract.burn_token(token_id);
-            let collectible = contract.get_collectible_by_gate_id(gate_id(1)).unwrap();
-            assert_eq!(collectible.metadata.copies.unwrap(), 8);
+            contract.set_mintgate_fee("5/100".parse().unwrap(), charlie());
+            let expected = config("5/100", "30/100", "5/100", "charlie");
+            assert_eq!(contract.get_config(), expected);
+            assert_eq!(config_events(), vec![expected]);
         });
     }
 
     #[test]
-    #[should_panic(expected = "Token ID `U64(0)` does not belong to account `bob`")]
-    fn transfer_a_non_approved_token_should_panic() {
+    fn set_mintgate_fee_applies_to_payouts() {
         init()
             .run_as(mintgate_admin(), |contract| {
-                contract.create_test_collectible(alice(), gate_id(1), 10);
-                contract.claim_token(gate_id(1));
+                contract.create_royalty_collectible(alice(), gate_id(1), 10, "15/100");
+                contract.grant_role(charlie(), Role::Admin);
+            })
+            .run_as(charlie(), |contract| {
+                contract.set_mintgate_fee("10/100".parse().unwrap(), charlie());
             })
             .run_as(bob(), |contract| {
-                let token_id = contract.last_claimed_token();
-                contract.burn_token(token_id);
+                let token_id = contract.claim_token(gate_id(1));
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
+                assert_eq!(payout.len(), 3);
+                assert_eq!(payout.get(charlie().as_ref()).unwrap().0, 200);
+                assert_eq!(payout.get(alice().as_ref()).unwrap().0, 300);
+                assert_eq!(payout.get(bob().as_ref()).unwrap().0, 1500);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `admin` role")]
+    fn set_mintgate_fee_by_non_admin_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.set_mintgate_fee("5/100".parse().unwrap(), alice());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "The fraction must be less or equal to 1")]
+    fn set_invalid_mintgate_fee_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.set_mintgate_fee("2/1".parse().unwrap(), charlie());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty `30/100` is too large for the given NFT fee `70/100`")]
+    fn set_too_large_mintgate_fee_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.set_mintgate_fee("70/100".parse().unwrap(), charlie());
+        });
+    }
+
+    #[test]
+    fn set_royalty_bounds() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.set_royalty_bounds("1/100".parse().unwrap(), "50/100".parse().unwrap());
+            let expected = config("1/100", "50/100", "25/1000", mintgate_fee_account_id().as_ref());
+            assert_eq!(contract.get_config(), expected);
+            assert_eq!(config_events(), vec![expected]);
+
+            contract.create_royalty_collectible(alice(), gate_id(1), 10, "2/100");
+            contract.create_royalty_collectible(alice(), gate_id(2), 10, "45/100");
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty `40/100` of `GPZkspuVGaZxwWoP6bJoWU` is greater than max")]
+    fn create_a_collectible_after_lowering_max_royalty_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.set_royalty_bounds("5/100".parse().unwrap(), "20/100".parse().unwrap());
+            contract.create_royalty_collectible(alice(), gate_id(1), 10, "40/100");
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Mintgate fee `60/100` is too large for the royalties of existing collectibles"
+    )]
+    fn set_mintgate_fee_too_large_for_existing_collectibles_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.set_royalty_bounds("0/100".parse().unwrap(), "50/100".parse().unwrap());
+            contract.create_royalty_collectible(alice(), gate_id(1), 10, "50/100");
+            contract.set_royalty_bounds("0/100".parse().unwrap(), "10/100".parse().unwrap());
+            contract.set_mintgate_fee("60/100".parse().unwrap(), charlie());
+        });
+    }
+
+    #[test]
+    fn set_mintgate_fee_within_royalties_of_existing_collectibles() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.set_royalty_bounds("0/100".parse().unwrap(), "50/100".parse().unwrap());
+                contract.create_split_collectible(
+                    alice(),
+                    gate_id(1),
+                    10,
+                    "20/100",
+                    &[(bob(), "20/100")],
+                );
+                contract.set_royalty_bounds("0/100".parse().unwrap(), "10/100".parse().unwrap());
+                contract.set_mintgate_fee("55/100".parse().unwrap(), mintgate_fee_account_id());
+            })
+            .run_as(charlie(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                let payout = contract.nft_payout(token_id, 1000.into(), Some(10)).payout;
+                assert_eq!(payout.len(), 4);
+                assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 550);
+                assert_eq!(payout.get(alice().as_ref()).unwrap().0, 200);
+                assert_eq!(payout.get(bob().as_ref()).unwrap().0, 200);
+                assert_eq!(payout.get(charlie().as_ref()).unwrap().0, 50);
             });
     }
+
+    #[test]
+    #[should_panic(expected = "Account `alice` does not have the `admin` role")]
+    fn set_royalty_bounds_by_non_admin_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.set_royalty_bounds("1/100".parse().unwrap(), "50/100".parse().unwrap());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Min royalty `10/100` must be less or equal to max royalty `5/100`")]
+    fn set_royalty_bounds_with_min_greater_than_max_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.set_royalty_bounds("10/100".parse().unwrap(), "5/100".parse().unwrap());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty `1/1` is too large for the given NFT fee `25/1000`")]
+    fn set_too_large_max_royalty_should_panic() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.set_royalty_bounds("10/100".parse().unwrap(), "1/1".parse().unwrap());
+        });
+    }
 }
 
 mod nft_transfer {
@@ -817,6 +4052,7 @@ mod nft_transfer {
 mod nft_approve {
 
     use super::*;
+    use near_sdk::test_utils::get_created_receipts;
 
     #[test]
     #[should_panic(expected = "The msg argument must contain the minimum price")]
@@ -843,27 +4079,76 @@ mod nft_approve {
     }
 
     #[test]
-    fn nft_approve_a_token() {
+    fn nft_approve_a_token() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            let token_id = contract.claim_token(gate_id(1));
+            contract.nft_approve(token_id, bob(), approve_msg(10));
+
+            let token = contract.nft_token(token_id).unwrap();
+            assert_eq!(token.approval_counter, U64(1));
+            assert_eq!(token.approvals.len(), 1);
+            assert_eq!(token.approvals[bob().as_ref()], TokenApproval::new(1, U128(10)));
+        });
+    }
+
+    #[test]
+    fn nft_approve_a_token_in_many_markets() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            let token_id = contract.claim_token(gate_id(1));
+            contract.nft_approve(token_id, bob(), approve_msg(10));
+            contract.nft_approve(token_id, charlie(), approve_msg(15));
+
+            let token = contract.nft_token(token_id).unwrap();
+            assert_eq!(token.approval_counter, U64(2));
+            assert_eq!(token.approvals.len(), 2);
+            assert_eq!(token.approvals[bob().as_ref()], TokenApproval::new(1, U128(10)));
+            assert_eq!(token.approvals[charlie().as_ref()], TokenApproval::new(2, U128(15)));
+        });
+    }
+
+    #[test]
+    fn nft_approve_a_token_twice_in_the_same_market() {
         init().run_as(mintgate_admin(), |contract| {
             contract.create_test_collectible(alice(), gate_id(1), 10);
             let token_id = contract.claim_token(gate_id(1));
             contract.nft_approve(token_id, bob(), approve_msg(10));
+            contract.nft_approve(token_id, bob(), approve_msg(15));
 
             let token = contract.nft_token(token_id).unwrap();
-            assert_eq!(token.approval_counter, U64(1));
+            assert_eq!(token.approval_counter, U64(2));
             assert_eq!(token.approvals.len(), 1);
-            assert_eq!(token.approvals[bob().as_ref()], TokenApproval::new(1, U128(10)));
+            assert_eq!(token.approvals[bob().as_ref()], TokenApproval::new(2, U128(15)));
         });
     }
 
     #[test]
-    #[should_panic(expected = "At most one approval is allowed per Token")]
-    fn nft_approve_a_token_twice_should_panic() {
+    fn nft_is_approved() {
         init().run_as(mintgate_admin(), |contract| {
             contract.create_test_collectible(alice(), gate_id(1), 10);
             let token_id = contract.claim_token(gate_id(1));
+            assert!(!contract.nft_is_approved(token_id, bob(), None));
+
             contract.nft_approve(token_id, bob(), approve_msg(10));
             contract.nft_approve(token_id, charlie(), approve_msg(15));
+            assert!(contract.nft_is_approved(token_id, bob(), None));
+            assert!(contract.nft_is_approved(token_id, bob(), Some(U64(1))));
+            assert!(!contract.nft_is_approved(token_id, bob(), Some(U64(2))));
+            assert!(contract.nft_is_approved(token_id, charlie(), Some(U64(2))));
+            assert!(!contract.nft_is_approved(token_id, market(), None));
+
+            contract.nft_revoke(token_id, bob());
+            assert!(!contract.nft_is_approved(token_id, bob(), None));
+            assert!(contract.nft_is_approved(token_id, charlie(), None));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Token ID `U64(99)` was not found")]
+    fn nft_is_approved_for_non_existent_token_should_panic() {
+        init().run_as(alice(), |contract| {
+            contract.nft_is_approved(99.into(), bob(), None);
         });
     }
 
@@ -891,6 +4176,47 @@ mod nft_approve {
                 assert_eq!(token.approvals.len(), 0);
             });
     }
+
+    #[test]
+    fn nft_transfer_by_a_market_revokes_other_markets() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                contract.nft_approve(token_id, market(), approve_msg(10));
+                contract.nft_approve(token_id, alice(), approve_msg(10));
+                contract.nft_approve(token_id, mintgate_admin(), approve_msg(10));
+            })
+            .run_as(market(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.contract.nft_transfer(charlie(), token_id, Some(U64(1)), None);
+                assert_eq!(get_created_receipts().len(), 2);
+
+                let token = contract.nft_token(token_id).unwrap();
+                assert_eq!(token.owner_id, charlie().to_string());
+                assert_eq!(token.approvals.len(), 0);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "The approval_id is different from enforce_approval_id")]
+    fn nft_transfer_with_stale_approval_id_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                contract.nft_approve(token_id, market(), approve_msg(10));
+                contract.nft_approve(token_id, market(), approve_msg(20));
+            })
+            .run_as(market(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_transfer(charlie(), token_id, Some(U64(1)), None);
+            });
+    }
 }
 
 mod nft_revoke_all {
@@ -927,7 +4253,7 @@ mod nft_payout {
     #[should_panic(expected = "Token ID `U64(99)` was not found")]
     fn nft_payout_non_existent_token_id_should_panic() {
         init().run_as(bob(), |contract| {
-            contract.nft_payout(99.into(), 0.into());
+            contract.nft_payout(99.into(), 0.into(), Some(10));
         });
     }
 
@@ -939,7 +4265,7 @@ mod nft_payout {
             })
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                let payout = contract.nft_payout(token_id, 2000.into());
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
                 assert_eq!(payout.len(), 3);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
                 assert_eq!(payout.get(alice().as_ref()).unwrap().0, 0);
@@ -955,7 +4281,7 @@ mod nft_payout {
             })
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                let payout = contract.nft_payout(token_id, 2000.into());
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
                 assert_eq!(payout.len(), 3);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
                 assert_eq!(payout.get(alice().as_ref()).unwrap().0, 300);
@@ -963,6 +4289,73 @@ mod nft_payout {
             });
     }
 
+    #[test]
+    fn nft_get_payout_with_royalty_split() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_split_collectible(
+                    alice(),
+                    gate_id(1),
+                    10,
+                    "15/100",
+                    &[(charlie(), "5/100"), (market(), "1/100")],
+                );
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
+                assert_eq!(payout.len(), 5);
+                assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
+                assert_eq!(payout.get(alice().as_ref()).unwrap().0, 300);
+                assert_eq!(payout.get(charlie().as_ref()).unwrap().0, 100);
+                assert_eq!(payout.get(market().as_ref()).unwrap().0, 20);
+                assert_eq!(payout.get(bob().as_ref()).unwrap().0, 1530);
+            });
+    }
+
+    #[test]
+    fn nft_get_payout_with_periodic_royalty_split() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_split_collectible(
+                    alice(),
+                    gate_id(1),
+                    10,
+                    "1/7",
+                    &[(charlie(), "1/7"), (alice(), "1/1000")],
+                );
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
+                assert_eq!(payout.len(), 4);
+                assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
+                assert_eq!(payout.get(alice().as_ref()).unwrap().0, 287);
+                assert_eq!(payout.get(charlie().as_ref()).unwrap().0, 285);
+                assert_eq!(payout.get(bob().as_ref()).unwrap().0, 1378);
+                assert_eq!(payout.values().map(|amount| amount.0).sum::<u128>(), 2000);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Payout exceeds `max_len_payout` of `4` accounts")]
+    fn nft_payout_longer_than_max_len_payout_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_split_collectible(
+                    alice(),
+                    gate_id(1),
+                    10,
+                    "15/100",
+                    &[(charlie(), "5/100"), (market(), "1/100")],
+                );
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                contract.nft_payout(token_id, 2000.into(), Some(4));
+            });
+    }
+
     #[test]
     fn nft_get_example_payout() {
         init()
@@ -971,7 +4364,7 @@ mod nft_payout {
             })
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                let payout = contract.nft_payout(token_id, 5_000_000.into());
+                let payout = contract.nft_payout(token_id, 5_000_000.into(), None).payout;
                 assert_eq!(payout.len(), 3);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 125_000);
                 assert_eq!(payout.get(alice().as_ref()).unwrap().0, 1_500_000);
@@ -987,7 +4380,7 @@ mod nft_payout {
             })
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                let payout = contract.nft_payout(token_id, 2000.into());
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
                 assert_eq!(payout.len(), 3);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
                 assert_eq!(payout.get(alice().as_ref()).unwrap().0, 333);
@@ -1003,7 +4396,7 @@ mod nft_payout {
             })
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                let payout = contract.nft_payout(token_id, 2000.into());
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
                 assert_eq!(payout.len(), 3);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
                 assert_eq!(payout.get(alice().as_ref()).unwrap().0, 285);
@@ -1019,7 +4412,7 @@ mod nft_payout {
             })
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                let payout = contract.nft_payout(token_id, 2000.into());
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
                 assert_eq!(payout.len(), 2);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
                 assert_eq!(payout.get(bob().as_ref()).unwrap().0, 1950);
@@ -1034,7 +4427,7 @@ mod nft_payout {
             })
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
-                let payout = contract.nft_payout(token_id, 2000.into());
+                let payout = contract.nft_payout(token_id, 2000.into(), Some(10)).payout;
                 assert_eq!(payout.len(), 2);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
                 assert_eq!(payout.get(bob().as_ref()).unwrap().0, 1950);
@@ -1055,12 +4448,357 @@ mod nft_transfer_payout {
             .run_as(bob(), |contract| {
                 let token_id = contract.claim_token(gate_id(1));
                 let payout = contract
-                    .nft_transfer_payout(charlie(), token_id, None, None, Some(2000.into()))
-                    .unwrap();
+                    .nft_transfer_payout(charlie(), token_id, None, None, 2000.into(), Some(10))
+                    .payout;
                 assert_eq!(payout.len(), 3);
                 assert_eq!(payout.get(mintgate_fee_account_id().as_ref()).unwrap().0, 50);
                 assert_eq!(payout.get(alice().as_ref()).unwrap().0, 300);
                 assert_eq!(payout.get(bob().as_ref()).unwrap().0, 1650);
+                assert_eq!(contract.nft_token(token_id).unwrap().owner_id, charlie().to_string());
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Payout exceeds `max_len_payout` of `2` accounts")]
+    fn nft_transfer_payout_longer_than_max_len_payout_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_royalty_collectible(alice(), gate_id(1), 10, "15/100");
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                contract.nft_transfer_payout(charlie(), token_id, None, None, 2000.into(), Some(2));
+            });
+    }
+}
+
+mod nft_transfer_call {
+
+    use super::*;
+    use mg_core::{mocked_context::nft, nep171::NonFungibleTokenResolver};
+    use near_sdk::PromiseResult;
+    use std::collections::HashMap;
+
+    fn transfer_call(contract: &mut MockedContext<NftContractChecker>) -> TokenId {
+        let token_id = contract.claim_token(gate_id(1));
+        contract.nft_approve(token_id, market(), approve_msg(10));
+        contract.nft_transfer_call(charlie(), token_id, None, None, "stake".to_string());
+
+        let token = contract.nft_token(token_id).unwrap();
+        assert_eq!(token.owner_id, charlie().to_string());
+        assert_eq!(token.approvals.len(), 0);
+        token_id
+    }
+
+    fn resolve_transfer(
+        contract: &mut MockedContext<NftContractChecker>,
+        result: PromiseResult,
+        token_id: TokenId,
+        approvals: HashMap<String, TokenApproval>,
+    ) -> bool {
+        contract.context.current_account_id = nft().to_string();
+        contract.promise_results(vec![result]);
+        contract.nft_resolve_transfer(
+            bob().to_string(),
+            charlie().to_string(),
+            token_id,
+            Some(approvals),
+        )
+    }
+
+    fn approvals() -> HashMap<String, TokenApproval> {
+        vec![(market().to_string(), TokenApproval::new(1, U128(10)))].into_iter().collect()
+    }
+
+    #[test]
+    fn transfer_call_a_token_kept_by_receiver() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                transfer_call(contract);
+            })
+            .run_as(nft(), |contract| {
+                let token_id = contract.last_claimed_token();
+                let result = PromiseResult::Successful(b"false".to_vec());
+                assert!(resolve_transfer(contract, result, token_id, approvals()));
+
+                let token = contract.nft_token(token_id).unwrap();
+                assert_eq!(token.owner_id, charlie().to_string());
+                assert_eq!(token.approvals.len(), 0);
+                assert_eq!(contract.get_tokens_by_owner(bob()).len(), 0);
+            });
+    }
+
+    #[test]
+    fn transfer_call_a_token_returned_by_receiver() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                transfer_call(contract);
+            })
+            .run_as(nft(), |contract| {
+                let token_id = contract.last_claimed_token();
+                let result = PromiseResult::Successful(b"true".to_vec());
+                assert!(!resolve_transfer(contract, result, token_id, approvals()));
+
+                let token = contract.nft_token(token_id).unwrap();
+                assert_eq!(token.owner_id, bob().to_string());
+                assert_eq!(token.approval_counter, U64(1));
+                assert_eq!(token.approvals, approvals());
+                assert_eq!(contract.get_tokens_by_owner(charlie()).len(), 0);
+                assert_eq!(contract.get_tokens_by_owner(bob()).len(), 1);
+            });
+    }
+
+    #[test]
+    fn transfer_call_a_token_to_failing_receiver() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                transfer_call(contract);
+            })
+            .run_as(nft(), |contract| {
+                let token_id = contract.last_claimed_token();
+                assert!(!resolve_transfer(contract, PromiseResult::Failed, token_id, approvals()));
+
+                let token = contract.nft_token(token_id).unwrap();
+                assert_eq!(token.owner_id, bob().to_string());
+                assert_eq!(token.approvals, approvals());
+            });
+    }
+
+    #[test]
+    fn transfer_call_a_token_burned_by_receiver() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                transfer_call(contract);
+            })
+            .run_as(charlie(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.burn_token(token_id);
+            })
+            .run_as(nft(), |contract| {
+                let token_id = contract.last_claimed_token();
+                let result = PromiseResult::Successful(b"true".to_vec());
+                assert!(resolve_transfer(contract, result, token_id, approvals()));
+
+                assert!(contract.nft_token(token_id).is_none());
+                assert_eq!(contract.get_tokens_by_owner(bob()).len(), 0);
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough gas attached to make a transfer call")]
+    fn transfer_call_with_not_enough_gas_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                contract.context.prepaid_gas = 30_000_000_000_000;
+                contract.nft_transfer_call(charlie(), token_id, None, None, "".to_string());
+            });
+    }
+
+    #[test]
+    #[should_panic(expected = "Sender `charlie` is not authorized to make transfer")]
+    fn transfer_call_a_non_approved_token_should_panic() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+                contract.claim_token(gate_id(1));
+            })
+            .run_as(charlie(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_transfer_call(bob(), token_id, None, None, "".to_string());
+            });
+    }
+}
+
+mod nep297_events {
+
+    use super::*;
+    use mg_core::nep297::{
+        CollectibleCreateData, CollectibleDeleteData, Event, MintGateEvent, Nep171Event,
+        NftApproveData, NftBurnData, NftMintData, NftRevokeData, NftTransferData,
+    };
+    use near_sdk::test_utils::get_logs;
+
+    fn nep171_events() -> Vec<Nep171Event> {
+        get_logs()
+            .iter()
+            .filter_map(|log| match Event::from_log(log) {
+                Some(Event::Nep171 { event, .. }) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn mintgate_events() -> Vec<MintGateEvent> {
+        get_logs()
+            .iter()
+            .filter_map(|log| match Event::from_log(log) {
+                Some(Event::Mintgate { event, .. }) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn create_and_delete_collectible_emit_events() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            assert_eq!(
+                mintgate_events(),
+                vec![MintGateEvent::CollectibleCreate(vec![CollectibleCreateData {
+                    creator_id: alice().to_string(),
+                    gate_id: gate_id(1).to_string(),
+                    supply: 10,
+                }])]
+            );
+
+            contract.delete_collectible(gate_id(1));
+            assert_eq!(
+                mintgate_events(),
+                vec![MintGateEvent::CollectibleDelete(vec![CollectibleDeleteData {
+                    creator_id: alice().to_string(),
+                    gate_id: gate_id(1).to_string(),
+                }])]
+            );
+        });
+    }
+
+    #[test]
+    fn claim_transfer_and_burn_emit_events() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.contract.claim_token(gate_id(1));
+                assert_eq!(
+                    nep171_events(),
+                    vec![Nep171Event::NftMint(vec![NftMintData {
+                        owner_id: bob().to_string(),
+                        token_ids: vec![token_id],
+                        memo: None,
+                    }])]
+                );
+
+                contract.nft_transfer(charlie(), token_id, None, Some("gift".to_string()));
+                assert_eq!(
+                    nep171_events(),
+                    vec![Nep171Event::NftTransfer(vec![NftTransferData {
+                        authorized_id: None,
+                        old_owner_id: bob().to_string(),
+                        new_owner_id: charlie().to_string(),
+                        token_ids: vec![token_id],
+                        memo: Some("gift".to_string()),
+                    }])]
+                );
+            })
+            .run_as(charlie(), |contract| {
+                let token_id = contract.get_tokens_by_owner(charlie())[0].token_id;
+                contract.burn_token(token_id);
+                assert_eq!(
+                    nep171_events(),
+                    vec![Nep171Event::NftBurn(vec![NftBurnData {
+                        authorized_id: None,
+                        owner_id: charlie().to_string(),
+                        token_ids: vec![token_id],
+                        memo: None,
+                    }])]
+                );
+            });
+    }
+
+    #[test]
+    fn mint_to_emits_a_single_event() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(alice(), |contract| {
+                contract.mint_to(gate_id(1), vec![bob(), charlie(), bob()]);
+                assert_eq!(
+                    nep171_events(),
+                    vec![Nep171Event::NftMint(vec![
+                        NftMintData {
+                            owner_id: bob().to_string(),
+                            token_ids: vec![U64(0), U64(2)],
+                            memo: None,
+                        },
+                        NftMintData {
+                            owner_id: charlie().to_string(),
+                            token_ids: vec![U64(1)],
+                            memo: None,
+                        },
+                    ])]
+                );
+            });
+    }
+
+    #[test]
+    fn approved_transfer_emits_authorized_id() {
+        init()
+            .run_as(mintgate_admin(), |contract| {
+                contract.create_test_collectible(alice(), gate_id(1), 10);
+            })
+            .run_as(bob(), |contract| {
+                let token_id = contract.claim_token(gate_id(1));
+                contract.nft_approve(token_id, market(), approve_msg(10));
+                assert_eq!(
+                    mintgate_events(),
+                    vec![MintGateEvent::NftApprove(vec![NftApproveData {
+                        owner_id: bob().to_string(),
+                        token_id,
+                        account_id: market().to_string(),
+                        approval_id: U64(1),
+                        min_price: U128(10),
+                    }])]
+                );
+            })
+            .run_as(market(), |contract| {
+                let token_id = contract.last_claimed_token();
+                contract.nft_transfer(charlie(), token_id, None, None);
+                assert_eq!(
+                    nep171_events(),
+                    vec![Nep171Event::NftTransfer(vec![NftTransferData {
+                        authorized_id: Some(market().to_string()),
+                        old_owner_id: bob().to_string(),
+                        new_owner_id: charlie().to_string(),
+                        token_ids: vec![token_id],
+                        memo: None,
+                    }])]
+                );
             });
     }
+
+    #[test]
+    fn revoke_emits_event() {
+        init().run_as(mintgate_admin(), |contract| {
+            contract.create_test_collectible(alice(), gate_id(1), 10);
+            let token_id = contract.claim_token(gate_id(1));
+            contract.nft_approve(token_id, market(), approve_msg(10));
+            contract.nft_revoke(token_id, market());
+            assert_eq!(
+                mintgate_events(),
+                vec![MintGateEvent::NftRevoke(vec![NftRevokeData {
+                    owner_id: mintgate_admin().to_string(),
+                    token_id,
+                    account_id: market().to_string(),
+                }])]
+            );
+        });
+    }
 }
@@ -1,10 +1,18 @@
-use mg_core::{gate::ValidGateId, mocked_context::gate_id, MarketApproveMsg, TokenId};
+use mg_core::{
+    fraction::Fraction,
+    gate::ValidGateId,
+    mocked_context::gate_id,
+    voucher::{Voucher, VoucherCollectible, VoucherSigner},
+    MarketApproveMsg, NftApproveMsg, Pausable, Role, Token, TokenId, Transferability,
+};
 use mg_nft::Panic;
+use near_crypto::{InMemorySigner, KeyType};
 use near_sdk::{
     json_types::{ValidAccountId, U128, U64},
-    serde_json,
+    serde_json::{self, json, Value},
 };
-use near_sdk_sim::to_yocto;
+use near_sdk_sim::{call, to_yocto, view, UserAccount};
+use std::convert::TryInto;
 
 mod sim;
 use sim::*;
@@ -47,8 +55,13 @@ fn create_and_claim_collectibles() {
     create_collectible(nft, admin, charlie, gate_id(1), 0, "1/10")
         .failure(mg_nft::Panic::ZeroSupplyNotAllowed { gate_id: gate_id(1).to_string() }.msg());
 
-    create_collectible(nft, alice, charlie, gate_id(1), 10, "1/10")
-        .failure(Panic::AdminRestrictedOperation.msg());
+    create_collectible(nft, alice, charlie, gate_id(1), 10, "1/10").failure(
+        Panic::Unauthorized {
+            account_id: alice.account_id(),
+            role: mg_core::Role::CollectibleMinter,
+        }
+        .msg(),
+    );
 
     let n = 4;
     for k in 1..=n {
@@ -71,6 +84,155 @@ fn create_and_claim_collectibles() {
     }
 }
 
+#[test]
+fn mint_to_receivers() {
+    let Sim { nft, alice, bob, charlie, admin, .. } = &init(0, "1/1000", "30/100", "25/1000");
+
+    create_collectible(nft, admin, alice, gate_id(1), 100, "10/100").unwrap();
+    mint_to(nft, bob, 1, &[bob]).failure(
+        Panic::NotCollectibleCreator {
+            account_id: bob.account_id(),
+            gate_id: gate_id(1).to_string(),
+        }
+        .msg(),
+    );
+
+    let receivers = [bob, charlie].repeat(20);
+    let token_ids = mint_to(nft, alice, 1, &receivers).unwrap();
+    assert_eq!(token_ids.len(), 40);
+
+    let collectible = get_collectible_by_gate_id(nft, gate_id(1));
+    assert_eq!(collectible.current_supply, 60);
+    assert_eq!(collectible.minted_tokens, token_ids);
+
+    let token_id = claim_token(nft, bob, 1).unwrap();
+    assert_eq!(token_id, U64(40));
+}
+
+#[test]
+fn redeem_vouchers() {
+    let Sim { nft, alice, bob, charlie, admin, mintgate, .. } =
+        &init(0, "1/1000", "30/100", "25/1000");
+
+    create_collectible(nft, admin, alice, gate_id(1), 10, "10/100").unwrap();
+
+    let signer = VoucherSigner::from_seed(&[1; 32]);
+    let voucher = Voucher {
+        gate_id: gate_id(1),
+        collectible: None,
+        receiver_id: Some(bob.account_id()),
+        price: U128(to_yocto("3")),
+        expires_at: u64::MAX,
+        nonce: U64(1),
+    };
+
+    redeem_voucher(nft, bob, &signer, voucher.clone())
+        .failure(Panic::VoucherKeyNotFound { account_id: alice.account_id() }.msg());
+    set_voucher_key(nft, alice, &signer).unwrap();
+    redeem_voucher(nft, charlie, &signer, voucher.clone()).failure(
+        Panic::VoucherNotForAccount {
+            account_id: charlie.account_id(),
+            gate_id: gate_id(1).to_string(),
+        }
+        .msg(),
+    );
+    redeem_voucher(nft, bob, &VoucherSigner::from_seed(&[2; 32]), voucher.clone())
+        .failure(Panic::InvalidVoucherSignature { gate_id: gate_id(1).to_string() }.msg());
+
+    let alice_balance = alice.balance();
+    let mintgate_balance = mintgate.balance();
+    let token_id = redeem_voucher(nft, bob, &signer, voucher.clone()).unwrap();
+    alice.check_amount(alice_balance + to_yocto("3") - to_yocto("0.075"));
+    mintgate.check_amount(mintgate_balance + to_yocto("0.075"));
+
+    let token = get_tokens_by_owner(nft, bob).into_iter().next().unwrap();
+    assert_eq!(token.token_id, token_id);
+    assert_eq!(token.edition, 1);
+
+    redeem_voucher(nft, bob, &signer, voucher).failure(
+        Panic::VoucherAlreadyRedeemed { nonce: U64(1), creator_id: alice.account_id() }.msg(),
+    );
+
+    let voucher = Voucher {
+        gate_id: gate_id(2),
+        collectible: Some(VoucherCollectible {
+            creator_id: alice.account_id(),
+            title: "Lazy collectible".to_string(),
+            description: "Created by its first voucher".to_string(),
+            supply: 10,
+            royalty: "10/100".parse().unwrap(),
+            media: None,
+            media_hash: None,
+            reference: None,
+            reference_hash: None,
+        }),
+        receiver_id: None,
+        price: U128(0),
+        expires_at: u64::MAX,
+        nonce: U64(2),
+    };
+
+    redeem_voucher(nft, charlie, &signer, voucher.clone()).failure(
+        Panic::Unauthorized { account_id: alice.account_id(), role: Role::CollectibleMinter }.msg(),
+    );
+    call!(admin, nft.grant_role(alice.valid_account_id(), Role::CollectibleMinter))
+        .assert_success();
+    let token_id = redeem_voucher(nft, charlie, &signer, voucher).unwrap();
+
+    let collectible = get_collectible_by_gate_id(nft, gate_id(2));
+    assert_eq!(collectible.creator_id, alice.account_id());
+    assert_eq!(collectible.minted_tokens, vec![token_id]);
+}
+
+#[test]
+fn claim_linkdrops() {
+    let Sim { nft, alice, bob, admin, .. } = &mut init(0, "1/1000", "30/100", "25/1000");
+
+    create_collectible(nft, admin, alice, gate_id(1), 10, "10/100").unwrap();
+
+    let nft_id = nft.account_id();
+    let key = |seed: &str| InMemorySigner::from_seed(&nft_id, KeyType::ED25519, seed);
+    let bob_key = key("bob");
+    let fan_key = key("fan");
+    let fan_account_key = InMemorySigner::from_seed("fan.nft", KeyType::ED25519, "fan.nft");
+
+    let token_id = add_linkdrop(nft, alice, gate_id(1), &bob_key, to_yocto("1")).unwrap().unwrap();
+    let linkdrop = get_linkdrop(nft, &bob_key).unwrap();
+    assert_eq!(linkdrop.token_id, token_id);
+    assert_eq!(linkdrop.funder_id, alice.account_id());
+
+    // Only this contract, *i.e.*, a linkdrop key, can claim and resolve linkdrops.
+    assert!(!call!(bob, nft.claim_linkdrop(bob.valid_account_id())).is_ok());
+    let bob_public_key = bob_key.public_key.to_string().try_into().unwrap();
+    assert!(!call!(bob, nft.resolve_add_linkdrop(bob_public_key)).is_ok());
+
+    let bob_balance = bob.balance();
+    assert_eq!(claim_linkdrop(nft, &bob_key, &bob.account_id()).unwrap(), token_id);
+    bob.check_amount(bob_balance + linkdrop.amount.0);
+    assert_eq!(get_tokens_by_owner(nft, bob).into_iter().next().unwrap().token_id, token_id);
+    assert_eq!(get_linkdrop(nft, &bob_key), None);
+
+    let token_id = add_linkdrop(nft, alice, gate_id(1), &fan_key, to_yocto("1")).unwrap().unwrap();
+    assert!(create_account_and_claim_linkdrop(nft, &fan_key, "fan.nft", &fan_account_key).unwrap());
+    assert_eq!(get_linkdrop(nft, &fan_key), None);
+    let tokens: Vec<Token> =
+        view!(nft.get_tokens_by_owner("fan.nft".try_into().unwrap())).unwrap_json();
+    assert_eq!(tokens.into_iter().next().unwrap().token_id, token_id);
+
+    let fan_key = key("fan-again");
+    add_linkdrop(nft, alice, gate_id(1), &fan_key, to_yocto("1")).unwrap().unwrap();
+    assert!(!create_account_and_claim_linkdrop(nft, &fan_key, "fan.nft", &fan_account_key).unwrap());
+    assert!(get_linkdrop(nft, &fan_key).is_some());
+
+    let nft_key = nft.user_account.signer.clone();
+    let alice_balance = alice.balance();
+    assert_eq!(add_linkdrop(nft, alice, gate_id(1), &nft_key, to_yocto("1")).unwrap(), None);
+    assert_eq!(get_linkdrop(nft, &nft_key), None);
+    alice.check_amount(alice_balance - to_yocto("0.01"));
+    assert_eq!(get_tokens_by_owner(nft, alice).len(), 1);
+    remove_linkdrop(nft, alice, &nft_key).failure(Panic::LinkdropNotFound.msg());
+}
+
 #[test]
 fn nft_approve_and_revoke_tokens() {
     let Sim { nft, markets, fake_market, alice, bob, charlie, admin, .. } =
@@ -195,7 +357,9 @@ fn buy_a_few_tokens() {
     create_collectible(nft, admin, alice, gate_id(1), 10, "10/100").unwrap();
     claim_token(nft, bob, 1).unwrap();
 
+    let alice_balance = alice.balance();
     let token_id = claim_token(nft, alice, 1).unwrap();
+    alice.check_amount(alice_balance);
     nft_approve(nft, &markets[0], alice, token_id, "3").unwrap();
 
     buy_token(&markets[0], nft, bob, token_id, "2")
@@ -220,6 +384,220 @@ fn buy_a_few_tokens() {
     mintgate.check_amount(mintgate_balance + to_yocto("0.175"));
 }
 
+#[test]
+fn buy_a_token_listed_in_many_markets() {
+    let Sim { nft, markets, alice, bob, admin, .. } = &init(3, "1/1000", "30/100", "25/1000");
+
+    create_collectible(nft, admin, alice, gate_id(1), 10, "10/100").unwrap();
+    let token_id = claim_token(nft, alice, 1).unwrap();
+    for market in markets {
+        nft_approve(nft, market, alice, token_id, "3").unwrap();
+    }
+
+    let token = &get_tokens_by_owner(nft, alice)[0];
+    assert_eq!(token.approvals.len(), markets.len());
+
+    buy_token(&markets[1], nft, bob, token_id, "3").unwrap();
+
+    for market in markets {
+        let tokens = get_tokens_for_sale(market);
+        assert!(!tokens.iter().map(|t| t.token_id).collect::<Vec<TokenId>>().contains(&token_id));
+    }
+    let token = &get_tokens_by_owner(nft, bob)[0];
+    assert_eq!(token.approvals.len(), 0);
+}
+
+#[test]
+fn buy_a_token_no_longer_transferable() {
+    let Sim { nft, markets, alice, bob, admin, .. } = &init(1, "1/1000", "30/100", "25/1000");
+
+    create_collectible(nft, admin, alice, gate_id(1), 10, "10/100").unwrap();
+    let token_id = claim_token(nft, alice, 1).unwrap();
+    nft_approve(nft, &markets[0], alice, token_id, "3").unwrap();
+
+    call!(alice, nft.set_transferability(gate_id(1), Transferability::NonTransferable))
+        .assert_success();
+
+    let bob_balance = bob.balance();
+    call!(bob, markets[0].buy_token(nft.valid_account_id(), token_id), deposit = to_yocto("3"))
+        .assert_success();
+    bob.check_amount(bob_balance);
+
+    let tokens = get_tokens_by_owner(nft, alice);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].token_id, token_id);
+    assert_eq!(get_tokens_for_sale(&markets[0]).len(), 0);
+}
+
+#[test]
+fn buy_a_token_while_nft_transfers_are_paused() {
+    let Sim { nft, markets, alice, bob, admin, .. } = &init(1, "1/1000", "30/100", "25/1000");
+
+    create_collectible(nft, admin, alice, gate_id(1), 10, "10/100").unwrap();
+    let token_id = claim_token(nft, alice, 1).unwrap();
+    nft_approve(nft, &markets[0], alice, token_id, "3").unwrap();
+
+    call!(admin, nft.pause(Pausable::Transfers)).assert_success();
+
+    let bob_balance = bob.balance();
+    call!(bob, markets[0].buy_token(nft.valid_account_id(), token_id), deposit = to_yocto("3"))
+        .assert_success();
+    bob.check_amount(bob_balance);
+    assert_eq!(get_tokens_by_owner(nft, bob).len(), 0);
+    let tokens = get_tokens_for_sale(&markets[0]);
+    assert_eq!(tokens.iter().map(|t| t.token_id).collect::<Vec<TokenId>>(), vec![token_id]);
+
+    call!(admin, nft.unpause(Pausable::Transfers)).assert_success();
+    buy_token(&markets[0], nft, bob, token_id, "3").unwrap();
+}
+
+#[test]
+fn transfer_call_returns_rejected_tokens() {
+    let Sim { nft, markets, alice, bob, admin, .. } = &init(1, "1/1000", "30/100", "25/1000");
+
+    create_collectible(nft, admin, alice, gate_id(1), 10, "10/100").unwrap();
+    let token_id = claim_token(nft, alice, 1).unwrap();
+    nft_approve(nft, &markets[0], alice, token_id, "3").unwrap();
+
+    let check_returned = || {
+        let tokens = get_tokens_by_owner(nft, alice);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].token_id, token_id);
+        assert_eq!(tokens[0].approvals.len(), 1);
+        assert!(tokens[0].approvals.contains_key(&markets[0].account_id()));
+        assert_eq!(get_tokens_by_owner(nft, bob).len(), 0);
+    };
+
+    // `bob` has no contract deployed, so `nft_on_transfer` fails.
+    assert!(!nft_transfer_call(nft, alice, bob.valid_account_id(), token_id).unwrap());
+    check_returned();
+
+    // The market does not implement `nft_on_transfer`.
+    assert!(!nft_transfer_call(nft, alice, markets[0].valid_account_id(), token_id).unwrap());
+    check_returned();
+
+    nft_transfer_call(nft, bob, markets[0].valid_account_id(), token_id)
+        .failure(Panic::SenderNotAuthToTransfer { sender_id: bob.account_id() }.msg());
+
+    // Since approvals were restored, the token can still be bought.
+    buy_token(&markets[0], nft, bob, token_id, "3").unwrap();
+}
+
+#[test]
+fn upgrade_contracts_keeps_state() {
+    let Sim { nft, markets, alice, bob, charlie, admin, .. } =
+        &init_v1("1/1000", "30/100", "25/1000");
+    let market = &markets[0];
+
+    for (k, creator) in [alice, bob].iter().enumerate() {
+        call_v1(
+            admin,
+            &nft.account_id(),
+            "create_collectible",
+            json!({
+                "creator_id": creator.account_id(),
+                "gate_id": gate_id(k as u16 + 1),
+                "title": "My collectible",
+                "description": "NFT description",
+                "supply": 10,
+                "royalty": "10/100".parse::<Fraction>().unwrap(),
+                "media": null,
+                "media_hash": null,
+                "reference": null,
+                "reference_hash": null,
+            }),
+        )
+        .unwrap();
+    }
+
+    let claim_v1 = |user: &UserAccount, gate_key: u16| -> TokenId {
+        call_v1(user, &nft.account_id(), "claim_token", json!({ "gate_id": gate_id(gate_key) }))
+            .unwrap()
+            .unwrap_json()
+    };
+    let token_id = claim_v1(bob, 1);
+    claim_v1(bob, 2);
+    claim_v1(charlie, 1);
+
+    call_v1(
+        bob,
+        &nft.account_id(),
+        "nft_approve",
+        json!({
+            "token_id": token_id,
+            "account_id": market.account_id(),
+            "msg": serde_json::to_string(&NftApproveMsg { min_price: to_yocto("5").into() }).unwrap(),
+        }),
+    )
+    .unwrap();
+
+    let snapshot = || -> Value {
+        json!([
+            view!(nft.get_collectibles_by_creator(alice.valid_account_id())).unwrap_json_value(),
+            view!(nft.nft_tokens(None, None)).unwrap_json_value(),
+            view!(market.get_tokens_for_sale()).unwrap_json_value(),
+        ])
+    };
+    let before = snapshot();
+    assert_eq!(before[1].as_array().unwrap().len(), 3);
+    assert_eq!(before[2].as_array().unwrap().len(), 1);
+
+    // Only the contract account itself can migrate its state.
+    assert!(!call!(alice, nft.migrate()).is_ok());
+
+    upgrade_nft(nft).unwrap();
+    upgrade_market(market, admin).unwrap();
+
+    // Collectibles and tokens are migrated in batches, meanwhile the contract cannot be used.
+    assert!(view!(nft.nft_tokens(None, None)).is_err());
+    claim_token(nft, charlie, 1).unwrap_err();
+    // Nor can anyone take it over by initializing it again.
+    init_nft(nft, alice).failure(Panic::MigrationInProgress.msg());
+    assert!(!migrate_batch(nft, 2).unwrap());
+    assert!(!migrate_batch(nft, 2).unwrap());
+    assert!(view!(nft.nft_tokens(None, None)).is_err());
+    assert!(migrate_batch(nft, 2).unwrap());
+    migrate_batch(nft, 2).failure(Panic::MigrationNotStarted.msg());
+
+    // Editions are numbered by claim order and shown in token titles.
+    let mut before = before;
+    for (token, edition) in before[1].as_array_mut().unwrap().iter_mut().zip([1, 1, 2].iter()) {
+        token["edition"] = json!(edition);
+        token["metadata"]["title"] = json!(format!("My collectible #{}", edition));
+    }
+    assert_json_includes(&snapshot(), &before);
+    let collectible = get_collectible_by_gate_id(nft, gate_id(2));
+    assert_eq!(collectible.creator_id, bob.account_id());
+    assert!(!collectible.frozen);
+    assert!(collectible.royalty_split.is_empty());
+    assert_eq!(collectible.mint_price, U128(0));
+    assert_eq!(collectible.allowlist_root, None);
+    assert_eq!(collectible.max_claims_per_account, None);
+    assert_eq!(collectible.transferability, Transferability::Transferable);
+    assert_eq!(collectible.supply, 10);
+
+    upgrade_nft(nft).failure(Panic::UnsupportedStateMigration { from: 3, to: 3 }.msg());
+    upgrade_market(market, admin)
+        .failure(mg_market::Panics::UnsupportedStateMigration { from: 3, to: 3 }.msg());
+
+    let paused: Vec<Pausable> = view!(nft.get_paused()).unwrap_json();
+    assert!(paused.is_empty());
+    call!(admin, market.pause(Pausable::Purchases)).assert_success();
+    buy_token(market, nft, alice, token_id, "5")
+        .failure(mg_market::Panics::Paused { feature: Pausable::Purchases }.msg());
+    call!(admin, market.unpause(Pausable::Purchases)).assert_success();
+
+    let holders: Vec<String> = view!(nft.get_role_holders(Role::Owner)).unwrap_json();
+    assert_eq!(holders, vec![admin.account_id()]);
+    let holders: Vec<String> = view!(market.get_role_holders(Role::Owner)).unwrap_json();
+    assert_eq!(holders, vec![admin.account_id()]);
+
+    create_collectible(nft, admin, alice, gate_id(3), 10, "10/100").unwrap();
+    assert_eq!(claim_token(nft, charlie, 3).unwrap(), U64(3));
+    buy_token(market, nft, alice, token_id, "5").unwrap();
+    assert_eq!(get_tokens_for_sale(market).len(), 0);
+}
+
 fn approve_msg(price: u128, gate_id: ValidGateId, creator_id: ValidAccountId) -> String {
     serde_json::to_string(&MarketApproveMsg {
         min_price: price.into(),
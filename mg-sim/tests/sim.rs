@@ -1,16 +1,25 @@
 near_sdk_sim::lazy_static_include::lazy_static_include_bytes! {
     NFT_WASM_BYTES => "../target/wasm32-unknown-unknown/release/mg_nft.wasm",
     MARKET_WASM_BYTES => "../target/wasm32-unknown-unknown/release/mg_market.wasm",
+    NFT_V1_WASM_BYTES => "res/mg_nft_v1.wasm",
+    MARKET_V1_WASM_BYTES => "res/mg_market_v1.wasm",
 }
 
 use ansi_term::{Colour, Style};
 use mg_core::{
-    gate::ValidGateId, mocked_context::gate_id, Collectible, NftApproveMsg, Token, TokenId,
+    fraction::Fraction,
+    gate::ValidGateId,
+    linkdrop::Linkdrop,
+    mocked_context::gate_id,
+    voucher::{Voucher, VoucherSigner},
+    Collectible, NftApproveMsg, Token, TokenId,
 };
 use mg_market::TokenForSale;
+use near_crypto::InMemorySigner;
 use near_sdk::{
-    json_types::{ValidAccountId, U128, U64},
-    serde_json, Balance,
+    json_types::{Base58PublicKey, ValidAccountId, U128, U64},
+    serde_json::{self, json, Value},
+    Balance,
 };
 use near_sdk_sim::{
     call,
@@ -28,6 +37,12 @@ pub use mg_nft::NftContractContract as NftContract;
 const NFT_ID: &str = "nft";
 const MARKET_ID: &str = "market";
 
+/// Deposit attached to cover storage, any excess is refunded by the contract.
+pub const STORAGE_DEPOSIT: Balance = 100_000_000_000_000_000_000_000;
+
+/// Gas for linkdrop claims, it must be covered by the linkdrop key allowance.
+const LINKDROP_GAS: u64 = 150_000_000_000_000;
+
 pub trait BalanceChecker {
     fn balance(&self) -> Balance;
     fn check_amount(&self, expected_amount: Balance);
@@ -71,13 +86,16 @@ pub struct Sim {
     pub charlie: UserAccount,
 }
 
+fn create_users(
+    root: &UserAccount,
+) -> (UserAccount, UserAccount, UserAccount, UserAccount, UserAccount) {
+    let user = |account_id: &str| root.create_user(account_id.to_string(), to_yocto("20"));
+    (user("mintgate"), user("admin"), user("alice"), user("bob"), user("charlie"))
+}
+
 pub fn init(n: usize, min_royalty: &str, max_royalty: &str, mintgate_fee: &str) -> Sim {
     let root = init_simulator(None);
-    let mintgate = root.create_user("mintgate".to_string(), to_yocto("20"));
-    let admin = root.create_user("admin".to_string(), to_yocto("20"));
-    let alice = root.create_user("alice".to_string(), to_yocto("20"));
-    let bob = root.create_user("bob".to_string(), to_yocto("20"));
-    let charlie = root.create_user("charlie".to_string(), to_yocto("20"));
+    let (mintgate, admin, alice, bob, charlie) = create_users(&root);
 
     let nft = deploy!(
         contract: NftContract,
@@ -107,7 +125,7 @@ pub fn init(n: usize, min_royalty: &str, max_royalty: &str, mintgate_fee: &str)
             signer_account: root,
             deposit: near_sdk_sim::STORAGE_AMOUNT * 10,
             gas: near_sdk_sim::DEFAULT_GAS,
-            init_method: init()
+            init_method: init(admin.valid_account_id())
         );
         mids.push(mid);
         markets.push(market);
@@ -121,6 +139,144 @@ pub fn init(n: usize, min_royalty: &str, max_royalty: &str, mintgate_fee: &str)
     Sim { root, nft, markets, fake_market, mids, mintgate, admin, alice, bob, charlie }
 }
 
+/// Deploys both contracts as they were before state versioning was introduced,
+/// *i.e.*, the `res/*_v1.wasm` binaries.
+/// Use `call_v1` to interact with them, since their interface differs from the current one.
+pub fn init_v1(min_royalty: &str, max_royalty: &str, mintgate_fee: &str) -> Sim {
+    let root = init_simulator(None);
+    let (mintgate, admin, alice, bob, charlie) = create_users(&root);
+
+    let nft = deploy!(
+        contract: NftContract,
+        contract_id: NFT_ID,
+        bytes: &NFT_V1_WASM_BYTES,
+        signer_account: root,
+        deposit: STORAGE_AMOUNT * 10
+    );
+    call_v1(
+        &root,
+        NFT_ID,
+        "init",
+        json!({
+            "admin_id": admin.account_id(),
+            "metadata": metadata(),
+            "min_royalty": min_royalty.parse::<Fraction>().unwrap(),
+            "max_royalty": max_royalty.parse::<Fraction>().unwrap(),
+            "mintgate_fee": mintgate_fee.parse::<Fraction>().unwrap(),
+            "mintgate_fee_account_id": mintgate.account_id(),
+        }),
+    )
+    .unwrap();
+
+    let mid = format!("{}{}", MARKET_ID, 0);
+    let market = deploy!(
+        contract: MarketContract,
+        contract_id: mid.clone(),
+        bytes: &MARKET_V1_WASM_BYTES,
+        signer_account: root,
+        deposit: STORAGE_AMOUNT * 10
+    );
+    call_v1(&root, &mid, "init", json!({})).unwrap();
+
+    let fake_market = ContractAccount {
+        user_account: root.create_user("fake_market_account".to_string(), to_yocto("20")),
+        contract: MarketContract { account_id: "fake_market".to_string() },
+    };
+
+    Sim {
+        root,
+        nft,
+        markets: vec![market],
+        fake_market,
+        mids: vec![mid],
+        mintgate,
+        admin,
+        alice,
+        bob,
+        charlie,
+    }
+}
+
+/// Calls `method` on contracts deployed with `init_v1`.
+/// These contracts do not charge for storage, so no deposit is attached.
+pub fn call_v1(
+    user: &UserAccount,
+    contract_id: &str,
+    method: &str,
+    args: Value,
+) -> Result<ExecutionResult, String> {
+    println!("[{}] `{}` calling `{}` on v1 contract", contract_id, user.account_id, method);
+    tx(user.call(contract_id.to_string(), method, args.to_string().as_bytes(), DEFAULT_GAS, 0))
+}
+
+/// Deploys `bytes` into `contract` and migrates its state, both in a single transaction.
+fn upgrade(contract: &UserAccount, bytes: &[u8], args: Value) -> Result<ExecutionResult, String> {
+    println!("[{}] upgrading contract", contract.account_id);
+    tx(contract
+        .create_transaction(contract.account_id())
+        .deploy_contract(bytes.to_vec())
+        .function_call("migrate".to_string(), args.to_string().into_bytes(), DEFAULT_GAS, 0)
+        .submit())
+}
+
+/// Upgrades the NFT contract into its current version.
+pub fn upgrade_nft(nft: &ContractAccount<NftContract>) -> Result<(), String> {
+    upgrade(&nft.user_account, &NFT_WASM_BYTES, json!({})).map(|_| ())
+}
+
+/// Migrates at most `limit` collectibles and tokens left by `upgrade_nft`.
+pub fn migrate_batch(nft: &ContractAccount<NftContract>, limit: u32) -> Result<bool, String> {
+    tx(call!(nft.user_account, nft.migrate_batch(limit))).map(|result| result.unwrap_json())
+}
+
+/// Tries to initialize the NFT contract, making `owner` its owner.
+pub fn init_nft(nft: &ContractAccount<NftContract>, owner: &UserAccount) -> Result<(), String> {
+    tx(call!(
+        owner,
+        nft.init(
+            owner.valid_account_id(),
+            metadata(),
+            "0/1".parse().unwrap(),
+            "1/1".parse().unwrap(),
+            "0/1".parse().unwrap(),
+            owner.valid_account_id()
+        )
+    ))
+    .map(|_| ())
+}
+
+/// Upgrades the market contract into its current version.
+/// `owner` becomes the owner of markets deployed before they had one.
+pub fn upgrade_market(
+    market: &ContractAccount<MarketContract>,
+    owner: &UserAccount,
+) -> Result<(), String> {
+    let args = json!({ "owner_id": owner.account_id() });
+    upgrade(&market.user_account, &MARKET_WASM_BYTES, args).map(|_| ())
+}
+
+/// Asserts that `actual` contains everything in `expected`.
+/// Objects in `actual` may have additional fields, *e.g.*, fields added by a migration.
+pub fn assert_json_includes(actual: &Value, expected: &Value) {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, value) in expected {
+                match actual.get(key) {
+                    Some(actual_value) => assert_json_includes(actual_value, value),
+                    None => panic!("Key `{}` not found in {:?}", key, actual),
+                }
+            }
+        }
+        (Value::Array(actual), Value::Array(expected)) => {
+            assert_eq!(actual.len(), expected.len());
+            for (actual_value, value) in actual.iter().zip(expected) {
+                assert_json_includes(actual_value, value);
+            }
+        }
+        _ => assert_eq!(actual, expected),
+    }
+}
+
 fn metadata() -> mg_core::nep177::NFTContractMetadata {
     mg_core::nep177::NFTContractMetadata {
         spec: "mg-nft-1.0.0".to_string(),
@@ -164,9 +320,13 @@ pub fn create_collectible(
             None,
             None,
             None,
+            None,
+            None,
+            None,
+            None,
             None
         ),
-        0,
+        STORAGE_DEPOSIT,
         DEFAULT_GAS
     )) {
         Ok(_) => {
@@ -199,7 +359,7 @@ pub fn claim_token(
 ) -> Result<TokenId, String> {
     let gate_id = gate_id(gate_key);
     println!("[{}] `{}` claiming token for `{}`", nft.account_id(), user.account_id, gate_id,);
-    match tx(call!(user, nft.claim_token(gate_id))) {
+    match tx(call!(user, nft.claim_token(gate_id), deposit = STORAGE_DEPOSIT)) {
         Ok(x) => {
             let result: Option<TokenId> = x.unwrap_json();
             Ok(result.unwrap())
@@ -208,6 +368,119 @@ pub fn claim_token(
     }
 }
 
+pub fn mint_to(
+    nft: &ContractAccount<NftContract>,
+    user: &UserAccount,
+    gate_key: u16,
+    receivers: &[&UserAccount],
+) -> Result<Vec<TokenId>, String> {
+    let gate_id = gate_id(gate_key);
+    println!(
+        "[{}] `{}` minting {} tokens of `{}`",
+        nft.account_id(),
+        user.account_id,
+        receivers.len(),
+        gate_id
+    );
+    let receiver_ids = receivers.iter().map(|receiver| receiver.valid_account_id()).collect();
+    tx(call!(user, nft.mint_to(gate_id, receiver_ids), deposit = STORAGE_DEPOSIT * 10))
+        .map(|result| result.unwrap_json())
+}
+
+pub fn set_voucher_key(
+    nft: &ContractAccount<NftContract>,
+    user: &UserAccount,
+    signer: &VoucherSigner,
+) -> Result<(), String> {
+    println!("[{}] `{}` setting its voucher key", nft.account_id(), user.account_id);
+    tx(call!(user, nft.set_voucher_key(Some(signer.public_key())), deposit = STORAGE_DEPOSIT))
+        .map(|_| ())
+}
+
+pub fn redeem_voucher(
+    nft: &ContractAccount<NftContract>,
+    user: &UserAccount,
+    signer: &VoucherSigner,
+    voucher: Voucher,
+) -> Result<TokenId, String> {
+    println!(
+        "[{}] `{}` redeeming voucher `{:?}` for `{}`",
+        nft.account_id(),
+        user.account_id,
+        voucher.nonce,
+        voucher.gate_id
+    );
+    let signature = signer.sign(&nft.account_id(), &voucher);
+    let deposit = STORAGE_DEPOSIT + voucher.price.0;
+    tx(call!(user, nft.redeem_voucher(voucher, signature), deposit = deposit))
+        .map(|result| result.unwrap_json())
+}
+
+pub fn add_linkdrop(
+    nft: &ContractAccount<NftContract>,
+    user: &UserAccount,
+    gate_id: ValidGateId,
+    signer: &InMemorySigner,
+    deposit: Balance,
+) -> Result<Option<TokenId>, String> {
+    println!("[{}] `{}` adding a linkdrop for `{}`", nft.account_id(), user.account_id, gate_id);
+    tx(call!(user, nft.add_linkdrop(gate_id, public_key(signer)), deposit = deposit))
+        .map(|result| result.unwrap_json())
+}
+
+pub fn get_linkdrop(
+    nft: &ContractAccount<NftContract>,
+    signer: &InMemorySigner,
+) -> Option<Linkdrop> {
+    view!(nft.get_linkdrop(public_key(signer))).unwrap_json()
+}
+
+pub fn remove_linkdrop(
+    nft: &ContractAccount<NftContract>,
+    user: &UserAccount,
+    signer: &InMemorySigner,
+) -> Result<(), String> {
+    println!("[{}] `{}` removing a linkdrop", nft.account_id(), user.account_id);
+    tx(call!(user, nft.remove_linkdrop(public_key(signer)))).map(|_| ())
+}
+
+/// Claims the linkdrop signing with its own key,
+/// just like a wallet holding the linkdrop secret key would do.
+pub fn claim_linkdrop(
+    nft: &mut ContractAccount<NftContract>,
+    signer: &InMemorySigner,
+    account_id: &str,
+) -> Result<TokenId, String> {
+    println!("[{}] claiming linkdrop into `{}`", nft.account_id(), account_id);
+    let account_id: ValidAccountId = account_id.try_into().unwrap();
+    let nft_signer = std::mem::replace(&mut nft.user_account.signer, signer.clone());
+    let result = tx(call!(nft.user_account, nft.claim_linkdrop(account_id), gas = LINKDROP_GAS));
+    nft.user_account.signer = nft_signer;
+    result.map(|result| result.unwrap_json())
+}
+
+pub fn create_account_and_claim_linkdrop(
+    nft: &mut ContractAccount<NftContract>,
+    signer: &InMemorySigner,
+    new_account_id: &str,
+    new_signer: &InMemorySigner,
+) -> Result<bool, String> {
+    println!("[{}] creating `{}` to claim linkdrop", nft.account_id(), new_account_id);
+    let new_account_id: ValidAccountId = new_account_id.try_into().unwrap();
+    let nft_signer = std::mem::replace(&mut nft.user_account.signer, signer.clone());
+    let result = tx(call!(
+        nft.user_account,
+        nft.create_account_and_claim_linkdrop(new_account_id, public_key(new_signer)),
+        gas = LINKDROP_GAS
+    ));
+    nft.user_account.signer = nft_signer;
+    result.map(|result| result.unwrap_json())
+}
+
+fn public_key(signer: &InMemorySigner) -> Base58PublicKey {
+    signer.public_key.to_string().try_into().unwrap()
+}
+
 pub fn burn_token(
     nft: &ContractAccount<NftContract>,
     user: &UserAccount,
@@ -228,6 +501,30 @@ pub fn burn_token(
     }
 }
 
+pub fn nft_transfer_call(
+    nft: &ContractAccount<NftContract>,
+    user: &UserAccount,
+    receiver_id: ValidAccountId,
+    token_id: TokenId,
+) -> Result<bool, String> {
+    println!(
+        "[{}] `{}` transferring token `{:?}` to `{}` with call",
+        nft.account_id(),
+        user.account_id,
+        token_id,
+        receiver_id
+    );
+    match tx(call!(
+        user,
+        nft.nft_transfer_call(receiver_id, token_id, None, None, "".to_string()),
+        0,
+        DEFAULT_GAS
+    )) {
+        Ok(x) => Ok(x.unwrap_json()),
+        Err(msg) => Err(msg),
+    }
+}
+
 pub fn get_tokens_by_owner(nft: &ContractAccount<NftContract>, user: &UserAccount) -> Vec<Token> {
     let tokens: Vec<Token> =
         view!(nft.get_tokens_by_owner(user.account_id().try_into().unwrap())).unwrap_json();
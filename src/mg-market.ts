@@ -7,6 +7,9 @@ export type U128 = string;
 export type I128 = string;
 export type AccountId = string;
 export type ValidAccountId = string;
+export type Base58CryptoHash = string;
+export type Base58PublicKey = string;
+export type Base64VecU8 = string;
 
 /**
  *  The error variants thrown by *mg-core*.
@@ -117,10 +120,16 @@ export type TokenId = U64;
 export type Timestamp = number;
 
 /**
- *  Mapping from `AccountId`s to balance (in NEARs).
- *  The balance indicates the amount a Marketplace contract should pay when a Token is being sold.
+ *  Payout of a token sale, as defined by NEP-199.
  */
-export type Payout = Record<AccountId, U128>;
+export type Payout = {
+    /**
+     *  Mapping from `AccountId`s to balance (in NEARs).
+     *  The balance indicates the amount a Marketplace contract should pay when a Token is being sold.
+     */
+    payout: Record<AccountId, U128>;
+
+}
 
 /**
  *  A `Collectible` represents something of value.
@@ -143,6 +152,12 @@ export type Collectible = {
      */
     current_supply: number;
 
+    /**
+     *  Indicates how many `Token`s this `Collectible` was created with.
+     *  Unlike `current_supply`, it remains unchanged when tokens are minted.
+     */
+    supply: number;
+
     /**
      *  The list of `TokenId`s actually minted out of this `Collectible`.
      */
@@ -154,11 +169,120 @@ export type Collectible = {
      */
     royalty: Fraction;
 
+    /**
+     *  Royalties paid to the collaborators of `creator_id`, on top of `royalty`,
+     *  every time a minted token out of this `Collectible` is reselled.
+     */
+    royalty_split: RoyaltyShare[];
+
+    /**
+     *  Indicates the price (in NEARs) to be paid when claiming a `Token` out of this `Collectible`.
+     *  A `mint_price` of `0` means tokens can be claimed for free.
+     */
+    mint_price: U128;
+
+    /**
+     *  The root of the Merkle tree of accounts allowed to claim `Token`s out of this `Collectible`.
+     *  When present, tokens can only be claimed with a proof, see `merkle`.
+     */
+    allowlist_root: Base58CryptoHash|null;
+
+    /**
+     *  Indicates how many `Token`s each account can claim out of this `Collectible`.
+     *  Tokens transferred or burned still count towards this limit.
+     *  A `max_claims_per_account` of `null` means there is no limit.
+     */
+    max_claims_per_account: number|null;
+
+    /**
+     *  Indicates who can transfer the `Token`s minted out of this `Collectible`.
+     */
+    transferability: Transferability;
+
     /**
      *  Additional info provided by NEP-177.
      */
     metadata: Metadata;
 
+    /**
+     *  Indicates whether `metadata` can no longer be updated.
+     */
+    frozen: boolean;
+
+    /**
+     *  The commitment of the real metadata of this `Collectible`, until it is revealed.
+     *  While present, `metadata` is just a placeholder, see `reveal`.
+     */
+    metadata_commitment: Base58CryptoHash|null;
+
+    /**
+     *  Indicates when `Token`s can no longer be claimed out of this `Collectible`,
+     *  as UNIX epoch datetime in miliseconds, while claims start at `metadata.starts_at`.
+     *  Unlike `metadata.expires_at`, it does not affect the access granted by claimed tokens.
+     */
+    claims_end_at: Timestamp|null;
+
+}
+
+/**
+ *  Royalty paid to a collaborator of a `Collectible`.
+ */
+export type RoyaltyShare = {
+    /**
+     *  The collaborator to be paid.
+     */
+    account_id: AccountId;
+
+    /**
+     *  The royalty as percentage (in NEARs) of the sale price paid to `account_id`.
+     */
+    share: Fraction;
+
+}
+
+/**
+ *  Indicates who can transfer the `Token`s of a `Collectible`.
+ *  Only `Transferable` tokens can be approved for markets.
+ */
+export enum Transferability {
+    /**
+     *  Tokens can be transferred by their owners and approved accounts.
+     */
+    Transferable,
+
+    /**
+     *  Tokens cannot be transferred at all, *i.e.*, they are soulbound to their owners.
+     */
+    NonTransferable,
+
+    /**
+     *  Tokens can only be transferred to or from the `creator_id` of the `Collectible`,
+     *  *e.g.*, the creator hands out tokens and owners give them back.
+     */
+    CreatorOnly,
+
+}
+
+/**
+ *  Indicates whether `Token`s can be claimed out of a `Collectible` at a given time,
+ *  according to the `starts_at` and `expires_at` of its `metadata` and its `claims_end_at`.
+ */
+export enum ClaimStatus {
+    /**
+     *  Claims have not started yet.
+     */
+    Upcoming,
+
+    /**
+     *  Tokens can be claimed.
+     */
+    Live,
+
+    /**
+     *  Claims have already ended.
+     */
+    Ended,
+
 }
 
 /**
@@ -177,6 +301,12 @@ export type Token = {
      */
     gate_id: GateId;
 
+    /**
+     *  The edition number of this `Token` within its `Collectible`, starting from `1`.
+     *  It is assigned when minted, following the order in which tokens are claimed.
+     */
+    edition: number;
+
     /**
      *  The owner of this token.
      */
@@ -207,7 +337,8 @@ export type Token = {
 
     /**
      *  Additional info defined by NEP-177.
-     *  This `metadata` effectively joins fields from its respective `gate_id`.
+     *  This `metadata` effectively joins fields from its respective `gate_id`,
+     *  with the `edition` appended to the `title`, *e.g.*, `My collectible #7`.
      */
     metadata: Metadata;
 
@@ -301,6 +432,83 @@ export type TokenApproval = {
 
 }
 
+/**
+ *  Roles an account can hold to perform privileged operations in a contract.
+ *  The `Owner` implicitly holds every role,
+ *  while an `Admin` also acts as `CollectibleMinter` and `Pauser`.
+ */
+export enum Role {
+    /**
+     *  The single account that owns the contract.
+     */
+    Owner,
+
+    /**
+     *  Manages the contract on behalf of the owner, *e.g.*, grants other roles.
+     */
+    Admin,
+
+    /**
+     *  Allowed to create collectibles.
+     */
+    CollectibleMinter,
+
+    /**
+     *  Allowed to pause and unpause the contract.
+     */
+    Pauser,
+
+}
+
+/**
+ *  Features that can be paused in case of an emergency.
+ *  Each contract only checks the features it implements.
+ */
+export enum Pausable {
+    /**
+     *  Claiming tokens in the NFT contract.
+     */
+    Minting,
+
+    /**
+     *  Transferring and approving tokens in the NFT contract.
+     */
+    Transfers,
+
+    /**
+     *  Buying tokens in the Marketplace contract.
+     */
+    Purchases,
+
+}
+
+/**
+ *  Parameters of the NFT contract that rule royalties and fees.
+ *  They are set when the contract is initialized, and can be later updated by an admin.
+ */
+export type NftConfig = {
+    /**
+     *  Minimum `royalty` allowed when creating a `Collectible`.
+     */
+    min_royalty: Fraction;
+
+    /**
+     *  Maximum `royalty` allowed when creating a `Collectible`.
+     */
+    max_royalty: Fraction;
+
+    /**
+     *  Percentage fee paid to `mintgate_fee_account_id` when a `Token` is sold.
+     */
+    mintgate_fee: Fraction;
+
+    /**
+     *  Account receiving the `mintgate_fee`.
+     */
+    mintgate_fee_account_id: AccountId;
+
+}
+
 /**
  *  Associated metadata for the NFT contract as defined by NEP-177
  * 
@@ -345,6 +553,281 @@ export type NFTContractMetadata = {
 
 }
 
+/**
+ *  Any event emitted by our contracts, tagged by its `standard`.
+ *  The `event` field of each variant is flattened,
+ *  so it adds both `event` and `data` fields.
+ */
+export enum Event {
+    /**
+     */
+    Nep171,
+
+    /**
+     */
+    Mintgate,
+
+}
+
+/**
+ *  Events defined by the Non-Fungible Token standard.
+ */
+export enum Nep171Event {
+    /**
+     */
+    NftMint,
+
+    /**
+     */
+    NftTransfer,
+
+    /**
+     */
+    NftBurn,
+
+}
+
+/**
+ *  Data of the `nft_mint` event.
+ */
+export type NftMintData = {
+    /**
+     *  The account receiving the minted tokens.
+     */
+    owner_id: AccountId;
+
+    /**
+     *  The tokens minted.
+     */
+    token_ids: TokenId[];
+
+    /**
+     *  Optional message.
+     */
+    memo: string|null;
+
+}
+
+/**
+ *  Data of the `nft_transfer` event.
+ */
+export type NftTransferData = {
+    /**
+     *  The approved account that made the transfer on behalf of the owner, if any.
+     */
+    authorized_id: AccountId|null;
+
+    /**
+     *  The owner before the transfer.
+     */
+    old_owner_id: AccountId;
+
+    /**
+     *  The owner after the transfer.
+     */
+    new_owner_id: AccountId;
+
+    /**
+     *  The tokens transferred.
+     */
+    token_ids: TokenId[];
+
+    /**
+     *  Optional message.
+     */
+    memo: string|null;
+
+}
+
+/**
+ *  Data of the `nft_burn` event.
+ */
+export type NftBurnData = {
+    /**
+     *  The approved account that burned the tokens on behalf of the owner, if any.
+     */
+    authorized_id: AccountId|null;
+
+    /**
+     *  The owner of the burned tokens.
+     */
+    owner_id: AccountId;
+
+    /**
+     *  The tokens burned.
+     */
+    token_ids: TokenId[];
+
+    /**
+     *  Optional message.
+     */
+    memo: string|null;
+
+}
+
+/**
+ *  Events specific to MintGate.
+ */
+export enum MintGateEvent {
+    /**
+     */
+    CollectibleCreate,
+
+    /**
+     */
+    CollectibleDelete,
+
+    /**
+     */
+    NftApprove,
+
+    /**
+     */
+    NftRevoke,
+
+    /**
+     */
+    RoleGrant,
+
+    /**
+     */
+    RoleRevoke,
+
+    /**
+     */
+    ConfigUpdate,
+
+    /**
+     */
+    Pause,
+
+    /**
+     */
+    Unpause,
+
+}
+
+/**
+ *  Data of the `collectible_create` event.
+ */
+export type CollectibleCreateData = {
+    /**
+     *  The creator of the collectible.
+     */
+    creator_id: AccountId;
+
+    /**
+     *  The identifier of the collectible.
+     */
+    gate_id: GateId;
+
+    /**
+     *  How many tokens can be minted out of the collectible.
+     */
+    supply: number;
+
+}
+
+/**
+ *  Data of the `collectible_delete` event.
+ */
+export type CollectibleDeleteData = {
+    /**
+     *  The creator of the collectible.
+     */
+    creator_id: AccountId;
+
+    /**
+     *  The identifier of the collectible.
+     */
+    gate_id: GateId;
+
+}
+
+/**
+ *  Data of the `nft_approve` event.
+ */
+export type NftApproveData = {
+    /**
+     *  The owner of the approved token.
+     */
+    owner_id: AccountId;
+
+    /**
+     *  The token approved.
+     */
+    token_id: TokenId;
+
+    /**
+     *  The account approved to transfer the token, usually a marketplace.
+     */
+    account_id: AccountId;
+
+    /**
+     *  The approval ID assigned to `account_id`.
+     */
+    approval_id: U64;
+
+    /**
+     *  Minimum price the token should be sold for.
+     */
+    min_price: U128;
+
+}
+
+/**
+ *  Data of the `nft_revoke` event.
+ */
+export type NftRevokeData = {
+    /**
+     *  The owner of the token.
+     */
+    owner_id: AccountId;
+
+    /**
+     *  The token whose approval was revoked.
+     */
+    token_id: TokenId;
+
+    /**
+     *  The account no longer approved to transfer the token.
+     */
+    account_id: AccountId;
+
+}
+
+/**
+ *  Data of both `role_grant` and `role_revoke` events.
+ */
+export type RoleData = {
+    /**
+     *  The account granted or revoked.
+     */
+    account_id: AccountId;
+
+    /**
+     *  The role granted or revoked.
+     */
+    role: Role;
+
+}
+
+/**
+ *  Data of both `pause` and `unpause` events.
+ */
+export type PauseData = {
+    /**
+     *  The account that paused or unpaused `feature`.
+     */
+    account_id: AccountId;
+
+    /**
+     *  The feature paused or unpaused.
+     */
+    feature: Pausable;
+
+}
+
 /**
  *  In our implementation of the standard,
  *  The `nft_approve` method must conform with the following:
@@ -385,6 +868,208 @@ export type MarketApproveMsg = {
 
 }
 
+/**
+ *  Represents the terms, set by the creator of a `Collectible`, to mint one of its tokens.
+ */
+export type Voucher = {
+    /**
+     *  The `Collectible` to mint the token from.
+     */
+    gate_id: ValidGateId;
+
+    /**
+     *  The `Collectible` to create when `gate_id` does not exist yet,
+     *  so creators need not create their collectibles before issuing vouchers.
+     *  It is ignored once the collectible exists.
+     */
+    collectible: VoucherCollectible|null;
+
+    /**
+     *  The only account allowed to redeem this voucher.
+     *  When `None`, any account can redeem it.
+     */
+    receiver_id: AccountId|null;
+
+    /**
+     *  The price to pay when redeeming this voucher.
+     *  It replaces the `mint_price` of the collectible.
+     */
+    price: U128;
+
+    /**
+     *  When this voucher expires, as a UNIX epoch datetime in milliseconds.
+     */
+    expires_at: Timestamp;
+
+    /**
+     *  Chosen by the creator, each nonce can be redeemed only once across all of its vouchers.
+     */
+    nonce: U64;
+
+}
+
+/**
+ *  Represents the `Collectible` created by the first voucher redeemed for its `GateId`.
+ *  It is created with no `mint_price`, `royalty_split` nor claim window.
+ */
+export type VoucherCollectible = {
+    /**
+     *  The creator of the collectible, whose voucher key signs the voucher.
+     */
+    creator_id: AccountId;
+
+    /**
+     *  The title of the collectible.
+     */
+    title: string;
+
+    /**
+     *  The description of the collectible.
+     */
+    description: string;
+
+    /**
+     *  How many tokens can be minted out of the collectible.
+     */
+    supply: number;
+
+    /**
+     *  The royalty paid to `creator_id` every time a token is resold.
+     */
+    royalty: Fraction;
+
+    /**
+     *  The media of the collectible, as in its `Metadata`.
+     */
+    media: string|null;
+
+    /**
+     *  The hash of `media`, as in its `Metadata`.
+     */
+    media_hash: string|null;
+
+    /**
+     *  The off-chain reference of the collectible, as in its `Metadata`.
+     */
+    reference: string|null;
+
+    /**
+     *  The hash of `reference`, as in its `Metadata`.
+     */
+    reference_hash: string|null;
+
+}
+
+/**
+ *  Represents a `Token` held by the NFT contract until its linkdrop is claimed.
+ */
+export type Linkdrop = {
+    /**
+     *  The token to be given to the claimer.
+     */
+    token_id: TokenId;
+
+    /**
+     *  The account that funded this linkdrop.
+     */
+    funder_id: AccountId;
+
+    /**
+     *  The balance given to the claimer along with the token.
+     */
+    amount: U128;
+
+}
+
+/**
+ *  Represents the raffle of a `Collectible`.
+ */
+export type Raffle = {
+    /**
+     *  Accounts can enter the raffle until this time, as a UNIX epoch datetime in milliseconds.
+     *  Its winners can be drawn afterwards.
+     */
+    ends_at: Timestamp;
+
+    /**
+     *  The deposit to attach when entering the raffle.
+     *  It is returned to losers, and to winners when they claim their token.
+     */
+    entry_deposit: U128;
+
+    /**
+     *  How many accounts have entered the raffle.
+     */
+    entries: number;
+
+    /**
+     *  How many winners are to be drawn, set when the draw starts.
+     */
+    winners: number|null;
+
+    /**
+     *  How many winners have been drawn so far.
+     */
+    drawn: number;
+
+}
+
+/**
+ *  Represents the entry of an account in a raffle.
+ */
+export enum RaffleEntry {
+    /**
+     *  The account has entered the raffle, but it has not been drawn as a winner.
+     */
+    Entered,
+
+    /**
+     *  The account has been drawn as a winner, so it can claim a token.
+     */
+    Won,
+
+}
+
+/**
+ *  The `Metadata` fields of a `Collectible` hidden until it is revealed,
+ *  *i.e.*, the ones set by `update_collectible_metadata`.
+ */
+export type HiddenMetadata = {
+    /**
+     */
+    title: string;
+
+    /**
+     */
+    description: string;
+
+    /**
+     */
+    media: string|null;
+
+    /**
+     */
+    media_hash: string|null;
+
+    /**
+     */
+    reference: string|null;
+
+    /**
+     */
+    reference_hash: string|null;
+
+    /**
+     */
+    extra: string|null;
+
+}
+
+/**
+ *  Represents the version of a contract state layout.
+ */
+export type StateVersion = number;
+
 /**
  *  In marketplace contract, each token must be addressed by `<nft contract id, token id>`.
  */
@@ -455,6 +1140,36 @@ export enum Panics {
      */
     NotEnoughDepositToBuyToken,
 
+    /**
+     *  Thrown when `migrate` does not know how to migrate the stored state.
+     */
+    UnsupportedStateMigration,
+
+    /**
+     *  Thrown when `migrate` is called on a contract without state.
+     */
+    StateNotFound,
+
+    /**
+     *  Thrown when `migrate` needs an `owner_id` for a layout without owner.
+     */
+    MigrationOwnerMissing,
+
+    /**
+     *  Thrown when a privileged method is called by an account not holding the required role.
+     */
+    Unauthorized,
+
+    /**
+     *  Thrown when trying to grant or revoke the `Owner` role.
+     */
+    OwnerRoleNotGrantable,
+
+    /**
+     *  Thrown when calling a method whose feature is paused.
+     */
+    Paused,
+
 }
 
 /**
@@ -464,8 +1179,55 @@ export enum Panics {
 export interface MarketContract {
     /**
      *  Initializes the Market contract.
+     * 
+     *  - `owner_id` is the account owning this contract, it is allowed to perform every privileged operation.
+     */
+    init: { owner_id: ValidAccountId };
+
+    /**
+     *  Pauses `feature` in case of an emergency, until it is unpaused.
+     *  This contract checks the `purchases` feature, *i.e.*, `buy_token`.
+     *  View methods are never paused.
+     *  Only accounts holding the `Pauser` role can pause.
+     */
+    pause(args: { feature: Pausable }, gas?: any): Promise<void>;
+
+    /**
+     *  Unpauses `feature` previously paused with `pause`.
+     *  Only accounts holding the `Pauser` role can unpause.
+     */
+    unpause(args: { feature: Pausable }, gas?: any): Promise<void>;
+
+    /**
+     *  Returns the features currently paused.
+     */
+    get_paused(): Promise<Pausable[]>;
+
+    /**
+     *  Grants `role` to `account_id`.
+     *  Only the owner can grant the `Admin` role,
+     *  whereas any `Admin` can grant the remaining roles.
+     *  This contract only checks the `Pauser` role.
+     */
+    grant_role(args: { account_id: ValidAccountId, role: Role }, gas?: any): Promise<void>;
+
+    /**
+     *  Revokes `role` from `account_id`.
+     *  The same rules of `grant_role` apply.
+     */
+    revoke_role(args: { account_id: ValidAccountId, role: Role }, gas?: any): Promise<void>;
+
+    /**
+     *  Transfers the ownership of this contract to `new_owner_id`.
+     *  Only the current owner can transfer the ownership.
+     */
+    transfer_ownership(args: { new_owner_id: ValidAccountId }, gas?: any): Promise<void>;
+
+    /**
+     *  Returns the accounts explicitly holding `role`.
+     *  Note that the owner holds every role, but it is listed only for the `Owner` role.
      */
-    init: {  };
+    get_role_holders(args: { role: Role }): Promise<AccountId[]>;
 
     /**
      *  Returns all available tokens for sale.
@@ -496,6 +1258,12 @@ export interface MarketContract {
      * 
      *  When the token is sold,
      *  royalties are paid by this marketplace according to `nft_contract_id::nft_transfer_payout`.
+     *  At most `MAX_LEN_PAYOUT` accounts are paid out, as defined by NEP-199.
+     *  If the NFT contract refuses the transfer,
+     *  *e.g.*, the token is no longer transferable or has more than `MAX_LEN_PAYOUT` payees,
+     *  the deposit is refunded to the buyer.
+     *  The token is put back for sale only when this marketplace is still approved to transfer it,
+     *  see `nft_is_approved`, otherwise its listing is dropped.
      */
     buy_token(args: { nft_contract_id: ValidAccountId, token_id: TokenId }, gas?: any, amount?: any): Promise<void>;
 
@@ -528,12 +1296,19 @@ export interface MarketContract extends NonFungibleTokenApprovalsReceiver {}
 
 export const MarketContractMethods = {
     viewMethods: [
+        "get_paused",
+        "get_role_holders",
         "get_tokens_for_sale",
         "get_tokens_by_owner_id",
         "get_tokens_by_gate_id",
         "get_tokens_by_creator_id",
     ],
     changeMethods: [
+        "pause",
+        "unpause",
+        "grant_role",
+        "revoke_role",
+        "transfer_ownership",
         "buy_token",
         "nft_on_approve",
         "nft_on_revoke",
@@ -7,6 +7,9 @@ export type U128 = string;
 export type I128 = string;
 export type AccountId = string;
 export type ValidAccountId = string;
+export type Base58CryptoHash = string;
+export type Base58PublicKey = string;
+export type Base64VecU8 = string;
 
 /**
  *  The error variants thrown by *mg-core*.
@@ -117,10 +120,16 @@ export type TokenId = U64;
 export type Timestamp = number;
 
 /**
- *  Mapping from `AccountId`s to balance (in NEARs).
- *  The balance indicates the amount a Marketplace contract should pay when a Token is being sold.
+ *  Payout of a token sale, as defined by NEP-199.
  */
-export type Payout = Record<AccountId, U128>;
+export type Payout = {
+    /**
+     *  Mapping from `AccountId`s to balance (in NEARs).
+     *  The balance indicates the amount a Marketplace contract should pay when a Token is being sold.
+     */
+    payout: Record<AccountId, U128>;
+
+}
 
 /**
  *  A `Collectible` represents something of value.
@@ -143,6 +152,12 @@ export type Collectible = {
      */
     current_supply: number;
 
+    /**
+     *  Indicates how many `Token`s this `Collectible` was created with.
+     *  Unlike `current_supply`, it remains unchanged when tokens are minted.
+     */
+    supply: number;
+
     /**
      *  The list of `TokenId`s actually minted out of this `Collectible`.
      */
@@ -154,11 +169,120 @@ export type Collectible = {
      */
     royalty: Fraction;
 
+    /**
+     *  Royalties paid to the collaborators of `creator_id`, on top of `royalty`,
+     *  every time a minted token out of this `Collectible` is reselled.
+     */
+    royalty_split: RoyaltyShare[];
+
+    /**
+     *  Indicates the price (in NEARs) to be paid when claiming a `Token` out of this `Collectible`.
+     *  A `mint_price` of `0` means tokens can be claimed for free.
+     */
+    mint_price: U128;
+
+    /**
+     *  The root of the Merkle tree of accounts allowed to claim `Token`s out of this `Collectible`.
+     *  When present, tokens can only be claimed with a proof, see `merkle`.
+     */
+    allowlist_root: Base58CryptoHash|null;
+
+    /**
+     *  Indicates how many `Token`s each account can claim out of this `Collectible`.
+     *  Tokens transferred or burned still count towards this limit.
+     *  A `max_claims_per_account` of `null` means there is no limit.
+     */
+    max_claims_per_account: number|null;
+
+    /**
+     *  Indicates who can transfer the `Token`s minted out of this `Collectible`.
+     */
+    transferability: Transferability;
+
     /**
      *  Additional info provided by NEP-177.
      */
     metadata: Metadata;
 
+    /**
+     *  Indicates whether `metadata` can no longer be updated.
+     */
+    frozen: boolean;
+
+    /**
+     *  The commitment of the real metadata of this `Collectible`, until it is revealed.
+     *  While present, `metadata` is just a placeholder, see `reveal`.
+     */
+    metadata_commitment: Base58CryptoHash|null;
+
+    /**
+     *  Indicates when `Token`s can no longer be claimed out of this `Collectible`,
+     *  as UNIX epoch datetime in miliseconds, while claims start at `metadata.starts_at`.
+     *  Unlike `metadata.expires_at`, it does not affect the access granted by claimed tokens.
+     */
+    claims_end_at: Timestamp|null;
+
+}
+
+/**
+ *  Royalty paid to a collaborator of a `Collectible`.
+ */
+export type RoyaltyShare = {
+    /**
+     *  The collaborator to be paid.
+     */
+    account_id: AccountId;
+
+    /**
+     *  The royalty as percentage (in NEARs) of the sale price paid to `account_id`.
+     */
+    share: Fraction;
+
+}
+
+/**
+ *  Indicates who can transfer the `Token`s of a `Collectible`.
+ *  Only `Transferable` tokens can be approved for markets.
+ */
+export enum Transferability {
+    /**
+     *  Tokens can be transferred by their owners and approved accounts.
+     */
+    Transferable,
+
+    /**
+     *  Tokens cannot be transferred at all, *i.e.*, they are soulbound to their owners.
+     */
+    NonTransferable,
+
+    /**
+     *  Tokens can only be transferred to or from the `creator_id` of the `Collectible`,
+     *  *e.g.*, the creator hands out tokens and owners give them back.
+     */
+    CreatorOnly,
+
+}
+
+/**
+ *  Indicates whether `Token`s can be claimed out of a `Collectible` at a given time,
+ *  according to the `starts_at` and `expires_at` of its `metadata` and its `claims_end_at`.
+ */
+export enum ClaimStatus {
+    /**
+     *  Claims have not started yet.
+     */
+    Upcoming,
+
+    /**
+     *  Tokens can be claimed.
+     */
+    Live,
+
+    /**
+     *  Claims have already ended.
+     */
+    Ended,
+
 }
 
 /**
@@ -177,6 +301,12 @@ export type Token = {
      */
     gate_id: GateId;
 
+    /**
+     *  The edition number of this `Token` within its `Collectible`, starting from `1`.
+     *  It is assigned when minted, following the order in which tokens are claimed.
+     */
+    edition: number;
+
     /**
      *  The owner of this token.
      */
@@ -207,7 +337,8 @@ export type Token = {
 
     /**
      *  Additional info defined by NEP-177.
-     *  This `metadata` effectively joins fields from its respective `gate_id`.
+     *  This `metadata` effectively joins fields from its respective `gate_id`,
+     *  with the `edition` appended to the `title`, *e.g.*, `My collectible #7`.
      */
     metadata: Metadata;
 
@@ -301,6 +432,83 @@ export type TokenApproval = {
 
 }
 
+/**
+ *  Roles an account can hold to perform privileged operations in a contract.
+ *  The `Owner` implicitly holds every role,
+ *  while an `Admin` also acts as `CollectibleMinter` and `Pauser`.
+ */
+export enum Role {
+    /**
+     *  The single account that owns the contract.
+     */
+    Owner,
+
+    /**
+     *  Manages the contract on behalf of the owner, *e.g.*, grants other roles.
+     */
+    Admin,
+
+    /**
+     *  Allowed to create collectibles.
+     */
+    CollectibleMinter,
+
+    /**
+     *  Allowed to pause and unpause the contract.
+     */
+    Pauser,
+
+}
+
+/**
+ *  Features that can be paused in case of an emergency.
+ *  Each contract only checks the features it implements.
+ */
+export enum Pausable {
+    /**
+     *  Claiming tokens in the NFT contract.
+     */
+    Minting,
+
+    /**
+     *  Transferring and approving tokens in the NFT contract.
+     */
+    Transfers,
+
+    /**
+     *  Buying tokens in the Marketplace contract.
+     */
+    Purchases,
+
+}
+
+/**
+ *  Parameters of the NFT contract that rule royalties and fees.
+ *  They are set when the contract is initialized, and can be later updated by an admin.
+ */
+export type NftConfig = {
+    /**
+     *  Minimum `royalty` allowed when creating a `Collectible`.
+     */
+    min_royalty: Fraction;
+
+    /**
+     *  Maximum `royalty` allowed when creating a `Collectible`.
+     */
+    max_royalty: Fraction;
+
+    /**
+     *  Percentage fee paid to `mintgate_fee_account_id` when a `Token` is sold.
+     */
+    mintgate_fee: Fraction;
+
+    /**
+     *  Account receiving the `mintgate_fee`.
+     */
+    mintgate_fee_account_id: AccountId;
+
+}
+
 /**
  *  Associated metadata for the NFT contract as defined by NEP-177
  * 
@@ -346,246 +554,1385 @@ export type NFTContractMetadata = {
 }
 
 /**
- *  In our implementation of the standard,
- *  The `nft_approve` method must conform with the following:
- *  - The `msg` argument must contain a value, *i.e.*, cannot be `None`.
- *  - The value of `msg` must be a valid JSON,
- *    that deserializes to this struct.
+ *  Any event emitted by our contracts, tagged by its `standard`.
+ *  The `event` field of each variant is flattened,
+ *  so it adds both `event` and `data` fields.
  */
-export type NftApproveMsg = {
+export enum Event {
     /**
-     *  Indicates the minimum price (in NEARs) requested by owner to pay for the token.
      */
-    min_price: U128;
+    Nep171,
+
+    /**
+     */
+    Mintgate,
 
 }
 
 /**
- *  Represents the payload that arrives to the Marketplace contract,
- *  from our NFT implementation.
- *  It contains the `min_price` of the token.
- *  Additionally it is augmented with `gate_id` and `creator_id`
- *  so the Marketplace can lookup by this fields.
+ *  Events defined by the Non-Fungible Token standard.
  */
-export type MarketApproveMsg = {
+export enum Nep171Event {
     /**
-     *  Indicates the minimum price (in NEARs) that an account must pay to buy a token.
      */
-    min_price: U128;
+    NftMint,
 
     /**
-     *  Represents the `gate_id` of the token being approved if present.
      */
-    gate_id: ValidGateId|null;
+    NftTransfer,
 
     /**
-     *  Represents the `creator_id` of the collectible of the token being approved if present.
      */
-    creator_id: AccountId|null;
+    NftBurn,
 
 }
 
 /**
- *  The error variants thrown by *mg-nft*.
+ *  Data of the `nft_mint` event.
  */
-export enum Panic {
+export type NftMintData = {
     /**
+     *  The account receiving the minted tokens.
      */
-    MaxRoyaltyLessThanMinRoyalty,
+    owner_id: AccountId;
 
     /**
+     *  The tokens minted.
      */
-    RoyaltyMinThanAllowed,
+    token_ids: TokenId[];
 
     /**
+     *  Optional message.
      */
-    RoyaltyMaxThanAllowed,
+    memo: string|null;
 
-    /**
-     */
-    RoyaltyTooLarge,
+}
 
+/**
+ *  Data of the `nft_transfer` event.
+ */
+export type NftTransferData = {
     /**
+     *  The approved account that made the transfer on behalf of the owner, if any.
      */
-    GateIdAlreadyExists,
+    authorized_id: AccountId|null;
 
     /**
+     *  The owner before the transfer.
      */
-    ZeroSupplyNotAllowed,
+    old_owner_id: AccountId;
 
     /**
+     *  The owner after the transfer.
      */
-    InvalidArgument,
+    new_owner_id: AccountId;
 
     /**
+     *  The tokens transferred.
      */
-    AdminRestrictedOperation,
+    token_ids: TokenId[];
 
     /**
+     *  Optional message.
      */
-    GateIdNotFound,
+    memo: string|null;
 
-    /**
-     */
-    GateIdExhausted,
+}
 
+/**
+ *  Data of the `nft_burn` event.
+ */
+export type NftBurnData = {
     /**
+     *  The approved account that burned the tokens on behalf of the owner, if any.
      */
-    GateIdHasTokens,
+    authorized_id: AccountId|null;
 
     /**
+     *  The owner of the burned tokens.
      */
-    NotAuthorized,
+    owner_id: AccountId;
 
     /**
+     *  The tokens burned.
      */
-    TokenIdNotFound,
+    token_ids: TokenId[];
 
     /**
+     *  Optional message.
      */
-    TokenIdNotOwnedBy,
+    memo: string|null;
+
+}
 
+/**
+ *  Events specific to MintGate.
+ */
+export enum MintGateEvent {
     /**
      */
-    OneApprovalAllowed,
+    CollectibleCreate,
 
     /**
      */
-    SenderNotAuthToTransfer,
+    CollectibleDelete,
 
     /**
      */
-    ReceiverIsOwner,
+    NftApprove,
 
     /**
      */
-    EnforceApprovalFailed,
+    NftRevoke,
 
     /**
      */
-    MsgFormatNotRecognized,
+    RoleGrant,
 
     /**
      */
-    MsgFormatMinPriceMissing,
+    RoleRevoke,
 
     /**
      */
-    RevokeApprovalFailed,
+    ConfigUpdate,
 
     /**
      */
-    ExceedTokensToBatchApprove,
+    Pause,
 
     /**
      */
-    Errors,
+    Unpause,
 
 }
 
 /**
- *  Represents a list of errors when performing a batch update,
- *  identified by `TokenId`.
- */
-export type Panics = [TokenId, Panic][];
-
-/**
- *  Methods for the NFT contract.
- *  Methods belonging to a NEP Standard are implemented in their own interfaces.
+ *  Data of the `collectible_create` event.
  */
-export interface NftContract {
+export type CollectibleCreateData = {
     /**
-     *  Initializes the contract.
-     *  This contract methods needs to be explicitely called
-     *  since the default construction of the contract will panic.
-     * 
-     *  - `admin_id` is the valid account that is allowed to perform certain operations.
-     *  - `metadata` represents the general information of the contract.
-     *  - `min_royalty` and `max_royalty` indicates what must be the max and min royalty respectively when creating a collectible.
-     *  - `mintgate_fee` is the percetange to be paid to `mintgate_fee_account_id` for each sale.
+     *  The creator of the collectible.
      */
-    init: { admin_id: ValidAccountId, metadata: NFTContractMetadata, min_royalty: Fraction, max_royalty: Fraction, mintgate_fee: Fraction, mintgate_fee_account_id: ValidAccountId };
+    creator_id: AccountId;
 
     /**
-     *  Creates a new `Collectible`, identified by `gate_id`.
-     *  The `supply` indicates maximum supply for this collectible.
-     *  The `royalty` indicates the royalty (as percentage) paid to the creator (`predecessor_account_id`).
-     *  This royalty is paid when any `Token` is being resold in any marketplace.
-     * 
-     *  The sum of `royalty` and `mintgate_fee` should be less than `1`.
-     *  Panics otherwise.
-     *  This is to be able to make payouts all participants.
-     * 
-     *  See <https://github.com/epam/mintgate/issues/3>.
+     *  The identifier of the collectible.
      */
-    create_collectible(args: { creator_id: ValidAccountId, gate_id: ValidGateId, title: string, description: string, supply: number, royalty: Fraction, media: string|null, media_hash: string|null, reference: string|null, reference_hash: string|null }, gas?: any): Promise<void>;
+    gate_id: GateId;
 
     /**
-     *  Returns the `Collectible` with the given `gate_id`.
-     *  Panics otherwise.
-     * 
-     *  See <https://github.com/epam/mintgate/issues/16>.
+     *  How many tokens can be minted out of the collectible.
      */
-    get_collectible_by_gate_id(args: { gate_id: ValidGateId }): Promise<Collectible|null>;
+    supply: number;
+
+}
 
+/**
+ *  Data of the `collectible_delete` event.
+ */
+export type CollectibleDeleteData = {
     /**
-     *  Returns all `Collectible`s created by `creator_id`.
-     * 
-     *  See <https://github.com/epam/mintgate/issues/15>.
+     *  The creator of the collectible.
      */
-    get_collectibles_by_creator(args: { creator_id: ValidAccountId }): Promise<Collectible[]>;
+    creator_id: AccountId;
 
     /**
-     *  Deletes the given `Collectible` by `gate_id`.
-     *  The collectible can only be deleted if there are no minted tokens.
-     *  Moreover, only the `creator_id` of the collectible or
-     *  the contract `admin_id` are allowed to delete the collectible.
+     *  The identifier of the collectible.
      */
-    delete_collectible(args: { gate_id: ValidGateId }, gas?: any): Promise<void>;
+    gate_id: GateId;
 
+}
+
+/**
+ *  Data of the `nft_approve` event.
+ */
+export type NftApproveData = {
     /**
-     *  Claims a `Token` for the `Collectible` indicated by `gate_id`.
-     *  The claim is on behalf the `predecessor_account_id`.
-     *  Returns a `TokenId` that represents this claim.
-     *  If the given `gate_id` has exhausted its supply, this call will panic.
-     * 
-     *  See <https://github.com/epam/mintgate/issues/6>.
+     *  The owner of the approved token.
      */
-    claim_token(args: { gate_id: ValidGateId }, gas?: any): Promise<TokenId>;
+    owner_id: AccountId;
 
     /**
-     *  Burns (deletes) the `Token` identifed by `token_id`.
-     *  Only the `owner_id` can burn the token.
-     * 
-     *  After succefully delete the token,
-     *  a cross-contract call  is made to `nft_on_revoke` for each approval
-     *  to delist from their marketplaces.
+     *  The token approved.
      */
-    burn_token(args: { token_id: TokenId }, gas?: any): Promise<void>;
+    token_id: TokenId;
 
     /**
-     *  Returns all `Token`s owned by `owner_id`.
+     *  The account approved to transfer the token, usually a marketplace.
      */
-    get_tokens_by_owner(args: { owner_id: ValidAccountId }): Promise<Token[]>;
+    account_id: AccountId;
 
     /**
-     *  Returns all tokens claimed by `owner_id` belonging to `gate_id`.
-     * 
-     *  See <https://github.com/epam/mintgate/issues/14>.
+     *  The approval ID assigned to `account_id`.
      */
-    get_tokens_by_owner_and_gate_id(args: { gate_id: ValidGateId, owner_id: ValidAccountId }): Promise<Token[]>;
+    approval_id: U64;
 
     /**
-     *  Approves a batch of tokens, similar to `nft_approve`.
-     *  Each approval contains the `TokenId` to approve and the minimum price to sell the token for.
-     *  `account_id` indicates the market account contract where list these tokens.
+     *  Minimum price the token should be sold for.
      */
-    batch_approve(args: { tokens: [TokenId, U128][], account_id: ValidAccountId }, gas?: any): Promise<void>;
+    min_price: U128;
 
 }
 
 /**
- *  Non-Fungible Token (NEP-171) v1.0.0
+ *  Data of the `nft_revoke` event.
+ */
+export type NftRevokeData = {
+    /**
+     *  The owner of the token.
+     */
+    owner_id: AccountId;
+
+    /**
+     *  The token whose approval was revoked.
+     */
+    token_id: TokenId;
+
+    /**
+     *  The account no longer approved to transfer the token.
+     */
+    account_id: AccountId;
+
+}
+
+/**
+ *  Data of both `role_grant` and `role_revoke` events.
+ */
+export type RoleData = {
+    /**
+     *  The account granted or revoked.
+     */
+    account_id: AccountId;
+
+    /**
+     *  The role granted or revoked.
+     */
+    role: Role;
+
+}
+
+/**
+ *  Data of both `pause` and `unpause` events.
+ */
+export type PauseData = {
+    /**
+     *  The account that paused or unpaused `feature`.
+     */
+    account_id: AccountId;
+
+    /**
+     *  The feature paused or unpaused.
+     */
+    feature: Pausable;
+
+}
+
+/**
+ *  In our implementation of the standard,
+ *  The `nft_approve` method must conform with the following:
+ *  - The `msg` argument must contain a value, *i.e.*, cannot be `None`.
+ *  - The value of `msg` must be a valid JSON,
+ *    that deserializes to this struct.
+ */
+export type NftApproveMsg = {
+    /**
+     *  Indicates the minimum price (in NEARs) requested by owner to pay for the token.
+     */
+    min_price: U128;
+
+}
+
+/**
+ *  Represents the payload that arrives to the Marketplace contract,
+ *  from our NFT implementation.
+ *  It contains the `min_price` of the token.
+ *  Additionally it is augmented with `gate_id` and `creator_id`
+ *  so the Marketplace can lookup by this fields.
+ */
+export type MarketApproveMsg = {
+    /**
+     *  Indicates the minimum price (in NEARs) that an account must pay to buy a token.
+     */
+    min_price: U128;
+
+    /**
+     *  Represents the `gate_id` of the token being approved if present.
+     */
+    gate_id: ValidGateId|null;
+
+    /**
+     *  Represents the `creator_id` of the collectible of the token being approved if present.
+     */
+    creator_id: AccountId|null;
+
+}
+
+/**
+ *  Represents the terms, set by the creator of a `Collectible`, to mint one of its tokens.
+ */
+export type Voucher = {
+    /**
+     *  The `Collectible` to mint the token from.
+     */
+    gate_id: ValidGateId;
+
+    /**
+     *  The `Collectible` to create when `gate_id` does not exist yet,
+     *  so creators need not create their collectibles before issuing vouchers.
+     *  It is ignored once the collectible exists.
+     */
+    collectible: VoucherCollectible|null;
+
+    /**
+     *  The only account allowed to redeem this voucher.
+     *  When `None`, any account can redeem it.
+     */
+    receiver_id: AccountId|null;
+
+    /**
+     *  The price to pay when redeeming this voucher.
+     *  It replaces the `mint_price` of the collectible.
+     */
+    price: U128;
+
+    /**
+     *  When this voucher expires, as a UNIX epoch datetime in milliseconds.
+     */
+    expires_at: Timestamp;
+
+    /**
+     *  Chosen by the creator, each nonce can be redeemed only once across all of its vouchers.
+     */
+    nonce: U64;
+
+}
+
+/**
+ *  Represents the `Collectible` created by the first voucher redeemed for its `GateId`.
+ *  It is created with no `mint_price`, `royalty_split` nor claim window.
+ */
+export type VoucherCollectible = {
+    /**
+     *  The creator of the collectible, whose voucher key signs the voucher.
+     */
+    creator_id: AccountId;
+
+    /**
+     *  The title of the collectible.
+     */
+    title: string;
+
+    /**
+     *  The description of the collectible.
+     */
+    description: string;
+
+    /**
+     *  How many tokens can be minted out of the collectible.
+     */
+    supply: number;
+
+    /**
+     *  The royalty paid to `creator_id` every time a token is resold.
+     */
+    royalty: Fraction;
+
+    /**
+     *  The media of the collectible, as in its `Metadata`.
+     */
+    media: string|null;
+
+    /**
+     *  The hash of `media`, as in its `Metadata`.
+     */
+    media_hash: string|null;
+
+    /**
+     *  The off-chain reference of the collectible, as in its `Metadata`.
+     */
+    reference: string|null;
+
+    /**
+     *  The hash of `reference`, as in its `Metadata`.
+     */
+    reference_hash: string|null;
+
+}
+
+/**
+ *  Represents a `Token` held by the NFT contract until its linkdrop is claimed.
+ */
+export type Linkdrop = {
+    /**
+     *  The token to be given to the claimer.
+     */
+    token_id: TokenId;
+
+    /**
+     *  The account that funded this linkdrop.
+     */
+    funder_id: AccountId;
+
+    /**
+     *  The balance given to the claimer along with the token.
+     */
+    amount: U128;
+
+}
+
+/**
+ *  Represents the raffle of a `Collectible`.
+ */
+export type Raffle = {
+    /**
+     *  Accounts can enter the raffle until this time, as a UNIX epoch datetime in milliseconds.
+     *  Its winners can be drawn afterwards.
+     */
+    ends_at: Timestamp;
+
+    /**
+     *  The deposit to attach when entering the raffle.
+     *  It is returned to losers, and to winners when they claim their token.
+     */
+    entry_deposit: U128;
+
+    /**
+     *  How many accounts have entered the raffle.
+     */
+    entries: number;
+
+    /**
+     *  How many winners are to be drawn, set when the draw starts.
+     */
+    winners: number|null;
+
+    /**
+     *  How many winners have been drawn so far.
+     */
+    drawn: number;
+
+}
+
+/**
+ *  Represents the entry of an account in a raffle.
+ */
+export enum RaffleEntry {
+    /**
+     *  The account has entered the raffle, but it has not been drawn as a winner.
+     */
+    Entered,
+
+    /**
+     *  The account has been drawn as a winner, so it can claim a token.
+     */
+    Won,
+
+}
+
+/**
+ *  The `Metadata` fields of a `Collectible` hidden until it is revealed,
+ *  *i.e.*, the ones set by `update_collectible_metadata`.
+ */
+export type HiddenMetadata = {
+    /**
+     */
+    title: string;
+
+    /**
+     */
+    description: string;
+
+    /**
+     */
+    media: string|null;
+
+    /**
+     */
+    media_hash: string|null;
+
+    /**
+     */
+    reference: string|null;
+
+    /**
+     */
+    reference_hash: string|null;
+
+    /**
+     */
+    extra: string|null;
+
+}
+
+/**
+ *  Represents the version of a contract state layout.
+ */
+export type StateVersion = number;
+
+/**
+ *  The error variants thrown by *mg-nft*.
+ */
+export enum Panic {
+    /**
+     */
+    MaxRoyaltyLessThanMinRoyalty,
+
+    /**
+     */
+    RoyaltyMinThanAllowed,
+
+    /**
+     */
+    RoyaltyMaxThanAllowed,
+
+    /**
+     */
+    RoyaltyTooLarge,
+
+    /**
+     */
+    RoyaltySplitMaxThanAllowed,
+
+    /**
+     */
+    RoyaltySplitTooLarge,
+
+    /**
+     */
+    MintgateFeeTooLarge,
+
+    /**
+     */
+    GateIdAlreadyExists,
+
+    /**
+     */
+    ZeroSupplyNotAllowed,
+
+    /**
+     */
+    InvalidArgument,
+
+    /**
+     */
+    Unauthorized,
+
+    /**
+     */
+    OwnerRoleNotGrantable,
+
+    /**
+     */
+    Paused,
+
+    /**
+     */
+    GateIdNotFound,
+
+    /**
+     */
+    CollectibleFrozen,
+
+    /**
+     */
+    MetadataAlreadyCommitted,
+
+    /**
+     */
+    MetadataNotCommitted,
+
+    /**
+     */
+    InvalidMetadataReveal,
+
+    /**
+     */
+    NotCollectibleCreator,
+
+    /**
+     */
+    AllowlistProofRequired,
+
+    /**
+     */
+    InvalidAllowlistProof,
+
+    /**
+     */
+    AllowlistProofAlreadyUsed,
+
+    /**
+     */
+    ClaimLimitReached,
+
+    /**
+     */
+    ClaimCodeRequired,
+
+    /**
+     */
+    InvalidClaimCode,
+
+    /**
+     */
+    RaffleAlreadyExists,
+
+    /**
+     */
+    RaffleNotFound,
+
+    /**
+     */
+    RaffleEnded,
+
+    /**
+     */
+    RaffleNotEnded,
+
+    /**
+     */
+    RaffleAlreadyEntered,
+
+    /**
+     */
+    RaffleEntryNotFound,
+
+    /**
+     */
+    RaffleAlreadyDrawn,
+
+    /**
+     */
+    RaffleNotDrawn,
+
+    /**
+     */
+    NotRaffleWinner,
+
+    /**
+     */
+    RaffleHasEntries,
+
+    /**
+     */
+    RaffleWinnersOnly,
+
+    /**
+     */
+    ClaimNotStarted,
+
+    /**
+     */
+    ClaimEnded,
+
+    /**
+     */
+    TokenNotTransferable,
+
+    /**
+     */
+    GateIdExhausted,
+
+    /**
+     */
+    NotEnoughSupply,
+
+    /**
+     */
+    MintToTooLong,
+
+    /**
+     */
+    InvalidVoucherKey,
+
+    /**
+     */
+    VoucherKeyNotFound,
+
+    /**
+     */
+    InvalidVoucherSignature,
+
+    /**
+     */
+    VoucherNotForAccount,
+
+    /**
+     */
+    VoucherExpired,
+
+    /**
+     */
+    VoucherAlreadyRedeemed,
+
+    /**
+     */
+    LinkdropAlreadyExists,
+
+    /**
+     */
+    LinkdropNotFound,
+
+    /**
+     */
+    NotLinkdropFunder,
+
+    /**
+     */
+    InvalidNewAccountId,
+
+    /**
+     */
+    GateIdHasTokens,
+
+    /**
+     */
+    TokenIdNotFound,
+
+    /**
+     */
+    TokenIdNotOwnedBy,
+
+    /**
+     */
+    SenderNotAuthToTransfer,
+
+    /**
+     */
+    ReceiverIsOwner,
+
+    /**
+     */
+    EnforceApprovalFailed,
+
+    /**
+     */
+    MsgFormatNotRecognized,
+
+    /**
+     */
+    MsgFormatMinPriceMissing,
+
+    /**
+     */
+    RevokeApprovalFailed,
+
+    /**
+     */
+    NotEnoughDepositForStorage,
+
+    /**
+     */
+    NotEnoughDepositForMintPrice,
+
+    /**
+     */
+    NotEnoughDepositForLinkdrop,
+
+    /**
+     */
+    NotEnoughDepositForRaffleEntry,
+
+    /**
+     */
+    NotEnoughGasForTransferCall,
+
+    /**
+     */
+    PayoutTooLong,
+
+    /**
+     */
+    UnsupportedStateMigration,
+
+    /**
+     */
+    StateNotFound,
+
+    /**
+     */
+    MigrationNotStarted,
+
+    /**
+     */
+    MigrationInProgress,
+
+    /**
+     */
+    ExceedTokensToBatchApprove,
+
+    /**
+     */
+    Errors,
+
+}
+
+/**
+ *  Represents a list of errors when performing a batch update,
+ *  identified by `TokenId`.
+ */
+export type Panics = [TokenId, Panic][];
+
+/**
+ *  Methods for the NFT contract.
+ *  Methods belonging to a NEP Standard are implemented in their own interfaces.
+ */
+export interface NftContract {
+    /**
+     *  Initializes the contract.
+     *  This contract methods needs to be explicitely called
+     *  since the default construction of the contract will panic.
+     *  It cannot be called while a migration is in progress, see `migrate`.
+     * 
+     *  - `owner_id` is the account owning this contract, it is allowed to perform every privileged operation.
+     *  - `metadata` represents the general information of the contract.
+     *  - `min_royalty` and `max_royalty` indicates what must be the max and min royalty respectively when creating a collectible.
+     *  - `mintgate_fee` is the percetange to be paid to `mintgate_fee_account_id` for each sale.
+     */
+    init: { owner_id: ValidAccountId, metadata: NFTContractMetadata, min_royalty: Fraction, max_royalty: Fraction, mintgate_fee: Fraction, mintgate_fee_account_id: ValidAccountId };
+
+    /**
+     *  Creates a new `Collectible`, identified by `gate_id`.
+     *  The `supply` indicates maximum supply for this collectible.
+     *  The `royalty` indicates the royalty (as percentage) paid to the creator (`predecessor_account_id`).
+     *  This royalty is paid when any `Token` is being resold in any marketplace.
+     * 
+     *  The sum of `royalty` and `mintgate_fee` should be less than `1`.
+     *  Panics otherwise.
+     *  This is to be able to make payouts all participants.
+     * 
+     *  The optional `royalty_split` indicates the royalties paid to collaborators,
+     *  in addition to the `royalty` paid to the creator.
+     *  It can have at most `MAX_ROYALTY_SPLIT_LEN` shares.
+     *  The sum of `royalty` and all shares should be less or equal to `max_royalty`,
+     *  and adding `mintgate_fee` to it should be less than `1`.
+     * 
+     *  The optional `starts_at` and `claims_end_at` indicate when tokens can be claimed,
+     *  as UNIX epoch datetimes in miliseconds.
+     *  When `starts_at` is not given, tokens can be claimed right away.
+     *  When `claims_end_at` is not given, tokens can be claimed until the supply is exhausted.
+     *  The optional `expires_at` indicates when claimed tokens no longer grant access,
+     *  see `has_access`, so tokens cannot be claimed after it either.
+     *  If given, `starts_at` must be before both `claims_end_at` and `expires_at`.
+     * 
+     *  Only accounts holding the `CollectibleMinter` role can create collectibles.
+     * 
+     *  The caller must attach enough deposit to cover the storage used by the new collectible.
+     *  Any excess of the attached deposit is refunded.
+     * 
+     *  See <https://github.com/epam/mintgate/issues/3>.
+     */
+    create_collectible(args: { creator_id: ValidAccountId, gate_id: ValidGateId, title: string, description: string, supply: number, royalty: Fraction, media: string|null, media_hash: string|null, reference: string|null, reference_hash: string|null, royalty_split: RoyaltyShare[]|null, starts_at: Timestamp|null, claims_end_at: Timestamp|null, expires_at: Timestamp|null }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Returns the `Collectible` with the given `gate_id`.
+     *  Panics otherwise.
+     * 
+     *  See <https://github.com/epam/mintgate/issues/16>.
+     */
+    get_collectible_by_gate_id(args: { gate_id: ValidGateId }): Promise<Collectible|null>;
+
+    /**
+     *  Returns whether tokens of the `Collectible` with the given `gate_id`
+     *  can be claimed now (`live`), only later (`upcoming`), or not anymore (`ended`).
+     *  Note that a `live` collectible may have already exhausted its supply.
+     */
+    get_claim_status(args: { gate_id: ValidGateId }): Promise<ClaimStatus>;
+
+    /**
+     *  Returns all `Collectible`s created by `creator_id`.
+     * 
+     *  See <https://github.com/epam/mintgate/issues/15>.
+     */
+    get_collectibles_by_creator(args: { creator_id: ValidAccountId }): Promise<Collectible[]>;
+
+    /**
+     *  Deletes the given `Collectible` by `gate_id`.
+     *  The collectible can only be deleted if there are no minted tokens.
+     *  Moreover, only the `creator_id` of the collectible or
+     *  an account holding the `Admin` role are allowed to delete the collectible.
+     */
+    delete_collectible(args: { gate_id: ValidGateId }, gas?: any): Promise<void>;
+
+    /**
+     *  Updates the metadata of the `Collectible` identified by `gate_id`.
+     *  The given fields replace the current ones, and `updated_at` is set to the current time.
+     *  The same length restrictions of `create_collectible` apply.
+     * 
+     *  Only the `creator_id` of the collectible or
+     *  an account holding the `Admin` role are allowed to update the collectible.
+     *  Moreover, the collectible must not be frozen.
+     * 
+     *  The caller must attach enough deposit to cover any additional storage used.
+     *  Any excess of the attached deposit is refunded.
+     */
+    update_collectible_metadata(args: { gate_id: ValidGateId, title: string, description: string, media: string|null, media_hash: string|null, reference: string|null, reference_hash: string|null, extra: string|null }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Freezes the metadata of the `Collectible` identified by `gate_id`,
+     *  so it cannot be updated anymore.
+     *  Note that a frozen collectible cannot be unfrozen.
+     * 
+     *  The same permissions of `update_collectible_metadata` apply.
+     */
+    freeze_collectible_metadata(args: { gate_id: ValidGateId }, gas?: any): Promise<void>;
+
+    /**
+     *  Hides the real metadata of the `Collectible` identified by `gate_id` until it is revealed,
+     *  committing to it with the given `metadata_commitment`, see `mg_core::reveal`.
+     *  Meanwhile, its current `metadata` is just a placeholder,
+     *  and so it is for its tokens.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to commit its metadata,
+     *  and only once, before any of its tokens is minted.
+     *  Its metadata must not be frozen yet, but it can be frozen afterwards,
+     *  since the revealed metadata is bound to the commitment anyway.
+     * 
+     *  The caller must attach enough deposit to cover the storage used by the commitment.
+     *  Any excess of the attached deposit is refunded.
+     */
+    commit_metadata(args: { gate_id: ValidGateId, metadata_commitment: Base58CryptoHash }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Reveals the real `metadata` of the `Collectible` identified by `gate_id`,
+     *  replacing its placeholder as `update_collectible_metadata` does.
+     *  The given `metadata` and `salt` must be the preimage of its `metadata_commitment`,
+     *  which is removed afterwards.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to reveal it,
+     *  even if its metadata is frozen.
+     * 
+     *  The caller must attach enough deposit to cover any additional storage used.
+     *  Any excess of the attached deposit is refunded.
+     */
+    reveal(args: { gate_id: ValidGateId, metadata: HiddenMetadata, salt: string }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Sets the `mint_price` to be paid when claiming tokens of the `Collectible` identified by `gate_id`.
+     *  A `mint_price` of `0` allows to claim tokens for free.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to set its `mint_price`.
+     */
+    set_mint_price(args: { gate_id: ValidGateId, mint_price: U128 }, gas?: any): Promise<void>;
+
+    /**
+     *  Sets the `allowlist_root` of the `Collectible` identified by `gate_id`.
+     *  When set, tokens can only be claimed by the accounts in the allowlist using `claim_token_with_proof`.
+     *  An `allowlist_root` of `null` allows any account to claim tokens.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to set its `allowlist_root`.
+     * 
+     *  The caller must attach enough deposit to cover any additional storage used.
+     *  Any excess of the attached deposit is refunded.
+     */
+    set_allowlist_root(args: { gate_id: ValidGateId, allowlist_root: Base58CryptoHash|null }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Sets the `max_claims_per_account` of the `Collectible` identified by `gate_id`.
+     *  Tokens already claimed by an account count towards the new limit.
+     *  A `max_claims_per_account` of `null` removes the limit.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to set its `max_claims_per_account`.
+     * 
+     *  The caller must attach enough deposit to cover any additional storage used.
+     *  Any excess of the attached deposit is refunded.
+     */
+    set_max_claims_per_account(args: { gate_id: ValidGateId, max_claims_per_account: number|null }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Sets the `transferability` of the `Collectible` identified by `gate_id`.
+     *  It applies to tokens already claimed as well, but existing approvals are not revoked.
+     *  Markets refund their buyers when the transfer of such a token is refused.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to set its `transferability`.
+     * 
+     *  The caller must attach enough deposit to cover any additional storage used.
+     *  Any excess of the attached deposit is refunded.
+     */
+    set_transferability(args: { gate_id: ValidGateId, transferability: Transferability }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Adds the sha256 `code_hashes` of one-time claim codes to the `Collectible` identified by `gate_id`.
+     *  Once a collectible has claim codes, tokens can only be claimed with `claim_with_code`,
+     *  even after all of its codes have been used.
+     *  Hashes already added are ignored.
+     *  See `mg_core::claim_code` to generate codes along with their hashes.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to add claim codes.
+     * 
+     *  The caller must attach enough deposit to cover the storage used by the new codes.
+     *  Any excess of the attached deposit is refunded.
+     */
+    add_claim_codes(args: { gate_id: ValidGateId, code_hashes: Base58CryptoHash[] }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Returns how many claim codes of the `Collectible` identified by `gate_id` have not been used yet.
+     */
+    get_claim_codes_left(args: { gate_id: ValidGateId }): Promise<number>;
+
+    /**
+     *  Returns whether the claim code with the given `code_hash` can still be used
+     *  to claim a `Token` of the `Collectible` identified by `gate_id`.
+     */
+    is_claim_code_available(args: { gate_id: ValidGateId, code_hash: Base58CryptoHash }): Promise<boolean>;
+
+    /**
+     *  Returns how many `Token`s `account_id` has claimed out of the `Collectible` identified by `gate_id`,
+     *  including tokens already transferred or burned.
+     */
+    get_claims_by_account(args: { gate_id: ValidGateId, account_id: ValidAccountId }): Promise<number>;
+
+    /**
+     *  Claims a `Token` for the `Collectible` indicated by `gate_id`.
+     *  The claim is on behalf the `predecessor_account_id`.
+     *  Returns a `TokenId` that represents this claim.
+     *  The new `Token` gets the next `edition` number of `gate_id`, starting from `1`.
+     *  If the given `gate_id` has exhausted its supply, this call will panic.
+     *  The same applies when claims of `gate_id` have not started yet or have already ended.
+     *  If the given `gate_id` has an `allowlist_root`, use `claim_token_with_proof` instead.
+     *  If it has claim codes, use `claim_with_code` instead.
+     *  If it has a raffle, only its winners can claim, see `create_raffle`.
+     *  If the `predecessor_account_id` has already claimed `max_claims_per_account` tokens,
+     *  this call will panic as well.
+     * 
+     *  The caller must attach the `mint_price` of the collectible,
+     *  plus enough deposit to cover the storage used by the new token.
+     *  The `mint_price` is split between the `creator_id` and
+     *  the `mintgate_fee_account_id` according to `mintgate_fee`.
+     *  Any excess of the attached deposit is refunded.
+     * 
+     *  See <https://github.com/epam/mintgate/issues/6>.
+     */
+    claim_token(args: { gate_id: ValidGateId }, gas?: any, amount?: any): Promise<TokenId>;
+
+    /**
+     *  Claims a `Token` for the `Collectible` indicated by `gate_id`,
+     *  proving the `predecessor_account_id` is in its allowlist.
+     *  The `proof` is the list of sibling hashes from the account leaf up to the `allowlist_root`,
+     *  as built by `mg_core::merkle::MerkleTree`.
+     *  Each account in the allowlist can use its proof only once.
+     * 
+     *  Otherwise, the same rules of `claim_token` apply.
+     */
+    claim_token_with_proof(args: { gate_id: ValidGateId, proof: Base58CryptoHash[] }, gas?: any, amount?: any): Promise<TokenId>;
+
+    /**
+     *  Claims a `Token` for the `Collectible` indicated by `gate_id`,
+     *  using one of the claim codes added with `add_claim_codes`.
+     *  The `code` is used up, so it cannot be used again.
+     * 
+     *  Otherwise, the same rules of `claim_token` apply,
+     *  except that the `allowlist_root` of the collectible is not checked.
+     */
+    claim_with_code(args: { gate_id: ValidGateId, code: string }, gas?: any, amount?: any): Promise<TokenId>;
+
+    /**
+     *  Creates a raffle for the `Collectible` identified by `gate_id`.
+     *  Accounts can enter the raffle with `enter_raffle` until `ends_at`,
+     *  as a UNIX epoch datetime in milliseconds, attaching the refundable `entry_deposit`.
+     *  After that, its winners are drawn with `draw_raffle`.
+     *  Once a collectible has a raffle, only its winners can claim tokens,
+     *  and the rest of the rules of `claim_token` still apply.
+     *  Neither `mint_to`, `redeem_voucher` nor `add_linkdrop` can mint its tokens anymore,
+     *  so the supply drawn for the winners remains available to them.
+     * 
+     *  Only the `creator_id` of the collectible is allowed to create a raffle.
+     * 
+     *  The caller must attach enough deposit to cover the storage used by the raffle.
+     *  Any excess of the attached deposit is refunded.
+     */
+    create_raffle(args: { gate_id: ValidGateId, ends_at: Timestamp, entry_deposit: U128 }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Returns the raffle of the `Collectible` identified by `gate_id`, if any.
+     */
+    get_raffle(args: { gate_id: ValidGateId }): Promise<Raffle|null>;
+
+    /**
+     *  Returns the entry of `account_id` in the raffle of the `Collectible` identified by `gate_id`,
+     *  as long as its deposit has been neither claimed nor withdrawn.
+     */
+    get_raffle_entry(args: { gate_id: ValidGateId, account_id: ValidAccountId }): Promise<RaffleEntry|null>;
+
+    /**
+     *  Enters the `predecessor_account_id` into the raffle of the `Collectible` identified by `gate_id`.
+     *  Each account can enter a raffle only once, and before it ends.
+     * 
+     *  The caller must attach the `entry_deposit` of the raffle,
+     *  plus enough deposit to cover the storage used by the entry.
+     *  Any excess of the attached deposit is refunded.
+     */
+    enter_raffle(args: { gate_id: ValidGateId }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Draws the winners of the raffle of the `Collectible` identified by `gate_id`,
+     *  using the random seed of the current block.
+     *  As many winners as entries are drawn, up to the `current_supply` of the collectible
+     *  when the draw starts.
+     *  Anyone can draw the winners once the raffle has ended.
+     * 
+     *  Each winner takes `GAS_FOR_DRAW` from the prepaid gas,
+     *  so the draw continues with the next call if the gas runs out.
+     *  Returns how many winners are left to be drawn.
+     */
+    draw_raffle(args: { gate_id: ValidGateId }, gas?: any): Promise<number>;
+
+    /**
+     *  Withdraws the entry deposit of the `predecessor_account_id`
+     *  from the raffle of the `Collectible` identified by `gate_id`,
+     *  once all of its winners have been drawn.
+     *  Losers use it to get their deposit back.
+     *  Winners get their deposit back when claiming their token,
+     *  so a winner withdrawing its deposit gives up its token.
+     */
+    withdraw_raffle_entry(args: { gate_id: ValidGateId }, gas?: any): Promise<void>;
+
+    /**
+     *  Mints a `Token` of the `Collectible` identified by `gate_id` to each account in `receiver_ids`.
+     *  An account can be listed more than once to receive several tokens.
+     *  Returns the `TokenId`s minted, in the same order as `receiver_ids`.
+     * 
+     *  Only the `creator_id` of the collectible or an `Admin` can mint tokens this way.
+     *  Unlike `claim_token`, neither the `mint_price`, the `allowlist_root`,
+     *  the claim window nor `max_claims_per_account` apply,
+     *  and the minted tokens do not count as claims of their receivers.
+     *  Tokens cannot be minted this way once the collectible has a raffle.
+     * 
+     *  At most `MAX_MINT_TO_LEN` tokens can be minted in a single call,
+     *  or fewer when the prepaid gas does not cover `GAS_FOR_MINT` for each of them.
+     * 
+     *  The caller must attach enough deposit to cover the storage used by the new tokens.
+     *  Any excess of the attached deposit is refunded.
+     */
+    mint_to(args: { gate_id: ValidGateId, receiver_ids: ValidAccountId[] }, gas?: any, amount?: any): Promise<TokenId[]>;
+
+    /**
+     *  Sets the ed25519 `public_key` the `predecessor_account_id` signs its vouchers with,
+     *  so tokens of its collectibles can be minted with `redeem_voucher`.
+     *  Vouchers signed with a former key can no longer be redeemed.
+     *  A `public_key` of `null` removes the key.
+     * 
+     *  The caller must attach enough deposit to cover any additional storage used.
+     *  Any excess of the attached deposit is refunded.
+     */
+    set_voucher_key(args: { public_key: Base58PublicKey|null }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Returns the ed25519 key `account_id` signs its vouchers with, if any.
+     */
+    get_voucher_key(args: { account_id: ValidAccountId }): Promise<Base58PublicKey|null>;
+
+    /**
+     *  Returns whether a voucher of `creator_id` with the given `nonce` has already been redeemed.
+     */
+    is_voucher_redeemed(args: { creator_id: ValidAccountId, nonce: U64 }): Promise<boolean>;
+
+    /**
+     *  Mints a `Token` of the `Collectible` identified by `voucher.gate_id`
+     *  to the `predecessor_account_id`, redeeming a `voucher` issued off-chain by its `creator_id`.
+     *  The `signature` is made with the voucher key of the creator, see `set_voucher_key`,
+     *  over the Borsh serialization of this contract account ID followed by `voucher`,
+     *  as `mg_core::voucher::VoucherSigner` does.
+     * 
+     *  The voucher can only be redeemed by its `receiver_id`, if any, and before its `expires_at`.
+     *  Each `nonce` of a creator can be redeemed only once.
+     *  If the collectible does not exist yet, it is created out of the `collectible` of the voucher,
+     *  whose `creator_id` signs the voucher and must hold the `CollectibleMinter` role.
+     *  Unlike `claim_token`, neither the `mint_price`, the `allowlist_root`,
+     *  the claim window nor `max_claims_per_account` apply,
+     *  and the minted token does not count as a claim of its receiver.
+     *  Vouchers cannot be redeemed once the collectible has a raffle.
+     * 
+     *  The caller must attach the `price` of the voucher,
+     *  plus enough deposit to cover the storage used by the new token, and collectible if created.
+     *  The `price` is split as the `mint_price` is in `claim_token`,
+     *  and any excess of the attached deposit is refunded.
+     */
+    redeem_voucher(args: { voucher: Voucher, signature: Base64VecU8 }, gas?: any, amount?: any): Promise<TokenId>;
+
+    /**
+     *  Funds a linkdrop of a new `Token` of the `Collectible` identified by `gate_id`.
+     *  The token is minted right away, and this contract holds it until the linkdrop is claimed.
+     * 
+     *  The given `public_key` is added as a function-call access key of this contract,
+     *  only allowed to call `claim_linkdrop` and `create_account_and_claim_linkdrop`.
+     *  The secret key of `public_key` is then shared with the claimer, *e.g.*, as a link,
+     *  so accounts without any NEAR can claim the token.
+     *  The linkdrop can only be claimed once its access key has been added.
+     *  If it cannot be added, *e.g.*, because this contract already holds `public_key`,
+     *  the token and the attached deposit are given back to the funder.
+     *  The returned promise resolves to the `TokenId` of the new token,
+     *  or `null` when the access key could not be added.
+     * 
+     *  Only the `creator_id` of the collectible or an `Admin` can fund linkdrops.
+     *  Like `mint_to`, neither the `mint_price`, the `allowlist_root`,
+     *  the claim window nor `max_claims_per_account` apply,
+     *  and linkdrops cannot be funded once the collectible has a raffle.
+     * 
+     *  The caller must attach enough deposit to cover the storage used by the new token and linkdrop,
+     *  plus `LINKDROP_ALLOWANCE` to pay for the gas of the claim.
+     *  The rest of the attached deposit is given to the claimer along with the token.
+     */
+    add_linkdrop(args: { gate_id: ValidGateId, public_key: Base58PublicKey }, gas?: any, amount?: any): Promise<void>;
+
+    /**
+     *  Returns the linkdrop funded for `public_key`, if its access key has been added
+     *  and it has not been claimed yet.
+     */
+    get_linkdrop(args: { public_key: Base58PublicKey }): Promise<Linkdrop|null>;
+
+    /**
+     *  Claims the linkdrop of the signer public key into the existing `account_id`,
+     *  giving it both the token and the linkdrop `amount`.
+     *  The access key of the linkdrop is deleted, so it cannot be claimed again.
+     * 
+     *  It must be signed with the access key of the linkdrop.
+     */
+    claim_linkdrop(args: { account_id: ValidAccountId }, gas?: any): Promise<TokenId>;
+
+    /**
+     *  Claims the linkdrop of the signer public key into `new_account_id`,
+     *  a new account with `new_public_key` as its full access key.
+     *  The new account is funded with the linkdrop `amount`, which must cover its storage.
+     * 
+     *  The `new_account_id` must be either a sub-account of this contract,
+     *  or a sub-account of a top-level account, *e.g.*, `testnet` or `near`,
+     *  which is then created by the linkdrop contract deployed there.
+     *  Once the account is created, it gets the token and the access key of the linkdrop is deleted.
+     *  If the account cannot be created, the linkdrop can be claimed again.
+     * 
+     *  It must be signed with the access key of the linkdrop.
+     */
+    create_account_and_claim_linkdrop(args: { new_account_id: ValidAccountId, new_public_key: Base58PublicKey }, gas?: any): Promise<void>;
+
+    /**
+     *  Removes the linkdrop funded for `public_key` before it is claimed,
+     *  giving back the token and the linkdrop `amount` to its `funder_id`.
+     *  The access key of the linkdrop is deleted as well.
+     * 
+     *  Only the `funder_id` of the linkdrop can remove it.
+     */
+    remove_linkdrop(args: { public_key: Base58PublicKey }, gas?: any): Promise<void>;
+
+    /**
+     *  Burns (deletes) the `Token` identifed by `token_id`.
+     *  Only the `owner_id` can burn the token.
+     * 
+     *  After succefully delete the token,
+     *  a cross-contract call  is made to `nft_on_revoke` for each approval
+     *  to delist from their marketplaces.
+     */
+    burn_token(args: { token_id: TokenId }, gas?: any): Promise<void>;
+
+    /**
+     *  Returns all `Token`s owned by `owner_id`.
+     */
+    get_tokens_by_owner(args: { owner_id: ValidAccountId }): Promise<Token[]>;
+
+    /**
+     *  Returns all tokens claimed by `owner_id` belonging to `gate_id`.
+     * 
+     *  See <https://github.com/epam/mintgate/issues/14>.
+     */
+    get_tokens_by_owner_and_gate_id(args: { gate_id: ValidGateId, owner_id: ValidAccountId }): Promise<Token[]>;
+
+    /**
+     *  Returns the `TokenId`s owned by `account_id` that grant access to the content gated by `gate_id`.
+     *  A token grants access as long as the `expires_at` of its collectible has not passed,
+     *  even after claims have ended.
+     *  An empty list means `account_id` has no access.
+     * 
+     *  Only the tokens owned by `account_id` are visited,
+     *  so it does not depend on the supply of `gate_id`.
+     */
+    has_access(args: { account_id: ValidAccountId, gate_id: ValidGateId }): Promise<TokenId[]>;
+
+    /**
+     *  Returns the current royalty and fee parameters of this contract.
+     */
+    get_config(): Promise<NftConfig>;
+
+    /**
+     *  Updates the `min_royalty` and `max_royalty` allowed when creating new collectibles.
+     *  Existing collectibles keep their `royalty`.
+     *  Only accounts holding the `Admin` role can update them.
+     * 
+     *  The same rules of `init` apply.
+     *  Moreover, the sum of `max_royalty` and `mintgate_fee` should be less than `1`.
+     */
+    set_royalty_bounds(args: { min_royalty: Fraction, max_royalty: Fraction }, gas?: any): Promise<void>;
+
+    /**
+     *  Updates the `mintgate_fee` paid on each sale and the `mintgate_fee_account_id` receiving it.
+     *  Only accounts holding the `Admin` role can update them.
+     * 
+     *  The sum of `max_royalty` and `mintgate_fee` should be less than `1`.
+     *  Likewise, the sum of the total royalty of any existing collectible and `mintgate_fee`
+     *  should be less than `1`, even if it was created under a greater `max_royalty`.
+     *  This is to be able to make payouts all participants.
+     */
+    set_mintgate_fee(args: { mintgate_fee: Fraction, mintgate_fee_account_id: ValidAccountId }, gas?: any): Promise<void>;
+
+    /**
+     *  Grants `role` to `account_id`.
+     *  Only the owner can grant the `Admin` role,
+     *  whereas any `Admin` can grant the remaining roles.
+     */
+    grant_role(args: { account_id: ValidAccountId, role: Role }, gas?: any): Promise<void>;
+
+    /**
+     *  Revokes `role` from `account_id`.
+     *  The same rules of `grant_role` apply.
+     */
+    revoke_role(args: { account_id: ValidAccountId, role: Role }, gas?: any): Promise<void>;
+
+    /**
+     *  Transfers the ownership of this contract to `new_owner_id`.
+     *  Only the current owner can transfer the ownership.
+     */
+    transfer_ownership(args: { new_owner_id: ValidAccountId }, gas?: any): Promise<void>;
+
+    /**
+     *  Returns the accounts explicitly holding `role`.
+     *  Note that the owner holds every role, but it is listed only for the `Owner` role.
+     */
+    get_role_holders(args: { role: Role }): Promise<AccountId[]>;
+
+    /**
+     *  Pauses `feature` in case of an emergency, until it is unpaused.
+     *  This contract checks the `minting` and `transfers` features,
+     *  *i.e.*, claiming tokens, and transferring or approving tokens respectively.
+     *  View methods are never paused.
+     *  Revoking approvals is deliberately not paused with `transfers`,
+     *  so owners can still delist their tokens from marketplaces during an emergency.
+     *  Only accounts holding the `Pauser` role can pause.
+     */
+    pause(args: { feature: Pausable }, gas?: any): Promise<void>;
+
+    /**
+     *  Unpauses `feature` previously paused with `pause`.
+     *  Only accounts holding the `Pauser` role can unpause.
+     */
+    unpause(args: { feature: Pausable }, gas?: any): Promise<void>;
+
+    /**
+     *  Returns the features currently paused.
+     */
+    get_paused(): Promise<Pausable[]>;
+
+    /**
+     *  Approves a batch of tokens, similar to `nft_approve`.
+     *  Each approval contains the `TokenId` to approve and the minimum price to sell the token for.
+     *  `account_id` indicates the market account contract where list these tokens.
+     *  Only tokens of `transferable` collectibles can be approved.
+     */
+    batch_approve(args: { tokens: [TokenId, U128][], account_id: ValidAccountId }, gas?: any): Promise<void>;
+
+}
+
+/**
+ *  Non-Fungible Token (NEP-171) v1.0.0
  *  https://nomicon.io/Standards/NonFungibleToken/Core.html
  * 
  *  Payouts is part of an ongoing (yet not settled) NEP spec:
@@ -594,6 +1941,11 @@ export interface NftContract {
 export interface NonFungibleTokenCore {
     /**
      *  Transfer the token `token_id` to the `receiver_id` account.
+     *  All approvals are cleared, and the token is delisted from every approved market
+     *  other than the sender.
+     * 
+     *  The `transferability` of the token's collectible must allow the sender to transfer it.
+     *  Tokens of a `creator_only` collectible can only be transferred to or from its `creator_id`.
      * 
      *  See <https://github.com/epam/mintgate/issues/18>.
      */
@@ -614,20 +1966,32 @@ export interface NonFungibleTokenCore {
      * 
      *  for any `token_id` claimed from `gate_id`.
      * 
-     *  This is part of an ongoing (yet not settled) NEP spec:
-     *  <https://github.com/thor314/NEPs/blob/patch-5/specs/Standards/NonFungibleToken/payouts.md>
+     *  Each collaborator in the `royalty_split` of `gate_id` is paid its `share` as well.
+     *  All amounts are rounded down, and the remainder is paid to `token.owner_id`.
+     * 
+     *  Panics if the payout has more than `max_len_payout` accounts, when given.
+     * 
+     *  See NEP-199: <https://github.com/near/NEPs/blob/master/neps/nep-0199.md>
      */
-    nft_payout(args: { token_id: TokenId, balance: U128 }): Promise<Payout>;
+    nft_payout(args: { token_id: TokenId, balance: U128, max_len_payout: number|null }): Promise<Payout>;
 
     /**
      *  Attempts to transfer the token.
      *  Afterwards returns the payout data.
      *  Effectively it is calling `nft_transfer` followed by `nft_payout`.
      * 
-     *  This is part of an ongoing (yet not settled) NEP spec:
-     *  <https://github.com/thor314/NEPs/blob/patch-5/specs/Standards/NonFungibleToken/payouts.md>
+     *  See NEP-199: <https://github.com/near/NEPs/blob/master/neps/nep-0199.md>
+     */
+    nft_transfer_payout(args: { receiver_id: ValidAccountId, token_id: TokenId, approval_id: U64|null, memo: string|null, balance: U128, max_len_payout: number|null }, gas?: any): Promise<Payout>;
+
+    /**
+     *  Transfers the token `token_id` to the `receiver_id` contract,
+     *  and then calls `nft_on_transfer` on it.
+     *  If `nft_on_transfer` returns `true` or fails,
+     *  the token is returned to its previous owner along with its approvals.
+     *  The returned promise resolves to whether the token was kept by `receiver_id`.
      */
-    nft_transfer_payout(args: { receiver_id: ValidAccountId, token_id: TokenId, approval_id: U64|null, memo: string|null, balance: U128|null }, gas?: any): Promise<Payout|null>;
+    nft_transfer_call(args: { receiver_id: ValidAccountId, token_id: TokenId, approval_id: U64|null, memo: string|null, msg: string }, gas?: any): Promise<void>;
 
     /**
      *  Returns the token identified by `token_id`.
@@ -663,16 +2027,26 @@ export interface NonFungibleTokenApprovalMgmt {
      *  The `msg` argument allows the caller to pass into additional information.
      *  A contract implementing the `nft_on_approve` methods must be
      *  deployed into `account_id`.
+     *  Only tokens of `transferable` collectibles can be approved.
      */
     nft_approve(args: { token_id: TokenId, account_id: ValidAccountId, msg: string|null }, gas?: any): Promise<void>;
 
+    /**
+     *  Returns whether `approved_account_id` is allowed to transfer `token_id`.
+     *  When `approval_id` is given, it must also match the approval ID of `approved_account_id`.
+     *  Tokens of collectibles that are no longer `Transferable` cannot be transferred by anyone.
+     */
+    nft_is_approved(args: { token_id: TokenId, approved_account_id: ValidAccountId, approval_id: U64|null }): Promise<boolean>;
+
     /**
      *  Revokes approval for `token_id` from `account_id`.
+     *  It can be called while `transfers` are paused, see `pause`.
      */
     nft_revoke(args: { token_id: TokenId, account_id: ValidAccountId }, gas?: any): Promise<void>;
 
     /**
      *  Revokes all approval for `token_id`.
+     *  Like `nft_revoke`, it is allowed while `transfers` are paused.
      */
     nft_revoke_all(args: { token_id: TokenId }, gas?: any): Promise<void>;
 
@@ -726,12 +2100,26 @@ export interface NftContract extends NonFungibleTokenCore, NonFungibleTokenMetad
 export const NftContractMethods = {
     viewMethods: [
         "get_collectible_by_gate_id",
+        "get_claim_status",
         "get_collectibles_by_creator",
+        "get_claim_codes_left",
+        "is_claim_code_available",
+        "get_claims_by_account",
+        "get_raffle",
+        "get_raffle_entry",
+        "get_voucher_key",
+        "is_voucher_redeemed",
+        "get_linkdrop",
         "get_tokens_by_owner",
         "get_tokens_by_owner_and_gate_id",
+        "has_access",
+        "get_config",
+        "get_role_holders",
+        "get_paused",
         "nft_payout",
         "nft_token",
         "nft_metadata",
+        "nft_is_approved",
         "nft_total_supply",
         "nft_tokens",
         "nft_supply_for_owner",
@@ -741,11 +2129,41 @@ export const NftContractMethods = {
     changeMethods: [
         "create_collectible",
         "delete_collectible",
+        "update_collectible_metadata",
+        "freeze_collectible_metadata",
+        "commit_metadata",
+        "reveal",
+        "set_mint_price",
+        "set_allowlist_root",
+        "set_max_claims_per_account",
+        "set_transferability",
+        "add_claim_codes",
         "claim_token",
+        "claim_token_with_proof",
+        "claim_with_code",
+        "create_raffle",
+        "enter_raffle",
+        "draw_raffle",
+        "withdraw_raffle_entry",
+        "mint_to",
+        "set_voucher_key",
+        "redeem_voucher",
+        "add_linkdrop",
+        "claim_linkdrop",
+        "create_account_and_claim_linkdrop",
+        "remove_linkdrop",
         "burn_token",
+        "set_royalty_bounds",
+        "set_mintgate_fee",
+        "grant_role",
+        "revoke_role",
+        "transfer_ownership",
+        "pause",
+        "unpause",
         "batch_approve",
         "nft_transfer",
         "nft_transfer_payout",
+        "nft_transfer_call",
         "nft_approve",
         "nft_revoke",
         "nft_revoke_all",
@@ -43,7 +43,7 @@ const callNftInit = async (
   mintgate_fee: Fraction
 ) => {
   const initiationArgs = {
-    admin_id: 'some_nonexistent_account_id',
+    owner_id: 'some_nonexistent_account_id',
     mintgate_fee_account_id: 'some_nonexistent_account_id_2',
     metadata: contractMetadata,
   };
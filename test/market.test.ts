@@ -3,7 +3,15 @@ import { utils } from 'near-api-js';
 
 import type { Account } from 'near-api-js';
 
-import { MAX_GAS_ALLOWED, createAddTestCollectible, generateGateId, getShare, formatNsToMs, logger } from './utils';
+import {
+  MAX_GAS_ALLOWED,
+  STORAGE_DEPOSIT,
+  createAddTestCollectible,
+  generateGateId,
+  getShare,
+  formatNsToMs,
+  logger,
+} from './utils';
 import { MINTGATE_FEE } from './initialData';
 import { Panics } from '../src/mg-market';
 
@@ -51,7 +59,7 @@ describe('Market contract', () => {
       await addTestCollectible(bob, { gate_id: gateId });
 
       for (let i = 0; i < numberOfTokensToAdd; i += 1) {
-        newTokensIds.push(await bob.contract.claim_token({ gate_id: gateId }));
+        newTokensIds.push(await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT));
       }
 
       await Promise.all(
@@ -86,7 +94,7 @@ describe('Market contract', () => {
       await addTestCollectible(alice, { gate_id: gateId });
 
       for (let i = 0; i < numberOfTokensToCreate; i += 1) {
-        newTokensIds.push(await bob.contract.claim_token({ gate_id: gateId }));
+        newTokensIds.push(await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT));
       }
 
       await Promise.all(
@@ -159,7 +167,7 @@ describe('Market contract', () => {
         royalty,
       });
 
-      tokenId = await alice.contract.claim_token({ gate_id: gateId });
+      tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
       await alice.contract.nft_approve(
         {
           token_id: tokenId,
@@ -345,7 +353,7 @@ describe('Market contract', () => {
           royalty,
         });
 
-        tokenId2 = await bob.contract.claim_token({ gate_id: gateId2 });
+        tokenId2 = await bob.contract.claim_token({ gate_id: gateId2 }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         await bob.contract.nft_approve(
           {
             token_id: tokenId2,
@@ -437,7 +445,7 @@ describe('Market contract', () => {
           royalty,
         });
 
-        const tokenId2 = await seller.contract.claim_token({ gate_id: gateId2 });
+        const tokenId2 = await seller.contract.claim_token({ gate_id: gateId2 }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await seller.contract.nft_approve(
           {
@@ -502,17 +510,23 @@ describe('Market contract', () => {
 
         const gateId2 = await generateGateId();
 
-        await admin.functionCall(bob.contractAccount.accountId, 'create_collectible', {
-          creator_id: creator.accountId,
-          gate_id: gateId2,
-          gate_url: 'Test gate url',
-          title: 'Test title',
-          description: 'Test description',
-          supply: 100,
-          royalty,
-        });
+        await admin.functionCall(
+          bob.contractAccount.accountId,
+          'create_collectible',
+          {
+            creator_id: creator.accountId,
+            gate_id: gateId2,
+            gate_url: 'Test gate url',
+            title: 'Test title',
+            description: 'Test description',
+            supply: 100,
+            royalty,
+          },
+          MAX_GAS_ALLOWED,
+          STORAGE_DEPOSIT
+        );
 
-        const tokenId2 = await seller.contract.claim_token({ gate_id: gateId2 });
+        const tokenId2 = await seller.contract.claim_token({ gate_id: gateId2 }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await seller.contract.nft_approve(
           {
@@ -588,9 +602,13 @@ describe('Market contract', () => {
           royalty,
         });
 
-        const executionOutcome = await seller.functionCall(bob.contractAccount.accountId, 'claim_token', {
-          gate_id: gateId2,
-        });
+        const executionOutcome = await seller.functionCall(
+          bob.contractAccount.accountId,
+          'claim_token',
+          { gate_id: gateId2 },
+          MAX_GAS_ALLOWED,
+          STORAGE_DEPOSIT
+        );
 
         if (
           !(typeof executionOutcome.status === 'object' && typeof executionOutcome.status.SuccessValue === 'string')
@@ -676,7 +694,7 @@ describe('Market contract', () => {
           royalty,
         });
 
-        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId2 });
+        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId2 }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         await alice.contract.nft_approve(
           {
             token_id: tokenId2,
@@ -753,7 +771,7 @@ describe('Market contract', () => {
       const sellerShare2 = +depositNearLarge - mintgateShare2 - creatorShare2;
 
       beforeAll(async () => {
-        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         await alice.contract.nft_approve(
           {
             token_id: tokenId2,
@@ -849,7 +867,7 @@ describe('Market contract', () => {
 
     describe('errors', () => {
       it('throws if buyer and seller are the same person', async () => {
-        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         const approveMessage: MarketApproveMsg = {
           min_price: '5',
           gate_id: '',
@@ -888,7 +906,7 @@ describe('Market contract', () => {
       });
 
       it('throws on buying not approved token', async () => {
-        const tokenId3 = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId3 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await expect(
           merchant2.contract.buy_token(
@@ -912,7 +930,7 @@ describe('Market contract', () => {
       });
 
       it('throws if not enough deposit provided', async () => {
-        const tokenId4 = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId4 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         const notEnoughDeposit = new BN(priceInternalNear!).sub(new BN(1));
 
         await alice.contract.nft_approve(
@@ -968,7 +986,7 @@ describe('Market contract', () => {
 
       await addTestCollectible(bob, { gate_id: gateId });
 
-      tokenId = await alice.contract.claim_token({ gate_id: gateId });
+      tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       await alice.contractAccount.functionCall(merchant.contract.contractId, 'nft_on_approve', {
         token_id: tokenId,
@@ -1033,7 +1051,7 @@ describe('Market contract', () => {
         gate_id: gateId,
       });
 
-      tokenId = await alice.contract.claim_token({ gate_id: gateId });
+      tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
       await alice.contract.nft_approve(
         {
           token_id: tokenId,
@@ -1076,7 +1094,7 @@ describe('Market contract', () => {
 
     describe('errors', () => {
       it('throws when revoking not approved token', async () => {
-        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await expect(
           alice.contractAccount.functionCall(merchant.contract.contractId, 'nft_on_revoke', { token_id: tokenId2 })
@@ -1118,7 +1136,9 @@ describe('Market contract', () => {
       await addTestCollectible(bob, { gate_id: gateId });
 
       tokensIds = await Promise.all(
-        Array.from({ length: numberOfTokensToAdd }, () => bob.contract.claim_token({ gate_id: gateId }))
+        Array.from({ length: numberOfTokensToAdd }, () =>
+          bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        )
       );
 
       await alice.contractAccount.functionCall(
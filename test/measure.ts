@@ -9,7 +9,7 @@ import type { Account } from 'near-api-js';
 
 import setup from './setup';
 import { createProfilers, getAccountFor, getContract, getUsers, getState } from './deploy';
-import { MAX_GAS_ALLOWED, addTestCollectible, generateGateId, logger } from './utils';
+import { MAX_GAS_ALLOWED, STORAGE_DEPOSIT, addTestCollectible, generateGateId, logger } from './utils';
 
 import { MarketContractMethods, NftContractMethods } from '../src';
 import { contractMetadata, MINTGATE_FEE, prefixes, royalty } from './initialData';
@@ -57,7 +57,7 @@ const measure = async () => {
   const nftUsers = await getUsers(prefixes.nft.users);
   const marketUsers = await getUsers(prefixes.market.users);
   const nftContractArguments: NftContract['init'] = {
-    admin_id: adminUser.accountId,
+    owner_id: adminUser.accountId,
     metadata: contractMetadata,
     mintgate_fee: MINTGATE_FEE,
     mintgate_fee_account_id: nftFeeUser.accountId,
@@ -78,7 +78,7 @@ const measure = async () => {
     'target/wasm32-unknown-unknown/release/mg_market.wasm',
     {
       func: 'init',
-      args: {},
+      args: { owner_id: adminUser.accountId },
     }
   );
 
@@ -139,7 +139,7 @@ const measure = async () => {
   collectibles = await alice.contract.get_collectibles_by_creator({ creator_id: alice.accountId });
   const gateId = collectibles[collectibles.length - 1].gate_id;
 
-  await bob.contract.claim_token({ gate_id: gateId });
+  await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
   for (let i = 0; i <= tokensToAdd - 1; i += tokensConcurrently) {
     const tokensToAddNow = i + tokensConcurrently > tokensToAdd - 1 ? tokensToAdd - 1 - i : tokensConcurrently;
@@ -149,7 +149,9 @@ const measure = async () => {
     }
 
     await Promise.all(
-      Array.from({ length: tokensToAddNow }, async () => bob.contract.claim_token({ gate_id: gateId }))
+      Array.from({ length: tokensToAddNow }, async () =>
+        bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+      )
     );
 
     data.push(
@@ -5,6 +5,7 @@ import type { Account } from 'near-api-js';
 
 import {
   MAX_GAS_ALLOWED,
+  STORAGE_DEPOSIT,
   createAddTestCollectible,
   generateGateId,
   isWithinLastMs,
@@ -429,8 +430,10 @@ describe('Nft contract', () => {
           expect.objectContaining({
             type: 'GuestPanic',
             panic_msg: JSON.stringify({
-              err: Panic[Panic.AdminRestrictedOperation],
-              msg: 'Operation is allowed only for admin',
+              err: Panic[Panic.Unauthorized],
+              account_id: bob.accountId,
+              role: 'collectible_minter',
+              msg: `Account \`${bob.accountId}\` does not have the \`collectible_minter\` role`,
             }),
           })
         );
@@ -559,7 +562,7 @@ describe('Nft contract', () => {
         const gateId = await generateGateId();
 
         await addTestCollectible(alice, { gate_id: gateId });
-        await alice.contract.claim_token({ gate_id: gateId });
+        await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await expect(alice.contract.delete_collectible({ gate_id: gateId })).rejects.toThrow(
           expect.objectContaining({
@@ -582,9 +585,10 @@ describe('Nft contract', () => {
           expect.objectContaining({
             type: 'GuestPanic',
             panic_msg: JSON.stringify({
-              err: Panic[Panic.NotAuthorized],
+              err: Panic[Panic.NotCollectibleCreator],
+              account_id: bob.accountId,
               gate_id: gateId,
-              msg: `Unable to delete gate ID \`${gateId}\``,
+              msg: `Account \`${bob.accountId}\` is not the creator of gate ID \`${gateId}\``,
             }),
           })
         );
@@ -592,6 +596,55 @@ describe('Nft contract', () => {
     });
   });
 
+  describe('update_collectible_metadata', () => {
+    const newMetadata = {
+      title: 'New title',
+      description: 'New description',
+      media: null,
+      media_hash: null,
+      reference: null,
+      reference_hash: null,
+      extra: '{}',
+    };
+
+    it('updates the metadata if called by creator', async () => {
+      const gateId = await generateGateId();
+
+      await addTestCollectible(alice, { gate_id: gateId });
+      await alice.contract.update_collectible_metadata(
+        { gate_id: gateId, ...newMetadata },
+        MAX_GAS_ALLOWED,
+        STORAGE_DEPOSIT
+      );
+
+      const collectible = await alice.contract.get_collectible_by_gate_id({ gate_id: gateId });
+      expect(collectible?.metadata).toMatchObject(newMetadata);
+      expect(collectible?.metadata.updated_at).not.toBeNull();
+      expect(collectible?.frozen).toBe(false);
+    });
+
+    it('throws if collectible is frozen', async () => {
+      const gateId = await generateGateId();
+
+      await addTestCollectible(alice, { gate_id: gateId });
+      await alice.contract.freeze_collectible_metadata({ gate_id: gateId });
+      expect((await alice.contract.get_collectible_by_gate_id({ gate_id: gateId }))?.frozen).toBe(true);
+
+      await expect(
+        alice.contract.update_collectible_metadata({ gate_id: gateId, ...newMetadata }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+      ).rejects.toThrow(
+        expect.objectContaining({
+          type: 'GuestPanic',
+          panic_msg: JSON.stringify({
+            err: Panic[Panic.CollectibleFrozen],
+            gate_id: gateId,
+            msg: `Metadata of gate ID \`${gateId}\` is frozen`,
+          }),
+        })
+      );
+    });
+  });
+
   describe('claim_token', () => {
     let gateId: string;
     const initialSupply = 1000;
@@ -607,7 +660,7 @@ describe('Nft contract', () => {
 
       initialTokensOfBob = await bob.contract.get_tokens_by_owner({ owner_id: bob.accountId });
 
-      tokenId = await bob.contract.claim_token({ gate_id: gateId });
+      tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       logger.data("Claimed token's id", tokenId);
       logger.data('Claimed claimer', bob.accountId);
@@ -678,7 +731,9 @@ describe('Nft contract', () => {
 
         logger.data('Attempting to claim a token for gate id', nonExistentId);
 
-        await expect(alice.contract.claim_token({ gate_id: nonExistentId })).rejects.toThrow(
+        await expect(
+          alice.contract.claim_token({ gate_id: nonExistentId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        ).rejects.toThrow(
           expect.objectContaining({
             type: 'GuestPanic',
             panic_msg: JSON.stringify({
@@ -700,9 +755,11 @@ describe('Nft contract', () => {
 
         logger.data('Attempting to claim 2 tokens for gate id created with supply of', 1);
 
-        await alice.contract.claim_token({ gate_id: gateIdNoSupply });
+        await alice.contract.claim_token({ gate_id: gateIdNoSupply }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
-        await expect(alice.contract.claim_token({ gate_id: gateIdNoSupply })).rejects.toThrow(
+        await expect(
+          alice.contract.claim_token({ gate_id: gateIdNoSupply }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        ).rejects.toThrow(
           expect.objectContaining({
             type: 'GuestPanic',
             panic_msg: JSON.stringify({
@@ -713,6 +770,28 @@ describe('Nft contract', () => {
           })
         );
       });
+
+      it('throws if mint price is not paid', async () => {
+        const pricedGateId = await generateGateId();
+        const mintPrice = parseNearAmount('1')!;
+
+        await addTestCollectible(alice, { gate_id: pricedGateId });
+        await alice.contract.set_mint_price({ gate_id: pricedGateId, mint_price: mintPrice });
+
+        await expect(
+          bob.contract.claim_token({ gate_id: pricedGateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        ).rejects.toThrow(
+          expect.objectContaining({
+            type: 'GuestPanic',
+            panic_msg: JSON.stringify({
+              err: Panic[Panic.NotEnoughDepositForMintPrice],
+              attached_deposit: STORAGE_DEPOSIT.toString(),
+              mint_price: mintPrice,
+              msg: `Attached deposit \`U128(${STORAGE_DEPOSIT.toString()})\` is not enough to pay the mint price \`U128(${mintPrice})\``,
+            }),
+          })
+        );
+      });
     });
   });
 
@@ -727,7 +806,7 @@ describe('Nft contract', () => {
       gateId = await generateGateId();
       await addTestCollectible(alice, { gate_id: gateId, supply: initialSupply });
 
-      tokenId = await alice.contract.claim_token({ gate_id: gateId });
+      tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       await alice.contract.nft_approve(
         {
@@ -776,7 +855,7 @@ describe('Nft contract', () => {
 
     describe('errors', () => {
       it('throws if the initiator does not own the token', async () => {
-        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await expect(bob.contract.burn_token({ token_id: tokenId2 }, MAX_GAS_ALLOWED)).rejects.toThrow(
           expect.objectContaining({
@@ -824,7 +903,7 @@ describe('Nft contract', () => {
       logger.data('Tokens before', tokensOfAliceBefore.length);
 
       for (let i = 0; i < numberOfTokensToClaim; i += 1) {
-        await alice.contract.claim_token({ gate_id: gateId });
+        await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
       }
 
       tokensOfAliceAfter = await alice.contract.get_tokens_by_owner({ owner_id: alice.accountId });
@@ -885,8 +964,8 @@ describe('Nft contract', () => {
       ]);
 
       for (let i = 0; i < numberOfTokensToClaim; i += 1) {
-        await alice.contract.claim_token({ gate_id: gateId1 });
-        await alice.contract.claim_token({ gate_id: gateId2 });
+        await alice.contract.claim_token({ gate_id: gateId1 }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
+        await alice.contract.claim_token({ gate_id: gateId2 }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
       }
 
       logger.data('Tokens claimed for new collectible', numberOfTokensToClaim);
@@ -947,7 +1026,9 @@ describe('Nft contract', () => {
       await addTestCollectible(alice, { gate_id: gateId });
 
       tokensIds = await Promise.all(
-        Array.from({ length: numberOfTokensToApprove }, () => alice.contract.claim_token({ gate_id: gateId }))
+        Array.from({ length: numberOfTokensToApprove }, () =>
+          alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        )
       );
 
       await alice.contract.batch_approve(
@@ -1003,15 +1084,13 @@ describe('Nft contract', () => {
       let validTokenId2: string;
 
       const nonexistentTokenId = '11111111111';
-      let alreadyApprovedTokenId: string;
       let foreignTokenId: string;
 
       beforeAll(async () => {
-        [alreadyApprovedTokenId] = tokensIds;
-        foreignTokenId = await bob.contract.claim_token({ gate_id: gateId });
+        foreignTokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
-        validTokenId = await alice.contract.claim_token({ gate_id: gateId });
-        validTokenId2 = await alice.contract.claim_token({ gate_id: gateId });
+        validTokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
+        validTokenId2 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         try {
           await alice.contract.batch_approve(
@@ -1019,7 +1098,6 @@ describe('Nft contract', () => {
               tokens: [
                 [validTokenId, randomMinPrice],
                 [nonexistentTokenId, randomMinPrice],
-                [alreadyApprovedTokenId, randomMinPrice],
                 [foreignTokenId, randomMinPrice],
                 [validTokenId2, randomMinPrice],
               ],
@@ -1060,7 +1138,6 @@ describe('Nft contract', () => {
               err: 'Errors',
               panics: [
                 [nonexistentTokenId, { err: Panic[Panic.TokenIdNotFound], token_id: nonexistentTokenId }],
-                [alreadyApprovedTokenId, { err: Panic[Panic.OneApprovalAllowed] }],
                 [
                   foreignTokenId,
                   {
@@ -1070,7 +1147,7 @@ describe('Nft contract', () => {
                   },
                 ],
               ],
-              msg: `3 error(s) detected, see \`panics\` fields for a full list of errors`,
+              msg: `2 error(s) detected, see \`panics\` fields for a full list of errors`,
             }),
           })
         );
@@ -1105,9 +1182,11 @@ describe('Nft contract', () => {
       it('throws if number of tokens to approve exceeds 10', async () => {
         const numberOfTokensToApprove = 11;
 
-        const tokenId = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         const tokensIdsNew = await Promise.all(
-          Array.from({ length: numberOfTokensToApprove - 1 }, () => alice.contract.claim_token({ gate_id: gateId }))
+          Array.from({ length: numberOfTokensToApprove - 1 }, () =>
+            alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+          )
         );
 
         tokensIdsNew.push(tokenId);
@@ -1145,6 +1224,35 @@ describe('Nft contract', () => {
     });
   });
 
+  describe('get_config', () => {
+    it("returns contract's royalty and fee settings", async () => {
+      const config = await alice.contract.get_config();
+      logger.data('Config returned', config);
+
+      expect(config).toEqual({
+        ...royaltySetting,
+        mintgate_fee: MINTGATE_FEE,
+        mintgate_fee_account_id: mintgate.accountId,
+      });
+    });
+
+    it('throws if fee is updated by not admin', async () => {
+      await expect(
+        alice.contract.set_mintgate_fee({ mintgate_fee: MINTGATE_FEE, mintgate_fee_account_id: alice.accountId })
+      ).rejects.toThrow(
+        expect.objectContaining({
+          type: 'GuestPanic',
+          panic_msg: JSON.stringify({
+            err: Panic[Panic.Unauthorized],
+            account_id: alice.accountId,
+            role: 'admin',
+            msg: `Account \`${alice.accountId}\` does not have the \`admin\` role`,
+          }),
+        })
+      );
+    });
+  });
+
   describe('nft_transfer', () => {
     let gateId: string;
 
@@ -1169,7 +1277,7 @@ describe('Nft contract', () => {
       let token: Token | null;
 
       beforeAll(async () => {
-        bobsTokenId = await bob.contract.claim_token({ gate_id: gateId });
+        bobsTokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         initialTokensOfAlice = await alice.contract.get_tokens_by_owner({ owner_id: alice.accountId });
         logger.data('New owner initially had tokens', initialTokensOfAlice.length);
@@ -1224,7 +1332,7 @@ describe('Nft contract', () => {
       });
 
       it("doesn't throw if sender is approved by owner", async () => {
-        const tokenId = await bob.contract.claim_token({ gate_id: gateId });
+        const tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         logger.data("Token's owner is", bob.accountId);
 
         await bob.contract.nft_approve(
@@ -1250,7 +1358,7 @@ describe('Nft contract', () => {
       });
 
       it('clears approvals', async () => {
-        const tokenId = await bob.contract.claim_token({ gate_id: gateId });
+        const tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
         logger.data("Token's owner is", bob.accountId);
 
         await bob.contract.nft_approve(
@@ -1290,7 +1398,7 @@ describe('Nft contract', () => {
       let token: Token | null;
 
       beforeAll(async () => {
-        alicesTokenId = await alice.contract.claim_token({ gate_id: gateId });
+        alicesTokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         token = await alice.contract.nft_token({ token_id: alicesTokenId });
       });
@@ -1394,24 +1502,25 @@ describe('Nft contract', () => {
       let payout: Payout;
 
       beforeAll(async () => {
-        const tokenId = await alice.contract.claim_token({ gate_id: gateId });
+        const tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         payout = await alice.contract.nft_payout({
           token_id: tokenId,
           balance: priceInternalNear!,
+          max_len_payout: 10,
         });
       });
 
       it("correctly calculates mintgate's share", () => {
-        expect(+formatNearAmount(payout[mintgate.accountId])).toBe(mintgateShare);
+        expect(+formatNearAmount(payout.payout[mintgate.accountId])).toBe(mintgateShare);
       });
 
       it("correctly calculates creator's share", () => {
-        expect(+formatNearAmount(payout[bob.accountId])).toBe(creatorShare);
+        expect(+formatNearAmount(payout.payout[bob.accountId])).toBe(creatorShare);
       });
 
       it("correctly calculates seller's share", () => {
-        expect(+formatNearAmount(payout[alice.accountId])).toBe(sellerShare);
+        expect(+formatNearAmount(payout.payout[alice.accountId])).toBe(sellerShare);
       });
     });
 
@@ -1419,20 +1528,56 @@ describe('Nft contract', () => {
       let payout: Payout;
 
       beforeAll(async () => {
-        const tokenId = await bob.contract.claim_token({ gate_id: gateId });
+        const tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         payout = await bob.contract.nft_payout({
           token_id: tokenId,
           balance: priceInternalNear!,
+          max_len_payout: 10,
         });
       });
 
       it("correctly calculates mintgate's share", () => {
-        expect(+formatNearAmount(payout[mintgate.accountId])).toBe(mintgateShare);
+        expect(+formatNearAmount(payout.payout[mintgate.accountId])).toBe(mintgateShare);
       });
 
       it("correctly calculates seller's (=== creator's) share", () => {
-        expect(+formatNearAmount(payout[bob.accountId])).toBe(sellerShare + creatorShare);
+        expect(+formatNearAmount(payout.payout[bob.accountId])).toBe(sellerShare + creatorShare);
+      });
+    });
+
+    describe('collectible has a royalty split', () => {
+      const share: Fraction = {
+        num: 1,
+        den: 10,
+      };
+
+      let payout: Payout;
+
+      beforeAll(async () => {
+        const splitGateId = await generateGateId();
+        await addTestCollectible(bob, {
+          gate_id: splitGateId,
+          royalty: { num: 1, den: 10 },
+          royalty_split: [{ account_id: merchant.accountId, share }],
+        });
+        const tokenId = await alice.contract.claim_token({ gate_id: splitGateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
+
+        payout = await alice.contract.nft_payout({
+          token_id: tokenId,
+          balance: priceInternalNear!,
+          max_len_payout: 10,
+        });
+      });
+
+      it("correctly calculates collaborator's share", () => {
+        expect(+formatNearAmount(payout.payout[merchant.accountId])).toBe(getShare(+priceHrNear, share));
+      });
+
+      it("correctly calculates seller's share", () => {
+        expect(+formatNearAmount(payout.payout[alice.accountId])).toBe(
+          +priceHrNear - mintgateShare - 2 * getShare(+priceHrNear, share)
+        );
       });
     });
 
@@ -1444,9 +1589,31 @@ describe('Nft contract', () => {
           alice.contract.nft_payout({
             token_id: nonExistentTokenId,
             balance: parseNearAmount('5')!,
+            max_len_payout: 10,
           })
         ).rejects.toThrow('TokenIdNotFound');
       });
+
+      it('throws if payout is longer than max_len_payout', async () => {
+        const tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
+
+        await expect(
+          alice.contract.nft_payout({
+            token_id: tokenId,
+            balance: priceInternalNear!,
+            max_len_payout: 2,
+          })
+        ).rejects.toThrow(
+          expect.objectContaining({
+            type: 'GuestPanic',
+            panic_msg: JSON.stringify({
+              err: Panic[Panic.PayoutTooLong],
+              max_len_payout: 2,
+              msg: 'Payout exceeds `max_len_payout` of `2` accounts',
+            }),
+          })
+        );
+      });
     });
   });
 
@@ -1470,7 +1637,8 @@ describe('Nft contract', () => {
       receiver_id: merchant.accountId,
       approval_id: null,
       memo: null,
-      balance: priceInternalNear,
+      balance: priceInternalNear!,
+      max_len_payout: 10,
     };
 
     beforeAll(async () => {
@@ -1482,7 +1650,7 @@ describe('Nft contract', () => {
     });
 
     it('returns the correct payout if receiver is not creator', async () => {
-      tokenId = await bob.contract.claim_token({ gate_id: gateId });
+      tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       const payoutReceived = await bob.contract.nft_transfer_payout({
         ...args,
@@ -1490,14 +1658,16 @@ describe('Nft contract', () => {
       });
 
       expect(payoutReceived).toEqual({
-        [mintgate.accountId]: mintgateShare,
-        [bob.accountId]: senderShare,
-        [alice.accountId]: creatorShare,
+        payout: {
+          [mintgate.accountId]: mintgateShare,
+          [bob.accountId]: senderShare,
+          [alice.accountId]: creatorShare,
+        },
       });
     });
 
     it('returns the correct payout if receiver is creator', async () => {
-      tokenId = await alice.contract.claim_token({ gate_id: gateId });
+      tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       const payoutReceived = await alice.contract.nft_transfer_payout({
         ...args,
@@ -1505,8 +1675,10 @@ describe('Nft contract', () => {
       });
 
       expect(payoutReceived).toEqual({
-        [mintgate.accountId]: mintgateShare,
-        [alice.accountId]: new BN(creatorShare).add(new BN(senderShare)).toString(),
+        payout: {
+          [mintgate.accountId]: mintgateShare,
+          [alice.accountId]: new BN(creatorShare).add(new BN(senderShare)).toString(),
+        },
       });
     });
 
@@ -1514,7 +1686,7 @@ describe('Nft contract', () => {
       let token: Token;
 
       beforeAll(async () => {
-        tokenId = await alice.contract.claim_token({ gate_id: gateId });
+        tokenId = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await alice.contract.nft_transfer_payout({
           ...args,
@@ -1555,7 +1727,7 @@ describe('Nft contract', () => {
         gate_id: gateId,
       });
 
-      const tokenId = await bob.contract.claim_token({ gate_id: gateId });
+      const tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
       logger.data('Claimed token with id', tokenId);
 
       const tokensOfBob = await bob.contract.get_tokens_by_owner({ owner_id: bob.accountId });
@@ -1590,7 +1762,7 @@ describe('Nft contract', () => {
       gateId = await generateGateId();
       await addTestCollectible(alice, { gate_id: gateId });
 
-      tokenId = await bob.contract.claim_token({ gate_id: gateId });
+      tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       token = await bob.contract.nft_token({ token_id: tokenId });
       logger.data('Token before approval', token);
@@ -1691,7 +1863,7 @@ describe('Nft contract', () => {
         logger.data('Attempting to approve token, approver', alice.accountId);
         logger.data('Attempting to approve token, owner', bob.accountId);
 
-        const tokenId2 = await bob.contract.claim_token({ gate_id: gateId });
+        const tokenId2 = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
         await expect(
           alice.contract.nft_approve(
@@ -1715,36 +1887,35 @@ describe('Nft contract', () => {
         );
       });
 
-      it('throws for already approved token ', async () => {
-        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId });
+      it('allows to approve a token in more than one market', async () => {
+        const tokenId2 = await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
-        await alice.contract.nft_approve(
-          {
-            token_id: tokenId2,
-            account_id: merchant.contract.contractId,
-            msg: JSON.stringify(message),
-          },
-          MAX_GAS_ALLOWED
+        await Promise.all(
+          [merchant, merchant2].map((market) =>
+            alice.contract.nft_approve(
+              {
+                token_id: tokenId2,
+                account_id: market.contract.contractId,
+                msg: JSON.stringify(message),
+              },
+              MAX_GAS_ALLOWED
+            )
+          )
         );
 
         const token2 = await alice.contract.nft_token({ token_id: tokenId2 });
-        logger.data('Attempting to approve token with approvals:', token2!.approvals);
+        logger.data('Approvals of token approved in two markets:', token2!.approvals);
 
-        await expect(
-          alice.contract.nft_approve({
+        expect(Object.keys(token2!.approvals).sort()).toEqual(
+          [merchant.contract.contractId, merchant2.contract.contractId].sort()
+        );
+        expect(
+          await alice.contract.nft_is_approved({
             token_id: tokenId2,
-            account_id: merchant.contract.contractId,
-            msg: JSON.stringify(message),
+            approved_account_id: merchant2.contract.contractId,
+            approval_id: token2!.approvals[merchant2.contract.contractId].approval_id,
           })
-        ).rejects.toThrow(
-          expect.objectContaining({
-            type: 'GuestPanic',
-            panic_msg: JSON.stringify({
-              err: Panic[Panic.OneApprovalAllowed],
-              msg: 'At most one approval is allowed per Token',
-            }),
-          })
-        );
+        ).toBe(true);
       });
 
       it('throws for nonexistent `token_id`', async () => {
@@ -1779,7 +1950,7 @@ describe('Nft contract', () => {
       gateId = await generateGateId();
       await addTestCollectible(alice, { gate_id: gateId });
 
-      tokenId = await bob.contract.claim_token({ gate_id: gateId });
+      tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       const msg: NftApproveMsg = {
         min_price: '5',
@@ -1855,7 +2026,7 @@ describe('Nft contract', () => {
     });
 
     it('throw if token is not approved for market', async () => {
-      const tokenId2 = await bob.contract.claim_token({ gate_id: gateId });
+      const tokenId2 = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
       const token2 = await bob.contract.nft_token({ token_id: tokenId2 });
 
       logger.data("Attempting to revoke token, token's approvals", token2!.approvals);
@@ -1908,7 +2079,7 @@ describe('Nft contract', () => {
       gateId = await generateGateId();
       await addTestCollectible(alice, { gate_id: gateId });
 
-      tokenId = await bob.contract.claim_token({ gate_id: gateId });
+      tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       await bob.contract.nft_approve(
         {
@@ -2050,9 +2221,9 @@ describe('Nft contract', () => {
 
       for (let i = 0; i < numberOfTokensToAdd; i += 1) {
         if (i % 2) {
-          alicesTokens.push(await alice.contract.claim_token({ gate_id: gateId }));
+          alicesTokens.push(await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT));
         } else {
-          bobsTokens.push(await bob.contract.claim_token({ gate_id: gateId }));
+          bobsTokens.push(await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT));
         }
       }
 
@@ -2105,10 +2276,12 @@ describe('Nft contract', () => {
       tokensBefore = await bob.contract.nft_tokens({ from_index: null, limit: null });
       logger.data('Tokens before', tokensBefore.length);
 
-      const firstTokenId = await bob.contract.claim_token({ gate_id: gateId });
+      const firstTokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       newTokensIds = await Promise.all(
-        Array.from({ length: numberOfTokensToClaim - 1 }, async () => bob.contract.claim_token({ gate_id: gateId }))
+        Array.from({ length: numberOfTokensToClaim - 1 }, async () =>
+          bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        )
       );
       newTokensIds.push(firstTokenId);
 
@@ -2177,10 +2350,12 @@ describe('Nft contract', () => {
       tokensAmtOwnedBefore = await bob.contract.nft_supply_for_owner({ account_id: alice.accountId });
       logger.data('Tokens owned by alice before', tokensAmtOwnedBefore);
 
-      await alice.contract.claim_token({ gate_id: gateId });
+      await alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       await Promise.all(
-        Array.from({ length: numberOfTokensToClaim - 1 }, async () => alice.contract.claim_token({ gate_id: gateId }))
+        Array.from({ length: numberOfTokensToClaim - 1 }, async () =>
+          alice.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        )
       );
 
       tokensAmtOwnedAfter = await bob.contract.nft_supply_for_owner({ account_id: alice.accountId });
@@ -2221,10 +2396,12 @@ describe('Nft contract', () => {
       });
       logger.data('Tokens before', tokensBefore.length);
 
-      const firstTokenId = await bob.contract.claim_token({ gate_id: gateId });
+      const firstTokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       newTokensIds = await Promise.all(
-        Array.from({ length: numberOfTokensToClaim - 1 }, async () => bob.contract.claim_token({ gate_id: gateId }))
+        Array.from({ length: numberOfTokensToClaim - 1 }, async () =>
+          bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT)
+        )
       );
       newTokensIds.push(firstTokenId);
 
@@ -2299,7 +2476,7 @@ describe('Nft contract', () => {
 
       await addTestCollectible(bob, { gate_id: gateId });
 
-      const tokenId = await bob.contract.claim_token({ gate_id: gateId });
+      const tokenId = await bob.contract.claim_token({ gate_id: gateId }, MAX_GAS_ALLOWED, STORAGE_DEPOSIT);
 
       expect(await bob.contract.nft_token_uri({ token_id: tokenId })).toBe(
         `${contractMetadata.base_uri}${contractMetadata.base_uri!.endsWith('/') ? '' : '/'}${gateId}`
@@ -36,7 +36,7 @@ export default class LocalTestEnvironment extends NodeEnvironment {
     const marketUsers = await getUsers(prefixes.market.users);
 
     const nftContractArguments: NftContract['init'] = {
-      admin_id: adminUser.accountId,
+      owner_id: adminUser.accountId,
       metadata: contractMetadata,
       mintgate_fee: MINTGATE_FEE,
       mintgate_fee_account_id: nftFeeUser.accountId,
@@ -57,7 +57,7 @@ export default class LocalTestEnvironment extends NodeEnvironment {
       'target/wasm32-unknown-unknown/release/mg_market.wasm',
       {
         func: 'init',
-        args: {},
+        args: { owner_id: adminUser.accountId },
       }
     );
 
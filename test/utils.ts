@@ -5,7 +5,7 @@ import { customAlphabet } from 'nanoid/async';
 import chalk from 'chalk';
 
 import type { Account } from 'near-api-js';
-import type { AccountContract, Fraction, NftContract } from '../src';
+import type { AccountContract, Fraction, NftContract, RoyaltyShare } from '../src';
 
 const gateIdNanoid = customAlphabet('0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ', 12);
 
@@ -38,6 +38,10 @@ export const createAddTestCollectible = (admin: Account) => async (
     media_hash?: string;
     reference?: string;
     reference_hash?: string;
+    royalty_split?: RoyaltyShare[];
+    starts_at?: number;
+    claims_end_at?: number;
+    expires_at?: number;
   } = {}
 ): Promise<void> => {
   let { gate_id } = collectibleData;
@@ -46,12 +50,18 @@ export const createAddTestCollectible = (admin: Account) => async (
     gate_id = await generateGateId();
   }
 
-  await admin.functionCall(creator.contract.contractId, 'create_collectible', {
-    creator_id: creator.accountId,
-    ...collectibleDefaultData,
-    ...collectibleData,
-    gate_id,
-  });
+  await admin.functionCall(
+    creator.contract.contractId,
+    'create_collectible',
+    {
+      creator_id: creator.accountId,
+      ...collectibleDefaultData,
+      ...collectibleData,
+      gate_id,
+    },
+    MAX_GAS_ALLOWED,
+    STORAGE_DEPOSIT
+  );
 };
 
 export const formatNsToMs = (timestampNs: number): number =>
@@ -129,3 +139,6 @@ export const getShare = (totalAmount: number, { num, den }: Fraction): number =>
 export const validGateIdRegEx = /^[a-z\d_-]{1,32}$/gi;
 
 export const MAX_GAS_ALLOWED = new BN(300000000000000);
+
+// Deposit attached to cover storage when creating collectibles and claiming tokens, the excess is refunded.
+export const STORAGE_DEPOSIT = new BN('100000000000000000000000');